@@ -1,7 +1,10 @@
+use crate::component::preview_component::Preview;
 use crate::component::AppComponent;
+use crate::compression::Codec;
+use crate::session::Session;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use strum::Display;
+use strum::{Display, VariantNames};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
 pub type ActionSender = UnboundedSender<Action>;
@@ -49,7 +52,7 @@ impl SelectorType {
 /// also to the application itself for deciding if the ui should be rerendered or not.
 ///
 /// Generally, if the state of the component has been updated, it should rerender the terminal.
-#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, Display)]
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, Display, VariantNames)]
 pub enum Action {
     Character(char),
     PasteText(String),
@@ -77,6 +80,7 @@ pub enum Action {
     NewLine,
     ToggleHelp,
     Return,
+    ConfirmReturn,
     Undo,
     Redo,
     Quit,
@@ -90,10 +94,37 @@ pub enum Action {
     StartOfWord,
     TogglePreview,
     ReloadPreview,
+    ScrollPreviewUp,
+    ScrollPreviewDown,
+    FindFiles,
+    NewFolder,
+    Rename,
+    Duplicate,
+    Move,
+    Trash,
+    ConfirmFileOperation,
+    CycleSort,
+    ToggleHidden,
+    ToggleColumns,
+    Places,
+    Bookmark,
+    Mark,
+    Extract,
     Resize(u16, u16),
     SaveTo,
+    SaveUncompressed,
+    NextBuffer,
+    PreviousBuffer,
+    CycleRebaseVerb,
+    Follow,
     NewFile,
     FileHistory,
+    Pin,
+    Remove,
+    ClearHistory,
+    ConfirmClearHistory,
+    QuickSwitch,
+    Sessions,
     Config,
 }
 
@@ -106,15 +137,33 @@ pub enum Action {
 /// This is separate from `Action` because they should not be able to be set to a specific keybind
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum AsyncAction {
-    LoadFileContents(String),
-    PreviewContents(Option<String>),
+    /// Contents read from the file at the given path, to be loaded in its buffer, and the codec
+    /// they were decompressed with
+    LoadFileContents(PathBuf, String, Option<Codec>),
+    /// Text appended to the file at the given path, and the file length it was read up to
+    AppendFileContents(PathBuf, u64, String),
+    PreviewContents(Option<Preview>),
     SavedFile(SaveFileResult),
     /// Navigate to a component representing `AppComponent`, or return from the current one if its `None`
     Navigate(Option<AppComponent>),
     SelectPath(PathBuf, SelectorType),
+    /// Files picked together to be opened
+    SelectPaths(Vec<PathBuf>),
     Error(String),
+    /// Error reading the file at the given path
+    FileError(PathBuf, String),
     StartAnimation,
     StopAnimation,
+    /// Text to be written to stdout once the application exits
+    WriteStdout(String),
+    /// Exit status of the application once it quits
+    ExitCode(u8),
+    /// Files found under the directory
+    FoundFiles(PathBuf, Vec<PathBuf>),
+    /// State of the editor when leaving it, saved as a session once the application quits
+    SessionState(Session),
+    /// The config file or a theme changed on disk
+    ConfigChanged,
 }
 
 impl Action {
@@ -124,6 +173,24 @@ impl Action {
             Action::Up | Action::Down | Action::Left | Action::Right
         )
     }
+    /// Whether the action changes the text of a buffer
+    pub fn is_editing_action(&self) -> bool {
+        matches!(
+            self,
+            Action::Character(_)
+                | Action::PasteText(_)
+                | Action::Paste
+                | Action::Cut
+                | Action::Insert
+                | Action::Backspace
+                | Action::Delete
+                | Action::NewLine
+                | Action::Tab
+                | Action::Undo
+                | Action::Redo
+                | Action::CycleRebaseVerb
+        )
+    }
 }
 
 #[derive(Clone, Debug, Copy, PartialEq, Eq)]
@@ -2,15 +2,27 @@ use crate::action::{
     Action, ActionReceiver, ActionResult, ActionSender, AsyncAction, AsyncActionReceiver,
     AsyncActionSender,
 };
+use crate::component::editor::options::EditorOptions;
 use crate::component::navigator::NavigatorComponent;
 use crate::component::{AppComponent, Component};
-use crate::config::Config;
+use crate::config::watcher::ConfigWatcher;
+use crate::config::{get_config_dir, Config};
 use crate::event::Event;
+use crate::session::{BufferSession, Session};
 use crate::tui::Tui;
 use color_eyre::Result;
 use crossterm::event::{KeyEvent, MouseEvent};
+use std::env::current_dir;
+use std::io::{stdout, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
+/// How long the config must stay unchanged before it is reloaded, since saving a file usually
+/// changes it several times in a row
+const CONFIG_RELOAD_DELAY: Duration = Duration::from_millis(200);
+
 pub struct App {
     config: Config,
     tui: Tui,
@@ -21,31 +33,85 @@ pub struct App {
     async_action_receiver: AsyncActionReceiver,
     component: NavigatorComponent,
     should_rerender: bool,
-    running_animation: bool,
+    /// Number of effect runners currently animating
+    running_animations: usize,
+    /// Text written to stdout after leaving the terminal interface
+    stdout_output: Option<String>,
+    exit_code: ExitCode,
+    /// Name the session is saved as when quitting
+    session_name: Option<String>,
+    /// Editor state received when leaving it
+    session: Option<Session>,
+    /// Whether quitting replaces the last session of the working directory, which pager and
+    /// stdout runs leave alone
+    save_last_session: bool,
+    /// Reloads the config when it changes, if the config directory can be watched
+    _config_watcher: Option<ConfigWatcher>,
+    /// Why the config directory cannot be watched, shown once the application starts
+    config_watcher_error: Option<String>,
+    /// When the config last changed, if it has not been reloaded since
+    config_changed_at: Option<Instant>,
 }
 
 impl App {
-    pub fn new() -> Result<Self> {
-        let comp = NavigatorComponent::new();
-        Self::create(comp)
+    /// Opens the files directly in the Editor component, or in the home component
+    /// if there is nothing to edit.
+    ///
+    /// A directory is opened in the file selector instead of being read as a file.
+    ///
+    /// If a session name is given, that session is restored and saved again when quitting.
+    /// Otherwise, the last session of the working directory is restored if there is nothing
+    /// to open and the config asks for it.
+    pub fn new_in_editor(options: EditorOptions, session_name: Option<String>) -> Result<Self> {
+        let config = Config::new()?;
+        let data_dir = &config.config.data_dir;
+        let save_last_session = !options.pager && !options.stdout;
+        let nothing_to_open = options.files.is_empty()
+            && options.directory.is_none()
+            && options.stdin.is_none()
+            && !options.stdout;
+        let session = match &session_name {
+            Some(name) => Session::load(data_dir, name)?,
+            None if nothing_to_open && config.config.restore_session => {
+                Session::load_last(data_dir, &current_dir()?)
+            }
+            None => None,
+        };
+        let comp = match session {
+            Some(mut session) => {
+                let files = options.files.into_iter().map(PathBuf::from);
+                session.buffers.extend(files.map(BufferSession::new));
+                AppComponent::RestoredSession(Box::new(session))
+            }
+            None if nothing_to_open => AppComponent::HomeScreen,
+            None => Self::opened_files_component(options),
+        };
+        let comp = NavigatorComponent::new_with_starting_component(comp);
+        let mut app = Self::create(config, comp)?;
+        app.session_name = session_name;
+        app.save_last_session = save_last_session;
+        Ok(app)
     }
 
-    /// Opens the file directly in the Editor component, or in the home component
-    /// if `file_path` is `None`.
-    pub fn new_in_editor(file_path: Option<String>) -> Result<Self> {
-        let Some(file_path) = file_path else {
-            return Self::new();
-        };
-        let editor = AppComponent::OpenedEditor(file_path);
-        let comp = NavigatorComponent::new_with_starting_component(editor);
-        Self::create(comp)
+    fn opened_files_component(options: EditorOptions) -> AppComponent {
+        match options.directory {
+            Some(directory) if options.files.is_empty() && options.stdin.is_none() => {
+                AppComponent::OpenedDirectory(directory)
+            }
+            _ => AppComponent::OpenedFiles(options),
+        }
     }
 
-    fn create(app_component: NavigatorComponent) -> Result<Self> {
+    fn create(config: Config, app_component: NavigatorComponent) -> Result<Self> {
         let (action_tx, action_rx) = mpsc::unbounded_channel::<Action>();
         let (async_action_tx, async_action_rx) = mpsc::unbounded_channel::<AsyncAction>();
+        let (config_watcher, config_watcher_error) =
+            match ConfigWatcher::new(&get_config_dir(), async_action_tx.clone()) {
+                Ok(watcher) => (Some(watcher), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
         Ok(Self {
-            config: Config::new()?,
+            config,
             tui: Tui::new()?,
             should_quit: false,
             action_sender: action_tx,
@@ -54,11 +120,23 @@ impl App {
             async_action_sender: async_action_tx,
             component: app_component,
             should_rerender: true,
-            running_animation: false,
+            running_animations: 0,
+            stdout_output: None,
+            exit_code: ExitCode::SUCCESS,
+            session_name: None,
+            session: None,
+            save_last_session: true,
+            _config_watcher: config_watcher,
+            config_watcher_error,
+            config_changed_at: None,
         })
     }
 
-    pub async fn run(&mut self) -> Result<()> {
+    /// Runs the application until it quits, returning its exit status.
+    ///
+    /// The status is a failure if the user quit while the editor had unsaved changes, which
+    /// lets tools using texti as their editor, like git, know the edit was aborted.
+    pub async fn run(&mut self) -> Result<ExitCode> {
         self.tui.enter()?;
         self.component
             .register_config(&self.config, &AppComponent::HomeScreen);
@@ -67,6 +145,11 @@ impl App {
         self.component
             .register_async_action_sender(self.async_action_sender.clone());
         self.component.init();
+        if let Some(e) = self.config_watcher_error.take() {
+            let message = format!("Config changes need a restart, can't watch them: {e}");
+            self.component.notify_error(message);
+        }
+        self.notify_theme_error();
         loop {
             self.handle_event().await?;
             self.handle_action()?;
@@ -75,9 +158,22 @@ impl App {
                 break;
             }
         }
+        self.session = None;
         self.component.exit();
+        self.handle_async_action()?;
         self.tui.exit()?;
-        Ok(())
+        if let Err(e) = self.save_session() {
+            eprintln!("Unable to save the session: {e}");
+        }
+        if let Some(output) = self.stdout_output.take() {
+            let mut stdout = stdout();
+            stdout.write_all(output.as_bytes())?;
+            if !output.is_empty() && !output.ends_with('\n') {
+                stdout.write_all(b"\n")?;
+            }
+            stdout.flush()?;
+        }
+        Ok(self.exit_code)
     }
 
     async fn handle_event(&mut self) -> Result<()> {
@@ -117,21 +213,91 @@ impl App {
         while let Ok(action) = self.async_action_receiver.try_recv() {
             let res = match action {
                 AsyncAction::StartAnimation => {
-                    self.running_animation = true;
+                    self.running_animations += 1;
                     continue;
                 }
                 AsyncAction::StopAnimation => {
-                    self.running_animation = false;
+                    self.running_animations = self.running_animations.saturating_sub(1);
                     self.should_rerender = true;
                     continue;
                 }
+                AsyncAction::WriteStdout(output) => {
+                    self.stdout_output = Some(output);
+                    continue;
+                }
+                AsyncAction::ExitCode(exit_code) => {
+                    self.exit_code = ExitCode::from(exit_code);
+                    continue;
+                }
+                AsyncAction::SessionState(session) => {
+                    self.session = Some(session);
+                    continue;
+                }
+                AsyncAction::ConfigChanged => {
+                    self.config_changed_at = Some(Instant::now());
+                    continue;
+                }
                 _ => self.component.handle_async_action(&action),
             };
             self.flag_for_rerender_if_asked(res)
         }
+        let settled = |at: &Instant| at.elapsed() >= CONFIG_RELOAD_DELAY;
+        if self.config_changed_at.take_if(|at| settled(at)).is_some() {
+            self.reload_config();
+        }
+        Ok(())
+    }
+    /// Reads the config again and registers it down the component tree, or keeps the current
+    /// one and shows why if the new one cannot be read.
+    fn reload_config(&mut self) {
+        match Config::new() {
+            Ok(config) => {
+                self.config = config;
+                let current_component = self.component.current_component.clone();
+                self.component
+                    .register_config(&self.config, &current_component);
+                self.notify_theme_error();
+            }
+            Err(e) => self
+                .component
+                .notify_error(format!("The config was not reloaded: {e}")),
+        }
+        self.should_rerender = true;
+    }
+    /// Tells why the default theme is used instead of the configured one, if it is.
+    fn notify_theme_error(&mut self) {
+        if let Some(e) = &self.config.theme_error {
+            let message = format!("{e}, the default theme is used");
+            self.component.notify_error(message);
+        }
+    }
+    /// Saves the editor the application quit in, under its session name if it has one, and as
+    /// the last session of the working directory unless told not to.
+    ///
+    /// Nothing is saved without any file open, so quitting from the home screen or an empty
+    /// editor keeps the sessions as they were.
+    fn save_session(&mut self) -> Result<()> {
+        let Some(session) = self.session.take() else {
+            return Ok(());
+        };
+        if session.buffers.is_empty() {
+            return Ok(());
+        }
+        let data_dir = &self.config.config.data_dir;
+        if let Some(name) = session.name.as_ref().or(self.session_name.as_ref()) {
+            session.save(data_dir, name)?;
+        }
+        if self.save_last_session {
+            session.save_last(data_dir, &current_dir()?)?;
+        }
         Ok(())
     }
     fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<()> {
+        let result = self.component.handle_key_event(key_event);
+        self.flag_for_rerender_if_asked(result);
+        if result.is_consumed() {
+            return Ok(());
+        }
         let comp = self
             .component
             .override_keybind_id(key_event)
@@ -157,7 +323,7 @@ impl App {
         };
     }
     fn render(&mut self) -> Result<()> {
-        if self.should_rerender || self.running_animation {
+        if self.should_rerender || self.running_animations > 0 {
             self.tui
                 .terminal
                 .draw(|frame| self.component.render(frame, frame.area()))?;
@@ -0,0 +1,389 @@
+use crate::compression::{read_at_most, MAX_DECOMPRESSED_SIZE};
+use crate::util::DateTime;
+use flate2::read::GzDecoder;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use zip::ZipArchive;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveKind {
+    fn of(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveKind::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else {
+            None
+        }
+    }
+}
+
+/// A file or folder stored in an archive.
+#[derive(Clone, Debug)]
+pub struct ArchiveEntry {
+    /// Path of the entry relative to the root of the archive
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// Permission bits, like `0o644`
+    pub permissions: u32,
+}
+
+impl ArchiveEntry {
+    fn folder(path: PathBuf) -> Self {
+        Self {
+            path,
+            is_dir: true,
+            size: 0,
+            modified: None,
+            permissions: 0o755,
+        }
+    }
+}
+
+/// Whether the file name is the one of a supported archive: `.zip`, `.tar` or `.tar.gz`.
+pub fn is_archive(path: &Path) -> bool {
+    ArchiveKind::of(path).is_some()
+}
+
+/// Splits a path that goes through an archive, like `/tmp/files.zip/docs/readme.md`, into
+/// the path of the archive and the path of the entry inside of it, which is empty for the root
+/// of the archive.
+pub fn split_archive_path(path: &Path) -> Option<(&Path, &Path)> {
+    let archive = path
+        .ancestors()
+        .find(|ancestor| is_archive(ancestor) && ancestor.is_file())?;
+    Some((archive, path.strip_prefix(archive).ok()?))
+}
+
+/// Every file and folder of an archive, read once so its folders can be browsed without
+/// reading the whole archive again each time.
+#[derive(Debug)]
+pub struct ArchiveListing {
+    archive: PathBuf,
+    modified: Option<SystemTime>,
+    entries: Vec<ArchiveEntry>,
+}
+
+impl ArchiveListing {
+    pub fn read(archive: &Path) -> io::Result<Self> {
+        let modified = fs::metadata(archive)?.modified().ok();
+        let mut entries = Vec::new();
+        visit_entries(archive, |entry, _| {
+            entries.push(entry);
+            Ok(true)
+        })?;
+        Ok(Self {
+            archive: archive.to_path_buf(),
+            modified,
+            entries,
+        })
+    }
+    /// Whether this is the listing of `archive`, and the archive has not changed since.
+    pub fn is_current(&self, archive: &Path) -> bool {
+        let modified = fs::metadata(archive).ok().and_then(|m| m.modified().ok());
+        self.archive == archive && modified.is_some() && modified == self.modified
+    }
+    /// The file or folder of the archive at `inner`, unless it only shows up in the paths of
+    /// other entries.
+    pub fn entry(&self, inner: &Path) -> Option<&ArchiveEntry> {
+        self.entries.iter().find(|entry| entry.path == inner)
+    }
+    /// The files and folders directly inside the folder `dir` of the archive.
+    ///
+    /// Folders that only show up in the paths of other entries are listed too.
+    pub fn list_dir(&self, dir: &Path) -> Vec<ArchiveEntry> {
+        let mut children = BTreeMap::new();
+        for entry in &self.entries {
+            let Ok(relative) = entry.path.strip_prefix(dir) else {
+                continue;
+            };
+            let mut components = relative.components();
+            let Some(name) = components.next() else {
+                continue;
+            };
+            let name = name.as_os_str().to_os_string();
+            if components.next().is_none() {
+                children.insert(name, entry.clone());
+            } else {
+                let path = dir.join(&name);
+                children
+                    .entry(name)
+                    .or_insert_with(|| ArchiveEntry::folder(path));
+            }
+        }
+        children.into_values().collect()
+    }
+}
+
+/// Reads the contents of the file `inner` of the archive, failing if they are larger than
+/// 256 MiB whatever size the archive claims they have.
+pub fn read_entry(archive: &Path, inner: &Path) -> io::Result<Vec<u8>> {
+    read_entry_with(archive, inner, |reader| {
+        read_at_most(reader, MAX_DECOMPRESSED_SIZE)
+    })
+}
+
+/// Calls `read` with a reader of the contents of the file `inner` of the archive, so that only
+/// the part of it that is needed is read.
+pub fn read_entry_with<T, F>(archive: &Path, inner: &Path, read: F) -> io::Result<T>
+where
+    F: FnOnce(&mut dyn Read) -> io::Result<T>,
+{
+    let mut read = Some(read);
+    let mut result = None;
+    visit_entries(archive, |entry, reader| {
+        if entry.path != inner || entry.is_dir {
+            return Ok(true);
+        }
+        if let Some(read) = read.take() {
+            result = Some(read(reader)?);
+        }
+        Ok(false)
+    })?;
+    result.ok_or_else(|| {
+        let message = format!("{} is not in {}", inner.display(), archive.display());
+        io::Error::new(io::ErrorKind::NotFound, message)
+    })
+}
+
+/// Extracts the file or folder `inner` of the archive into the folder `target`, returning where
+/// it was extracted to. Folders are extracted with everything inside of them.
+///
+/// Existing files are never overwritten.
+pub fn extract(archive: &Path, inner: &Path, target: &Path) -> io::Result<PathBuf> {
+    let Some(name) = inner.file_name() else {
+        let message = "Only entries of an archive can be extracted";
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+    };
+    let parent = inner.parent().unwrap_or(Path::new(""));
+    let mut extracted = false;
+    visit_entries(archive, |entry, reader| {
+        if !entry.path.starts_with(inner) {
+            return Ok(true);
+        }
+        let destination = target.join(entry.path.strip_prefix(parent).unwrap());
+        if entry.is_dir {
+            fs::create_dir_all(&destination)?;
+        } else {
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut file = File::create_new(&destination)?;
+            io::copy(reader, &mut file)?;
+            set_permissions(&file, entry.permissions)?;
+        }
+        extracted = true;
+        Ok(true)
+    })?;
+    if !extracted {
+        let message = format!("{} is not in {}", inner.display(), archive.display());
+        return Err(io::Error::new(io::ErrorKind::NotFound, message));
+    }
+    Ok(target.join(name))
+}
+
+/// Calls `visit` with every file and folder of the archive and a reader of its contents, until
+/// it returns `false`.
+///
+/// Entries whose paths would leave the archive, like `../file`, and entries that are neither
+/// files nor folders are skipped.
+fn visit_entries<F>(archive: &Path, mut visit: F) -> io::Result<()>
+where
+    F: FnMut(ArchiveEntry, &mut dyn Read) -> io::Result<bool>,
+{
+    let Some(kind) = ArchiveKind::of(archive) else {
+        let message = format!("{} is not an archive", archive.display());
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+    };
+    let file = File::open(archive)?;
+    match kind {
+        ArchiveKind::Zip => {
+            let mut zip = ZipArchive::new(file).map_err(io::Error::other)?;
+            for i in 0..zip.len() {
+                let mut file = zip.by_index(i).map_err(io::Error::other)?;
+                let Some(path) = file.enclosed_name().and_then(|p| normalize(&p)) else {
+                    continue;
+                };
+                let entry = ArchiveEntry {
+                    path,
+                    is_dir: file.is_dir(),
+                    size: file.size(),
+                    modified: file.last_modified().and_then(zip_time),
+                    permissions: file.unix_mode().unwrap_or(0o644) & 0o777,
+                };
+                if !visit(entry, &mut file)? {
+                    break;
+                }
+            }
+            Ok(())
+        }
+        ArchiveKind::Tar => visit_tar_entries(tar::Archive::new(file), visit),
+        ArchiveKind::TarGz => visit_tar_entries(tar::Archive::new(GzDecoder::new(file)), visit),
+    }
+}
+
+fn visit_tar_entries<R, F>(mut archive: tar::Archive<R>, mut visit: F) -> io::Result<()>
+where
+    R: Read,
+    F: FnMut(ArchiveEntry, &mut dyn Read) -> io::Result<bool>,
+{
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_type = entry.header().entry_type();
+        let is_dir = entry_type.is_dir();
+        if !is_dir && !entry_type.is_file() {
+            continue;
+        }
+        let Some(path) = normalize(&entry.path()?) else {
+            continue;
+        };
+        let header = entry.header();
+        let modified = header.mtime().ok();
+        let entry_info = ArchiveEntry {
+            path,
+            is_dir,
+            size: header.size()?,
+            modified: modified.map(|mtime| SystemTime::UNIX_EPOCH + Duration::from_secs(mtime)),
+            permissions: header.mode()? & 0o777,
+        };
+        if !visit(entry_info, &mut entry)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// The path without `.` components and trailing slashes, or `None` if it is empty or would
+/// leave the archive.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => normalized.push(name),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!normalized.as_os_str().is_empty()).then_some(normalized)
+}
+
+#[cfg(unix)]
+fn set_permissions(file: &File, permissions: u32) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    file.set_permissions(fs::Permissions::from_mode(permissions))
+}
+
+/// Extracted files keep the default permissions where they are not unix permission bits.
+#[cfg(not(unix))]
+fn set_permissions(_file: &File, _permissions: u32) -> io::Result<()> {
+    Ok(())
+}
+
+/// Converts the time a zip entry was modified at. Zip archives store it without a time zone,
+/// so it is read as UTC, like the times the file selector shows.
+fn zip_time(time: zip::DateTime) -> Option<SystemTime> {
+    let time = DateTime {
+        year: i64::from(time.year()),
+        month: u32::from(time.month()),
+        day: u32::from(time.day()),
+        hour: u32::from(time.hour()),
+        minute: u32::from(time.minute()),
+        second: u32::from(time.second()),
+    };
+    let timestamp = u64::try_from(time.timestamp()).ok()?;
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(timestamp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    #[test]
+    fn tar_gz_folders_are_listed_from_the_paths_of_their_files() {
+        let dir = TempDir::new("archive");
+        let archive = dir.join("files.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            File::create(&archive).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        for (path, contents) in [("./docs/readme.md", "docs"), ("top.txt", "top")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, contents.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let names: Vec<PathBuf> = ArchiveListing::read(&archive)
+            .unwrap()
+            .list_dir(Path::new(""))
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect();
+        assert_eq!(names, vec![PathBuf::from("docs"), PathBuf::from("top.txt")]);
+        let readme = archive.join("docs/readme.md");
+        let (found, inner) = split_archive_path(&readme).unwrap();
+        assert_eq!(found, archive);
+        assert_eq!(read_entry(found, inner).unwrap(), b"docs");
+    }
+
+    #[test]
+    fn entries_claiming_huge_sizes_are_read_as_stored() {
+        let dir = TempDir::new("archive");
+        let archive = dir.join("huge.tar");
+        let mut header = tar::Header::new_gnu();
+        header.set_path("huge.txt").unwrap();
+        header.set_size(1 << 50);
+        header.set_mode(0o644);
+        header.set_cksum();
+        let mut contents = header.as_bytes().to_vec();
+        contents.extend_from_slice(&[b'a'; 512]);
+        fs::write(&archive, contents).unwrap();
+
+        let read = read_entry(&archive, Path::new("huge.txt")).unwrap();
+        assert_eq!(read, [b'a'; 512]);
+    }
+
+    #[test]
+    fn zip_folders_are_extracted_with_their_files() {
+        let dir = TempDir::new("archive");
+        let archive = dir.join("files.zip");
+        let mut writer = ZipWriter::new(File::create(&archive).unwrap());
+        let options = SimpleFileOptions::default();
+        writer.start_file("docs/inner/readme.md", options).unwrap();
+        writer.write_all(b"readme").unwrap();
+        writer.start_file("../escaped.txt", options).unwrap();
+        writer.finish().unwrap();
+
+        let target = dir.join("extracted");
+        let extracted = extract(&archive, Path::new("docs"), &target).unwrap();
+        assert_eq!(extracted, target.join("docs"));
+        let readme = fs::read_to_string(target.join("docs/inner/readme.md")).unwrap();
+        assert_eq!(readme, "readme");
+        assert!(extract(&archive, Path::new("docs"), &target).is_err());
+        let listing = ArchiveListing::read(&archive).unwrap();
+        assert!(listing.list_dir(Path::new("")).len() == 1);
+    }
+}
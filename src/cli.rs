@@ -1,7 +1,73 @@
+use crate::component::editor::options::EditorOptions;
 use clap::Parser;
+use color_eyre::eyre::bail;
+use color_eyre::Result;
+use std::io::{read_to_string, stdin, IsTerminal};
+use std::path::{Path, PathBuf};
+
+const STDIN_FILE: &str = "-";
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 pub struct Cli {
-    pub file_dir: Option<String>,
-}
\ No newline at end of file
+    /// Files to open, each in its own buffer. A directory opens the file selector instead,
+    /// and `-` reads the text from stdin
+    pub files: Vec<String>,
+    /// Write the first buffer to stdout when saving or quitting, instead of to a file
+    #[arg(long)]
+    pub stdout: bool,
+    /// Quit when the last buffer is closed instead of returning to the home screen. texti
+    /// always blocks until it quits, so this is all tools expecting `--wait` need
+    #[arg(long, short)]
+    pub wait: bool,
+    /// Browse the files read-only, like a pager. Reads stdin when no file is given and
+    /// stdin is not a terminal
+    #[arg(long, short = 'R')]
+    pub pager: bool,
+    /// Open the files read-only. They can still be saved elsewhere
+    #[arg(long)]
+    pub readonly: bool,
+    /// Apply the actions and commands of a YAML script to the files and save them, without
+    /// opening the editor. Edited stdin is written to stdout
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["readonly", "pager"])]
+    pub script: Option<PathBuf>,
+    /// Restore the session saved with this name, and save it there when quitting. Files
+    /// given are opened along with the session buffers
+    #[arg(long, value_name = "NAME")]
+    pub session: Option<String>,
+    /// Check the config file for problems, print them along with their line, and exit with a
+    /// failure status if any was found
+    #[arg(long)]
+    pub check_config: bool,
+}
+
+impl Cli {
+    /// Creates the options for opening the editor, reading stdin if it was asked for.
+    ///
+    /// Fails if more than one directory is given, since the file selector opens in only one.
+    pub fn editor_options(self) -> Result<EditorOptions> {
+        let (stdin_files, files): (Vec<String>, Vec<String>) =
+            self.files.into_iter().partition(|f| f == STDIN_FILE);
+        let (directories, files): (Vec<String>, Vec<String>) =
+            files.into_iter().partition(|f| Path::new(f).is_dir());
+        if directories.len() > 1 {
+            let directories = directories.join(", ");
+            bail!("Only one directory can be opened, got {directories}");
+        }
+        let piped = self.pager && files.is_empty() && !stdin().is_terminal();
+        let stdin = if stdin_files.is_empty() && !piped {
+            None
+        } else {
+            Some(read_to_string(stdin())?)
+        };
+        Ok(EditorOptions {
+            files,
+            directory: directories.into_iter().next(),
+            stdin,
+            stdout: self.stdout,
+            quit_on_close: self.wait || self.pager,
+            pager: self.pager,
+            read_only: self.readonly,
+        })
+    }
+}
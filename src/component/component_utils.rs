@@ -1,4 +1,6 @@
 use crate::action::SaveFileResult;
+use crate::compression::Codec;
+use crate::config::theme::Theme;
 use clipboard::{ClipboardContext, ClipboardProvider};
 use ratatui::layout::{Constraint, Flex, Layout, Rect};
 use ratatui::widgets::{Block, BorderType};
@@ -26,11 +28,19 @@ pub(super) fn center(area: Rect) -> Rect {
     center_vertically(area, Constraint::Percentage(50))
 }
 
-pub(super) fn default_block() -> Block<'static> {
-    Block::bordered().border_type(BorderType::Rounded)
+pub(super) fn default_block(theme: &Theme) -> Block<'static> {
+    Block::bordered()
+        .border_type(BorderType::Rounded)
+        .border_style(theme.border)
 }
 
-pub(super) async fn write_file(path: PathBuf, lines: String, overwrite: bool) -> SaveFileResult {
+/// Writes the lines to the file, compressed with `codec` if there is one.
+pub(super) async fn write_file(
+    path: PathBuf,
+    lines: String,
+    overwrite: bool,
+    codec: Option<Codec>,
+) -> SaveFileResult {
     let exists = path.exists();
     if exists {
         if !overwrite {
@@ -45,6 +55,11 @@ pub(super) async fn write_file(path: PathBuf, lines: String, overwrite: bool) ->
     if path.is_dir() {
         return SaveFileResult::MissingName;
     }
+    let contents = match codec.map(|codec| codec.compress(lines.as_bytes())) {
+        Some(Ok(compressed)) => compressed,
+        Some(Err(e)) => return SaveFileResult::Error(e.to_string()),
+        None => lines.into_bytes(),
+    };
     let mut file = match File::create(&path).await {
         Ok(file) => file,
         Err(e) => {
@@ -52,7 +67,7 @@ pub(super) async fn write_file(path: PathBuf, lines: String, overwrite: bool) ->
             return result;
         }
     };
-    let result = if let Err(e) = file.write_all(lines.as_ref()).await {
+    let result = if let Err(e) = file.write_all(&contents).await {
         SaveFileResult::Error(e.to_string())
     } else {
         SaveFileResult::Saved(path)
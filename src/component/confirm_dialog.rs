@@ -2,12 +2,14 @@ use crate::action::{Action, ActionResult, ActionSender, AsyncActionSender};
 use crate::component::component_utils::{center, default_block, key_label_format};
 use crate::component::effect_runner::EffectRunner;
 use crate::component::{AppComponent, Component};
-use crate::config::effects::show_notification_effect;
+use crate::config::effects::{show_notification_effect, AnimationEvent};
 use crate::config::keybindings::key_event_to_string;
+use crate::config::theme::Theme;
 use crate::config::Config;
 use crossterm::event::KeyEvent;
 use ratatui::layout::Rect;
 use ratatui::text::{Line, Text};
+use ratatui::widgets::Clear;
 use ratatui::Frame;
 
 #[derive(Default)]
@@ -19,6 +21,7 @@ pub struct ConfirmDialogComponent {
     effect_runner: EffectRunner,
     cancel_key: String,
     confirm_key: String,
+    theme: Theme,
 }
 
 impl ConfirmDialogComponent {
@@ -26,7 +29,8 @@ impl ConfirmDialogComponent {
         self.title = title.to_string();
         self.message = message.to_string();
         self.action_on_confirm = Some(action_on_confirm);
-        self.effect_runner.add_effect(show_notification_effect())
+        self.effect_runner
+            .add_effect(AnimationEvent::Notification, show_notification_effect());
     }
     pub fn visible(&self) -> bool {
         self.action_on_confirm.is_some()
@@ -35,7 +39,7 @@ impl ConfirmDialogComponent {
 
 impl Component for ConfirmDialogComponent {
     fn register_config(&mut self, config: &Config, app_component: &AppComponent) {
-        let _ = app_component;
+        self.effect_runner.register_config(config, app_component);
         let confirm_key = config
             .keybindings
             .get_key_event_of_action(&AppComponent::Dialog, Action::Confirm);
@@ -44,6 +48,7 @@ impl Component for ConfirmDialogComponent {
             .keybindings
             .get_key_event_of_action(&AppComponent::Dialog, Action::Cancel);
         self.cancel_key = cancel_key.map(key_event_to_string).unwrap_or_default();
+        self.theme = config.theme.clone();
     }
     fn register_action_sender(&mut self, sender: ActionSender) {
         self.action_sender = Some(sender);
@@ -80,12 +85,13 @@ impl Component for ConfirmDialogComponent {
     fn render(&mut self, frame: &mut Frame, area: Rect) {
         if self.visible() {
             let area = center(area);
+            frame.render_widget(Clear, area);
             let enter_title = key_label_format(&self.confirm_key, "Yes");
             let cancel_title = key_label_format(&self.cancel_key, "No");
             let enter_title = Line::raw(&enter_title).right_aligned();
             let cancel_title = Line::raw(&cancel_title).left_aligned();
             let title = Line::raw(&self.title).centered();
-            let block = default_block()
+            let block = default_block(&self.theme)
                 .title_top(title)
                 .title_bottom(enter_title)
                 .title_bottom(cancel_title);
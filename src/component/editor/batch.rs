@@ -0,0 +1,438 @@
+use crate::action::Action;
+use crate::component::editor::buffer::Buffer;
+use crate::component::editor::git::cycle_rebase_verb;
+use crate::component::editor::options::EditorOptions;
+use crate::component::AppComponent;
+use crate::config::keybindings::parse_key_event;
+use crate::config::Config;
+use crate::util::{is_writable, read_text};
+use color_eyre::eyre::{bail, eyre, Result, WrapErr};
+use regex::Regex;
+use serde::Deserialize;
+use std::fs;
+use std::io::{stdout, Write};
+use std::path::{Path, PathBuf};
+use tui_textarea::{CursorMove, TextArea};
+
+const COMMAND_PREFIX: char = ':';
+
+/// A single step of a batch script.
+///
+/// Steps are tried in order: an `Action` as written in the keybindings config, a key resolved
+/// with the editor keybindings, or an ex-style command starting with `:`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Step {
+    Action(Action),
+    Key { key: String },
+    Command(String),
+}
+
+/// What a [`Step`] does to the buffer once resolved.
+enum Edit {
+    Action(Action),
+    Command(Command),
+}
+
+/// Ex-style commands, applied to the line of the cursor unless noted otherwise.
+#[derive(Debug)]
+enum Command {
+    /// `:N`, moves the cursor to the start of the line `N`, counting from 1
+    GoTo(u16),
+    /// `:$`, moves the cursor to the last line
+    GoToLast,
+    /// `:d`, deletes the line
+    Delete,
+    /// `:s/pattern/replacement/flags`, or `:%s` for every line. The `g` flag replaces every
+    /// match in a line instead of only the first one
+    Substitute {
+        regex: Regex,
+        replacement: String,
+        all_lines: bool,
+        global: bool,
+    },
+    /// `:i text`, inserts the text at the cursor
+    Insert(String),
+    /// `:o text`, opens a new line below the cursor with the text
+    Open(String),
+}
+
+impl Command {
+    fn parse(raw: &str) -> Result<Self> {
+        let Some(command) = raw.strip_prefix(COMMAND_PREFIX) else {
+            bail!("Unknown step `{raw}`, commands must start with `{COMMAND_PREFIX}`");
+        };
+        let command = command.trim_start();
+        if let Ok(line) = command.parse::<u16>() {
+            return Ok(Self::GoTo(line.saturating_sub(1)));
+        }
+        if command == "$" {
+            return Ok(Self::GoToLast);
+        }
+        if command == "d" {
+            return Ok(Self::Delete);
+        }
+        if let Some(text) = command.strip_prefix("i ") {
+            return Ok(Self::Insert(text.to_string()));
+        }
+        if let Some(text) = command.strip_prefix("o ") {
+            return Ok(Self::Open(text.to_string()));
+        }
+        let (all_lines, substitute) = match command.strip_prefix('%') {
+            Some(substitute) => (true, substitute),
+            None => (false, command),
+        };
+        if let Some(substitute) = substitute.strip_prefix('s') {
+            return Self::parse_substitute(substitute, all_lines);
+        }
+        bail!("Unknown command `{raw}`")
+    }
+    fn parse_substitute(raw: &str, all_lines: bool) -> Result<Self> {
+        let mut chars = raw.chars();
+        let Some(delimiter) = chars.next() else {
+            bail!("Missing pattern in substitute command");
+        };
+        let parts = split_unescaped(chars.as_str(), delimiter);
+        let [pattern, replacement, flags] = parts.as_slice() else {
+            bail!(
+                "Substitute commands are written as s{delimiter}pattern{delimiter}replacement{delimiter}flags"
+            );
+        };
+        let regex = Regex::new(pattern)?;
+        Ok(Self::Substitute {
+            regex,
+            replacement: replacement.clone(),
+            all_lines,
+            global: flags.contains('g'),
+        })
+    }
+    fn apply(&self, buffer: &mut Buffer) {
+        let text_area = &mut buffer.text_area;
+        match self {
+            Command::GoTo(line) => text_area.move_cursor(CursorMove::Jump(*line, 0)),
+            Command::GoToLast => text_area.move_cursor(CursorMove::Bottom),
+            Command::Delete => {
+                let (row, _) = text_area.cursor();
+                let mut lines = text_area.lines().to_vec();
+                lines.remove(row);
+                replace_lines(buffer, lines, (row, 0));
+            }
+            Command::Substitute {
+                regex,
+                replacement,
+                all_lines,
+                global,
+            } => {
+                let cursor = text_area.cursor();
+                let (row, _) = cursor;
+                let lines = text_area
+                    .lines()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, line)| {
+                        if !*all_lines && i != row {
+                            line.clone()
+                        } else if *global {
+                            regex.replace_all(line, replacement.as_str()).into_owned()
+                        } else {
+                            regex.replace(line, replacement.as_str()).into_owned()
+                        }
+                    })
+                    .collect();
+                replace_lines(buffer, lines, cursor);
+            }
+            Command::Insert(text) => {
+                text_area.insert_str(text);
+            }
+            Command::Open(text) => {
+                text_area.move_cursor(CursorMove::End);
+                text_area.insert_newline();
+                text_area.insert_str(text);
+            }
+        }
+    }
+}
+
+/// Splits `raw` at every `delimiter` not escaped by a backslash, removing the escape.
+fn split_unescaped(raw: &str, delimiter: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' && chars.peek() == Some(&delimiter) {
+            parts.last_mut().unwrap().push(delimiter);
+            chars.next();
+        } else if c == delimiter {
+            parts.push(String::new());
+        } else {
+            parts.last_mut().unwrap().push(c);
+        }
+    }
+    parts
+}
+
+fn replace_lines(buffer: &mut Buffer, lines: Vec<String>, (row, col): (usize, usize)) {
+    let mut text_area = TextArea::new(lines);
+    text_area.move_cursor(CursorMove::Jump(row as u16, col as u16));
+    buffer.text_area = text_area;
+}
+
+/// Applies a batch script to files and saves them, without opening the terminal interface.
+pub struct BatchRunner {
+    steps: Vec<Step>,
+    config: Config,
+}
+
+impl BatchRunner {
+    pub fn new(script: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(script)
+            .wrap_err_with(|| format!("Unable to read the script {}", script.display()))?;
+        let steps = serde_yaml::from_str(&contents)
+            .wrap_err_with(|| format!("Invalid script {}", script.display()))?;
+        Ok(Self {
+            steps,
+            config: Config::new()?,
+        })
+    }
+    /// Runs the script on every file and on stdin, writing the edited stdin to stdout. Files
+    /// are read like the editor reads them, and compressed files are saved compressed again.
+    ///
+    /// Fails if there is nothing to edit, or if the files should or can only be opened
+    /// read-only, like the files inside archives.
+    pub fn run(&self, options: EditorOptions) -> Result<()> {
+        if options.read_only || options.pager {
+            bail!("Scripts save the files they edit, they can't be opened read-only");
+        }
+        if options.files.is_empty() && options.stdin.is_none() {
+            bail!("No files to edit, give them after the script or pipe the text with `-`");
+        }
+        if let Some(directory) = options.directory {
+            bail!("Scripts edit files, {directory} is a directory");
+        }
+        let mut files = options.files.iter();
+        if let Some(file) = files.find(|file| !is_writable(Path::new(file))) {
+            bail!("Scripts save the files they edit, {file} is read-only");
+        }
+        if let Some(stdin) = options.stdin {
+            let mut buffer = Buffer::default();
+            buffer.set_text(stdin);
+            self.apply(&mut buffer)?;
+            let mut stdout = stdout();
+            stdout.write_all(buffer.contents().as_bytes())?;
+            stdout.flush()?;
+        }
+        for file in options.files {
+            let path = PathBuf::from(file);
+            let (contents, codec) =
+                read_text(&path).wrap_err_with(|| format!("Unable to read {}", path.display()))?;
+            let mut buffer = Buffer::new(Some(path.clone()));
+            buffer.codec = codec;
+            buffer.set_text(contents.clone());
+            self.apply(&mut buffer)
+                .wrap_err_with(|| format!("Unable to edit {}", path.display()))?;
+            if buffer.contents() != contents {
+                save(&buffer)?;
+            }
+        }
+        Ok(())
+    }
+    fn apply(&self, buffer: &mut Buffer) -> Result<()> {
+        buffer.text_area.move_cursor(CursorMove::Top);
+        for (index, step) in self.steps.iter().enumerate() {
+            let applied = match self.resolve(step) {
+                Ok(Edit::Action(Action::Quit)) => break,
+                Ok(Edit::Action(action)) => apply_action(buffer, &action),
+                Ok(Edit::Command(command)) => {
+                    command.apply(buffer);
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            };
+            applied.wrap_err_with(|| format!("Step {} failed", index + 1))?;
+        }
+        Ok(())
+    }
+    fn resolve(&self, step: &Step) -> Result<Edit> {
+        let key = match step {
+            Step::Action(action) => return Ok(Edit::Action(action.clone())),
+            Step::Command(command) => return Command::parse(command).map(Edit::Command),
+            Step::Key { key } => key,
+        };
+        let key_event = parse_key_event(key).map_err(|e| eyre!(e))?;
+        let keybindings = &self.config.keybindings;
+        if let Some(action) = keybindings.get_action(&AppComponent::Editor, key_event) {
+            Ok(Edit::Action(action.clone()))
+        } else if let Some(char) = key_event.code.as_char() {
+            Ok(Edit::Action(Action::Character(char)))
+        } else {
+            bail!("Key `{key}` is not bound to any action")
+        }
+    }
+}
+
+fn apply_action(buffer: &mut Buffer, action: &Action) -> Result<()> {
+    let text_area = &mut buffer.text_area;
+    match action {
+        Action::Character(char) => {
+            if text_area.is_selecting() {
+                text_area.cut();
+            }
+            text_area.insert_char(*char);
+        }
+        Action::PasteText(text) => {
+            text_area.insert_str(text);
+        }
+        Action::NewLine => text_area.insert_newline(),
+        Action::Tab => {
+            text_area.insert_tab();
+        }
+        Action::Backspace => {
+            text_area.delete_char();
+        }
+        Action::Delete => {
+            text_area.delete_next_char();
+        }
+        Action::Up => move_cursor(text_area, CursorMove::Up, false),
+        Action::Down => move_cursor(text_area, CursorMove::Down, false),
+        Action::Left => move_cursor(text_area, CursorMove::Back, false),
+        Action::Right => move_cursor(text_area, CursorMove::Forward, false),
+        Action::SelectUp => move_cursor(text_area, CursorMove::Up, true),
+        Action::SelectDown => move_cursor(text_area, CursorMove::Down, true),
+        Action::SelectLeft => move_cursor(text_area, CursorMove::Back, true),
+        Action::SelectRight => move_cursor(text_area, CursorMove::Forward, true),
+        Action::EndOfWord => text_area.move_cursor(CursorMove::WordForward),
+        Action::StartOfWord => text_area.move_cursor(CursorMove::WordBack),
+        Action::PageUp => buffer.scroll_page(false),
+        Action::PageDown => buffer.scroll_page(true),
+        Action::SelectAll => text_area.select_all(),
+        Action::Cancel => text_area.cancel_selection(),
+        Action::Copy => text_area.copy(),
+        Action::Cut => {
+            text_area.cut();
+        }
+        Action::Paste => {
+            text_area.paste();
+        }
+        Action::Undo => {
+            text_area.undo();
+        }
+        Action::Redo => {
+            text_area.redo();
+        }
+        Action::CycleRebaseVerb => {
+            let (row, col) = text_area.cursor();
+            if let Some(line) = cycle_rebase_verb(&text_area.lines()[row]) {
+                let mut lines = text_area.lines().to_vec();
+                lines[row] = line;
+                replace_lines(buffer, lines, (row, col));
+            }
+        }
+        Action::Insert | Action::Tick | Action::ToggleLineNumber => {}
+        Action::Save => save(buffer)?,
+        action => bail!("{action} can't be used in a script"),
+    }
+    Ok(())
+}
+
+fn move_cursor(text_area: &mut TextArea, cursor_move: CursorMove, select: bool) {
+    if !select {
+        text_area.cancel_selection();
+    } else if !text_area.is_selecting() {
+        text_area.start_selection();
+    }
+    text_area.move_cursor(cursor_move);
+}
+
+/// Saves the buffer to its file, compressed again with the codec it was read with.
+fn save(buffer: &Buffer) -> Result<()> {
+    let Some(path) = &buffer.file_path else {
+        return Ok(());
+    };
+    let contents = buffer.contents().into_bytes();
+    let contents = match buffer.codec {
+        Some(codec) => codec.compress(&contents)?,
+        None => contents,
+    };
+    fs::write(path, contents).wrap_err_with(|| format!("Unable to save {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::Codec;
+    use crate::test_util::TempDir;
+
+    fn runner(script: &str) -> BatchRunner {
+        let config = Config {
+            keybindings: Config::default_keybindings().unwrap(),
+            ..Default::default()
+        };
+        BatchRunner {
+            steps: serde_yaml::from_str(script).unwrap(),
+            config,
+        }
+    }
+
+    fn edit(script: &str, text: &str) -> Result<String> {
+        let mut buffer = Buffer::default();
+        buffer.set_text(text.to_string());
+        runner(script).apply(&mut buffer)?;
+        Ok(buffer.contents())
+    }
+
+    #[test]
+    fn commands_actions_and_keys_edit_the_buffer() {
+        let script = "
+- ':%s/o/0/g'
+- ':2'
+- ':d'
+- ':$'
+- ':o last'
+- ':1'
+- ':s/0/o/'
+- key: enter
+- ':i >'
+- Right
+- Right
+- Backspace
+";
+        let edited = edit(script, "foo\nbar\nbaz").unwrap();
+        assert_eq!(edited, "\n>f0\nbaz\nlast");
+    }
+
+    #[test]
+    fn invalid_steps_fail_with_their_number() {
+        let error = edit("- ':1'\n- ':x'\n", "text").unwrap_err();
+        assert_eq!(error.to_string(), "Step 2 failed");
+        assert!(edit("- ':s/a/b'\n", "text").is_err());
+        assert!(edit("- key: f12\n", "text").is_err());
+        assert!(edit("- OpenFile\n", "text").is_err());
+        let parts = split_unescaped(r"a\/b/c/", '/');
+        assert_eq!(parts, vec!["a/b", "c", ""]);
+    }
+
+    #[test]
+    fn read_only_or_empty_runs_are_rejected() {
+        let runner = runner("- Save\n");
+        assert!(runner.run(EditorOptions::default()).is_err());
+        let options = EditorOptions {
+            files: vec!["file".to_string()],
+            read_only: true,
+            ..Default::default()
+        };
+        assert!(runner.run(options).is_err());
+    }
+
+    #[test]
+    fn compressed_files_are_saved_compressed_again() {
+        let dir = TempDir::new("batch");
+        let file = dir.join("notes.txt.gz");
+        fs::write(&file, Codec::Gzip.compress(b"hello").unwrap()).unwrap();
+        let options = EditorOptions {
+            files: vec![file.display().to_string()],
+            ..Default::default()
+        };
+        runner("- ':s/hello/bye/'\n").run(options).unwrap();
+        let saved = Codec::Gzip.decompress(&fs::read(&file).unwrap()).unwrap();
+        assert_eq!(saved, b"bye");
+    }
+}
@@ -1,11 +1,18 @@
+use crate::archive::split_archive_path;
 use crate::component::component_utils::new_clipboard;
+use crate::compression::Codec;
+use crate::component::editor::git::GitFile;
+use crate::session::BufferSession;
 use clipboard::{ClipboardContext, ClipboardProvider};
 use color_eyre::eyre::eyre;
+use ratatui::layout::Rect;
 use std::env::current_dir;
+use std::ops::{Deref, DerefMut};
 use std::path::{Path, PathBuf};
-use tui_textarea::TextArea;
+use tui_textarea::{CursorMove, TextArea};
 
 const UNSAVED_FILE_NAME: &str = "unsaved";
+const STDOUT_FILE_NAME: &str = "stdout";
 const MAX_PATH_STRING_DEPTH: u16 = 10;
 
 pub(super) struct Buffer<'a> {
@@ -14,6 +21,25 @@ pub(super) struct Buffer<'a> {
     pub modified: bool,
     pub clipboard_context: Option<ClipboardContext>,
     pub current_path_string: Option<String>,
+    pub loading: bool,
+    /// Whether the contents are written to stdout instead of `file_path` when saving
+    pub write_to_stdout: bool,
+    pub git_file: Option<GitFile>,
+    /// First visible row and column of the text area
+    pub viewport: (u16, u16),
+    /// Rows of text visible in the text area
+    pub page_height: u16,
+    /// Whether the text can't be edited, because the file can't be written or it was
+    /// opened read-only
+    pub read_only: bool,
+    /// Whether text appended to the file is loaded as it is written, like `tail -f`
+    pub follow: bool,
+    /// Length of the file when it was last read, where reading resumes when following
+    pub file_length: u64,
+    /// Position from a restored session, applied the next time the buffer is rendered
+    pub restored_position: Option<BufferSession>,
+    /// Codec the file was decompressed with, and is compressed with again when saved
+    pub codec: Option<Codec>,
 }
 
 impl Default for Buffer<'_> {
@@ -24,6 +50,16 @@ impl Default for Buffer<'_> {
             modified: Default::default(),
             clipboard_context: new_clipboard(),
             current_path_string: Default::default(),
+            loading: Default::default(),
+            write_to_stdout: Default::default(),
+            git_file: Default::default(),
+            viewport: Default::default(),
+            page_height: Default::default(),
+            read_only: Default::default(),
+            follow: Default::default(),
+            file_length: Default::default(),
+            restored_position: Default::default(),
+            codec: Default::default(),
         }
     }
 }
@@ -35,12 +71,67 @@ impl Buffer<'_> {
         } else {
             None
         };
+        let git_file = file.as_deref().and_then(GitFile::detect);
         Self {
             file_path: file,
             current_path_string,
+            git_file,
             ..Default::default()
         }
     }
+    /// Creates an unnamed buffer already filled with `text`.
+    pub(super) fn with_text(text: &str) -> Self {
+        Self {
+            text_area: TextArea::from(text.lines()),
+            ..Default::default()
+        }
+    }
+    /// The buffer contents, as they should be written to a file.
+    pub(super) fn contents(&self) -> String {
+        self.text_area.lines().join("\n")
+    }
+    /// Width of the line numbers column, or 0 if they are hidden.
+    pub(super) fn gutter_width(&self) -> u16 {
+        if self.text_area.line_number_style().is_none() {
+            return 0;
+        }
+        self.text_area.lines().len().to_string().len() as u16 + 2
+    }
+    /// Updates `viewport` the same way the text area scrolls when rendered in `area`,
+    /// so it should be called right before rendering it.
+    pub(super) fn update_viewport(&mut self, area: Rect) {
+        let (row, col) = self.text_area.cursor();
+        let (top_row, top_col) = self.viewport;
+        let gutter = self.gutter_width();
+        let col = col as u16;
+        let col = if gutter == 0 {
+            col
+        } else if col <= gutter {
+            col * 2
+        } else {
+            col + gutter
+        };
+        self.viewport = (
+            next_scroll_top(top_row, row as u16, area.height),
+            next_scroll_top(top_col, col, area.width),
+        );
+        self.page_height = area.height;
+    }
+    /// Scrolls the text area a whole page up or down, keeping the cursor in the new page.
+    pub(super) fn scroll_page(&mut self, down: bool) {
+        let (_, col) = self.text_area.cursor();
+        let (top_row, _) = self.viewport;
+        let height = self.page_height.max(1);
+        let last_row = self.text_area.lines().len().saturating_sub(1) as u16;
+        let row = if down {
+            let top_row = (top_row + height).min(last_row.saturating_sub(height - 1));
+            (top_row + height - 1).min(last_row)
+        } else {
+            top_row.saturating_sub(height)
+        };
+        self.text_area
+            .move_cursor(CursorMove::Jump(row, col as u16));
+    }
     /// Gets the current file`s directory.
     /// For obtaining the file path, use `current_path`
     pub(super) fn current_directory(&self) -> PathBuf {
@@ -59,19 +150,51 @@ impl Buffer<'_> {
         self.modified = false;
         self.text_area = TextArea::default();
     }
+    /// Replaces the text with the contents of a file, leaving the cursor at its end.
+    pub(super) fn set_text(&mut self, text: String) {
+        self.clear_text();
+        self.text_area.insert_str(text);
+        self.text_area.cancel_selection();
+    }
     pub(super) fn change_path(&mut self, path: PathBuf) {
         self.current_path_string = Self::current_path(&path, MAX_PATH_STRING_DEPTH);
+        self.git_file = GitFile::detect(&path);
         self.file_path = Some(path);
         self.modified = false;
     }
+    /// Where this buffer was left, to be saved in a session.
+    ///
+    /// Buffers without a file, or for files git asked to edit, are not worth restoring.
+    pub(super) fn session(&self) -> Option<BufferSession> {
+        let path = self.file_path.clone()?;
+        if self.git_file.is_some() {
+            return None;
+        }
+        if let Some(position) = &self.restored_position {
+            return Some(position.clone());
+        }
+        Some(BufferSession {
+            path,
+            cursor: self.text_area.cursor(),
+            top_row: self.viewport.0,
+            search: self.text_area.search_pattern().map(|r| r.to_string()),
+        })
+    }
+    /// Whether this buffer has no file and no text, and can be replaced by another file.
+    pub(super) fn is_blank(&self) -> bool {
+        self.file_path.is_none() && !self.modified && self.text_area.is_empty()
+    }
     pub fn file_name(&self) -> String {
+        if self.write_to_stdout && self.file_path.is_none() {
+            return STDOUT_FILE_NAME.to_string();
+        }
         let Some(path) = &self.file_path else {
             return UNSAVED_FILE_NAME.to_string();
         };
         let Some(Some(file_name)) = path.file_name().map(|f| f.to_str()) else {
             return UNSAVED_FILE_NAME.to_string();
         };
-        if path.exists() {
+        if path.exists() || split_archive_path(path).is_some() {
             file_name.to_string()
         } else {
             format!("{file_name} - {UNSAVED_FILE_NAME}")
@@ -112,3 +235,112 @@ impl Buffer<'_> {
         copied.get_contents().ok()
     }
 }
+
+fn next_scroll_top(previous_top: u16, cursor: u16, length: u16) -> u16 {
+    if cursor < previous_top {
+        cursor
+    } else if previous_top + length <= cursor {
+        cursor + 1 - length
+    } else {
+        previous_top
+    }
+}
+
+/// All buffers opened in the editor.
+///
+/// There is always at least one buffer, and this struct dereferences to the one currently
+/// visible, so it can be used the same way as a single [`Buffer`].
+pub(super) struct Buffers<'a> {
+    buffers: Vec<Buffer<'a>>,
+    current: usize,
+}
+
+impl Default for Buffers<'_> {
+    fn default() -> Self {
+        Self {
+            buffers: vec![Buffer::default()],
+            current: 0,
+        }
+    }
+}
+
+impl<'a> Buffers<'a> {
+    pub(super) fn new(buffers: Vec<Buffer<'a>>) -> Self {
+        if buffers.is_empty() {
+            return Self::default();
+        }
+        Self {
+            buffers,
+            current: 0,
+        }
+    }
+    pub(super) fn len(&self) -> usize {
+        self.buffers.len()
+    }
+    pub(super) fn current_index(&self) -> usize {
+        self.current
+    }
+    /// Adds a new buffer after the current one and makes it visible.
+    pub(super) fn push(&mut self, buffer: Buffer<'a>) {
+        self.current += 1;
+        self.buffers.insert(self.current, buffer);
+    }
+    pub(super) fn next(&mut self) -> bool {
+        if self.buffers.len() < 2 {
+            return false;
+        }
+        self.current = (self.current + 1) % self.buffers.len();
+        true
+    }
+    pub(super) fn previous(&mut self) -> bool {
+        if self.buffers.len() < 2 {
+            return false;
+        }
+        self.current = self
+            .current
+            .checked_sub(1)
+            .unwrap_or(self.buffers.len() - 1);
+        true
+    }
+    /// Closes the current buffer, returning false if it is the last one open.
+    pub(super) fn close_current(&mut self) -> bool {
+        if self.buffers.len() < 2 {
+            return false;
+        }
+        self.buffers.remove(self.current);
+        if self.current >= self.buffers.len() {
+            self.current = self.buffers.len() - 1;
+        }
+        true
+    }
+    pub(super) fn position_of<P: AsRef<Path>>(&self, path: P) -> Option<usize> {
+        let path = path.as_ref();
+        self.buffers
+            .iter()
+            .position(|b| b.file_path.as_deref() == Some(path))
+    }
+    pub(super) fn select(&mut self, index: usize) {
+        if index < self.buffers.len() {
+            self.current = index;
+        }
+    }
+    pub(super) fn get_mut(&mut self, index: usize) -> Option<&mut Buffer<'a>> {
+        self.buffers.get_mut(index)
+    }
+    pub(super) fn iter(&self) -> impl Iterator<Item = &Buffer<'a>> {
+        self.buffers.iter()
+    }
+}
+
+impl<'a> Deref for Buffers<'a> {
+    type Target = Buffer<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.buffers[self.current]
+    }
+}
+
+impl DerefMut for Buffers<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buffers[self.current]
+    }
+}
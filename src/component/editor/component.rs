@@ -4,29 +4,35 @@ use crate::action::{
 };
 use crate::component::component_utils::{center, default_block, write_file};
 use crate::component::confirm_dialog::ConfirmDialogComponent;
-use crate::component::editor::buffer::Buffer;
+use crate::component::editor::buffer::{Buffer, Buffers};
+use crate::component::editor::git::{cycle_rebase_verb, GitFile};
+use crate::component::editor::options::EditorOptions;
 use crate::component::editor::search_box::SearchBoxComponent;
 use crate::component::file_selector::component::FileSelectorComponent;
 use crate::component::file_selector::file_history_saver::FileHistorySaver;
+use crate::component::file_selector::quick_switcher::QuickSwitcherComponent;
 use crate::component::help::HelpComponent;
 use crate::component::notification::NotificationComponent;
 use crate::component::{AppComponent, Component};
+use crate::compression::Codec;
 use crate::config::Config;
-use crate::util::read_dir;
+use crate::session::{BufferSession, Session};
+use crate::util::{is_writable, read_appended, read_dir};
 use crossterm::event::KeyEvent;
+use directories::UserDirs;
 use ratatui::layout::{Constraint, Layout, Rect};
-use ratatui::prelude::Color;
-use ratatui::style::Style;
 use ratatui::text::Line;
 use ratatui::Frame;
 use std::path::{Path, PathBuf};
 use throbber_widgets_tui::{Throbber, BRAILLE_SIX_DOUBLE};
 use tui_textarea::CursorMove;
 
+/// Exit status when quitting with unsaved changes
+const EXIT_UNSAVED: u8 = 1;
+
 #[derive(Default)]
 pub struct EditorComponent<'a> {
-    buffer: Buffer<'a>,
-    loading: bool,
+    buffer: Buffers<'a>,
     saving_file: bool,
     action_sender: Option<ActionSender>,
     task_result_sender: Option<AsyncActionSender>,
@@ -37,7 +43,15 @@ pub struct EditorComponent<'a> {
     file_dialog: FileSelectorComponent<'a>,
     confirm_dialog_component: ConfirmDialogComponent,
     search_box_component: SearchBoxComponent<'a>,
+    quick_switcher: QuickSwitcherComponent,
     file_history_saver: FileHistorySaver,
+    starting_directory: Option<PathBuf>,
+    quit_on_close: bool,
+    pager: bool,
+    read_only: bool,
+    /// File whose appended text is being read
+    reading_appended: Option<PathBuf>,
+    session_name: Option<String>,
 }
 
 impl<P: AsRef<Path>> From<P> for EditorComponent<'_> {
@@ -45,7 +59,7 @@ impl<P: AsRef<Path>> From<P> for EditorComponent<'_> {
         let path = PathBuf::from(value.as_ref());
         let buffer = Buffer::new(Some(path));
         Self {
-            buffer,
+            buffer: Buffers::new(vec![buffer]),
             ..Default::default()
         }
     }
@@ -53,38 +67,147 @@ impl<P: AsRef<Path>> From<P> for EditorComponent<'_> {
 
 impl EditorComponent<'_> {
     pub fn new<S: AsRef<str>>(file: S) -> Self {
-        let path = PathBuf::from(file.as_ref());
-        let path = path.canonicalize().unwrap_or(path);
-        let buffer = Buffer::new(Some(path));
+        let options = EditorOptions {
+            files: vec![file.as_ref().to_string()],
+            ..Default::default()
+        };
+        Self::new_with_options(options)
+    }
+    /// Opens stdin and every file in their own buffers, showing the first one.
+    ///
+    /// If a directory is set, the file selector is opened there once the editor starts.
+    pub fn new_with_options(options: EditorOptions) -> Self {
+        let read_only = options.read_only || options.pager;
+        let mut buffers: Vec<Buffer> = Vec::with_capacity(options.files.len() + 1);
+        if let Some(stdin) = &options.stdin {
+            let mut buffer = Buffer::with_text(stdin);
+            buffer.read_only = read_only;
+            buffers.push(buffer);
+        }
+        for file in options.files {
+            let path = PathBuf::from(file);
+            let path = path.canonicalize().unwrap_or(path);
+            if !buffers.iter().any(|b| b.file_path.as_ref() == Some(&path)) {
+                buffers.push(Buffer::new(Some(path)));
+            }
+        }
+        if options.stdout
+            && let Some(buffer) = buffers.first_mut()
+        {
+            buffer.write_to_stdout = true;
+        }
+        let starting_directory = options.directory.map(PathBuf::from);
         Self {
-            buffer,
+            buffer: Buffers::new(buffers),
+            starting_directory,
+            quit_on_close: options.quit_on_close,
+            pager: options.pager,
+            read_only,
             ..Default::default()
         }
     }
+    /// Opens the buffers of a session, which get their positions back once loaded.
+    pub fn from_session(session: Session) -> Self {
+        let files = session.buffers.iter();
+        let options = EditorOptions {
+            files: files.map(|b| b.path.display().to_string()).collect(),
+            pager: session.component == AppComponent::Pager,
+            ..Default::default()
+        };
+        let mut editor = Self::new_with_options(options);
+        for mut position in session.buffers {
+            position.path = position.path.canonicalize().unwrap_or(position.path);
+            if let Some(index) = editor.buffer.position_of(&position.path) {
+                editor.buffer.get_mut(index).unwrap().restored_position = Some(position);
+            }
+        }
+        editor.buffer.select(session.current_buffer);
+        editor.session_name = session.name;
+        editor
+    }
+    /// The open buffers and where they were left.
+    fn session(&self) -> Session {
+        let mut session = Session::new(self.keybinds_id().clone());
+        session.name = self.session_name.clone();
+        for (index, buffer) in self.buffer.iter().enumerate() {
+            let Some(buffer_session) = buffer.session() else {
+                continue;
+            };
+            if index == self.buffer.current_index() {
+                session.current_buffer = session.buffers.len();
+            }
+            session.buffers.push(buffer_session);
+        }
+        session
+    }
+    /// Scrolls and moves the cursor back to where they were in a session.
+    ///
+    /// The text area only scrolls when rendered, so it is rendered once with the cursor at the
+    /// bottom of the saved page, before moving the cursor back where it was.
+    fn restore_position(&mut self, frame: &mut Frame, area: Rect, position: BufferSession) {
+        if position.top_row > 0 {
+            let bottom_row = position.top_row + area.height.saturating_sub(1);
+            let text_area = &mut self.buffer.text_area;
+            text_area.move_cursor(CursorMove::Jump(bottom_row, 0));
+            self.buffer.update_viewport(area);
+            frame.render_widget(&self.buffer.text_area, area);
+        }
+        let text_area = &mut self.buffer.text_area;
+        let (row, col) = position.cursor;
+        text_area.move_cursor(CursorMove::Jump(row as u16, col as u16));
+        if let Some(search) = &position.search {
+            let _ = text_area.set_search_pattern(search);
+        }
+    }
     fn load_file(&mut self) {
         let Some(path) = &mut self.buffer.file_path else {
             return;
         };
         let action_sender = self.task_result_sender.clone().unwrap();
         let path = path.clone();
-        self.loading = true;
+        self.buffer.loading = true;
         tokio::spawn(async move {
             let action = read_dir(&path).await;
             let _ = action_sender.send(action);
         });
     }
+    fn load_all_files(&mut self) {
+        let current = self.buffer.current_index();
+        for i in 0..self.buffer.len() {
+            self.buffer.select(i);
+            self.load_file();
+        }
+        self.buffer.select(current);
+    }
+    fn open_file(&mut self, path: PathBuf) -> ActionResult {
+        if let Some(index) = self.buffer.position_of(&path) {
+            self.buffer.select(index);
+            return ActionResult::consumed(true);
+        }
+        if self.buffer.is_blank() {
+            // The blank stdin buffer is gone, so the opened file must not go to stdout
+            self.buffer.write_to_stdout = false;
+            self.buffer.change_path(path);
+        } else {
+            self.buffer.push(Buffer::new(Some(path)));
+        }
+        self.load_file();
+        ActionResult::consumed(true)
+    }
     fn handle_selector(&mut self, path_buf: PathBuf, selector_type: SelectorType) -> ActionResult {
         match selector_type {
             SelectorType::PickFolder => self.save_file_at(path_buf, true),
             SelectorType::NewFile => self.save_file_at(path_buf, false),
-            SelectorType::PickFile => {
-                self.buffer.change_path(path_buf);
-                self.load_file();
-                ActionResult::consumed(true)
-            }
+            SelectorType::PickFile => self.open_file(path_buf),
         }
     }
     fn handle_save_file(&mut self) -> ActionResult {
+        if self.buffer.read_only {
+            return self.handle_save_to();
+        }
+        if self.buffer.write_to_stdout {
+            return self.save_to_stdout();
+        }
         if !self.buffer.modified && self.buffer.file_path.is_some() {
             return ActionResult::not_consumed(false);
         }
@@ -93,17 +216,68 @@ impl EditorComponent<'_> {
         };
         self.save_file_at(path, true)
     }
+    /// Hands the buffer over to be written to stdout, and quits the application.
+    fn save_to_stdout(&mut self) -> ActionResult {
+        self.send_stdout_contents();
+        self.buffer.modified = false;
+        let _ = self.action_sender.as_ref().unwrap().send(Action::Quit);
+        ActionResult::consumed(true)
+    }
+    fn send_stdout_contents(&self) {
+        let Some(buffer) = self.buffer.iter().find(|b| b.write_to_stdout) else {
+            return;
+        };
+        let action = AsyncAction::WriteStdout(buffer.contents());
+        let _ = self.task_result_sender.as_ref().unwrap().send(action);
+    }
     fn handle_save_to(&mut self) -> ActionResult {
-        self.open_file_dialog(SelectorType::NewFile)
+        if !self.buffer.read_only {
+            return self.open_file_dialog(SelectorType::NewFile);
+        }
+        let directory = Some(self.buffer.current_directory())
+            .filter(|d| is_writable(d))
+            .or_else(|| UserDirs::new().map(|d| d.home_dir().to_path_buf()))
+            .unwrap_or_default();
+        self.file_dialog.show(directory, SelectorType::NewFile);
+        ActionResult::consumed(true)
     }
+    /// Saves the decompressed buffer next to the compressed file, without its compression
+    /// extension, asking first if that would overwrite a file.
+    ///
+    /// Compressed files without that extension would be overwritten, so the user picks where
+    /// to save them instead.
+    fn save_uncompressed(&mut self) -> ActionResult {
+        let (Some(path), Some(codec)) = (self.buffer.file_path.clone(), self.buffer.codec) else {
+            self.notification.notify_error("The file is not compressed");
+            return ActionResult::consumed(true);
+        };
+        let path = codec.uncompressed_path(&path);
+        if self.buffer.file_path.as_ref() == Some(&path) {
+            return self.handle_save_to();
+        }
+        self.buffer.codec = None;
+        self.buffer.modified = true;
+        self.save_file_at(path, false)
+    }
+    /// Whether a buffer of the file at `path` should be read-only.
+    fn is_read_only(&self, path: &Path) -> bool {
+        self.read_only || !is_writable(path)
+    }
+    /// Writes the buffer to `path`. It is compressed again with the codec it was read with,
+    /// unless it is saved to another file, which is compressed according to its extension.
     fn save_file_at(&mut self, path: PathBuf, overwrite: bool) -> ActionResult {
+        if self.buffer.file_path.as_ref() != Some(&path) {
+            self.buffer.codec = Codec::from_extension(&path);
+        }
+        let codec = self.buffer.codec;
         self.buffer.change_path(path.clone());
-        let lines = self.buffer.text_area.lines().join("\n");
+        self.buffer.write_to_stdout = false;
+        let lines = self.buffer.contents();
         let action_sender = self.task_result_sender.clone().unwrap();
         self.saving_file = true;
         self.file_dialog.hide();
         tokio::spawn(async move {
-            let r = write_file(path, lines, overwrite).await;
+            let r = write_file(path, lines, overwrite, codec).await;
             let _ = action_sender.send(AsyncAction::SavedFile(r));
         });
         ActionResult::consumed(true)
@@ -122,13 +296,16 @@ impl EditorComponent<'_> {
     }
     fn delete(&mut self) -> ActionResult {
         if self.buffer.text_area.delete_next_char() {
+            self.buffer.modified = true;
             ActionResult::consumed(true)
         } else {
             ActionResult::not_consumed(false)
         }
     }
     fn cut_selection(&mut self) -> ActionResult {
-        self.buffer.text_area.cut();
+        if self.buffer.text_area.cut() {
+            self.buffer.modified = true;
+        }
         let yanked = self.buffer.text_area.yank_text();
         self.stop_selection();
         if yanked.is_empty() {
@@ -166,11 +343,167 @@ impl EditorComponent<'_> {
         self.buffer.modified = true;
         ActionResult::consumed(true)
     }
-    fn load_file_contents(&mut self, contents: String) -> ActionResult {
-        self.loading = false;
-        self.buffer.clear_text();
-        self.buffer.text_area.insert_str(contents);
-        self.buffer.text_area.cancel_selection();
+    fn load_file_contents(
+        &mut self,
+        path: &Path,
+        contents: String,
+        codec: Option<Codec>,
+    ) -> ActionResult {
+        let Some(index) = self.buffer.position_of(path) else {
+            return ActionResult::consumed(false);
+        };
+        let start_at_top = self.pager;
+        let read_only = self.is_read_only(path);
+        let last_position = self.file_history_saver.position_of(path);
+        let buffer = self.buffer.get_mut(index).unwrap();
+        buffer.loading = false;
+        buffer.read_only = read_only;
+        buffer.file_length = contents.len() as u64;
+        buffer.codec = codec;
+        buffer.set_text(contents);
+        if start_at_top || buffer.git_file.is_some() {
+            buffer.text_area.move_cursor(CursorMove::Top);
+        } else if buffer.restored_position.is_none() {
+            buffer.restored_position = last_position;
+        }
+        ActionResult::consumed(true)
+    }
+    /// Stops showing the buffer of the file as loading, since it failed to be read, and lets
+    /// it be read again if it is followed.
+    fn handle_file_error(&mut self, path: &Path, msg: &str) -> ActionResult {
+        if self.reading_appended.as_deref() == Some(path) {
+            self.reading_appended = None;
+        }
+        if let Some(index) = self.buffer.position_of(path) {
+            self.buffer.get_mut(index).unwrap().loading = false;
+        }
+        self.notification.notify_error(msg);
+        ActionResult::consumed(true)
+    }
+    fn append_file_contents(&mut self, path: &Path, length: u64, text: &str) -> ActionResult {
+        self.reading_appended = None;
+        let Some(index) = self.buffer.position_of(path) else {
+            return ActionResult::consumed(false);
+        };
+        let buffer = self.buffer.get_mut(index).unwrap();
+        buffer.file_length = length;
+        if !buffer.follow || text.is_empty() {
+            return ActionResult::consumed(false);
+        }
+        buffer.text_area.cancel_selection();
+        buffer.text_area.move_cursor(CursorMove::Bottom);
+        buffer.text_area.move_cursor(CursorMove::End);
+        buffer.text_area.insert_str(text);
+        ActionResult::consumed(true)
+    }
+    fn toggle_follow(&mut self) -> ActionResult {
+        if self.buffer.file_path.is_none() {
+            self.notification.notify_error("Only files can be followed");
+            return ActionResult::consumed(true);
+        }
+        if self.buffer.codec.is_some() {
+            self.notification
+                .notify_error("Compressed files can't be followed");
+            return ActionResult::consumed(true);
+        }
+        self.buffer.follow = !self.buffer.follow;
+        if self.buffer.follow {
+            self.buffer.text_area.move_cursor(CursorMove::Bottom);
+        }
+        ActionResult::consumed(true)
+    }
+    /// Reads what was appended to the current file since it was last read, if it is followed.
+    fn read_followed_file(&mut self) {
+        if self.reading_appended.is_some() || !self.buffer.follow {
+            return;
+        }
+        let Some(path) = self.buffer.file_path.clone() else {
+            return;
+        };
+        let length = self.buffer.file_length;
+        let action_sender = self.task_result_sender.clone().unwrap();
+        self.reading_appended = Some(path.clone());
+        tokio::spawn(async move {
+            let action = read_appended(&path, length).await;
+            let _ = action_sender.send(action);
+        });
+    }
+    fn reject_edit(&mut self) -> ActionResult {
+        let save_key = self
+            .config
+            .keybindings
+            .get_key_string_or_default(Action::Save, self.keybinds_id());
+        if save_key.is_empty() {
+            self.notification.notify_error("The file is read-only");
+        } else {
+            let message = format!("Read-only, [{save_key}] saves elsewhere");
+            self.notification.notify_error(message);
+        }
+        ActionResult::consumed(true)
+    }
+    fn keybinds_id(&self) -> &'static AppComponent {
+        if self.pager {
+            &AppComponent::Pager
+        } else {
+            &AppComponent::Editor
+        }
+    }
+    fn next_buffer(&mut self) -> ActionResult {
+        let changed = self.buffer.next();
+        ActionResult::consumed(changed)
+    }
+    fn previous_buffer(&mut self) -> ActionResult {
+        let changed = self.buffer.previous();
+        ActionResult::consumed(changed)
+    }
+    fn handle_return(&mut self) -> ActionResult {
+        if self.buffer.modified {
+            const TITLE: &str = " Unsaved changes ";
+            const MESSAGE: &str = "Close the file without saving it?";
+            self.confirm_dialog_component
+                .show(TITLE, MESSAGE, Action::ConfirmReturn);
+            return ActionResult::consumed(true);
+        }
+        self.close_buffer()
+    }
+    /// Closes the current buffer, leaving the editor when it was the last one.
+    fn close_buffer(&mut self) -> ActionResult {
+        if let Some(position) = self.buffer.session() {
+            self.file_history_saver.save_position(&position);
+        }
+        if self.buffer.close_current() {
+            return ActionResult::consumed(true);
+        }
+        if self.quit_on_close {
+            let _ = self.action_sender.as_ref().unwrap().send(Action::Quit);
+            return ActionResult::consumed(false);
+        }
+        // The changes were discarded, so they don't make quitting from home fail
+        self.buffer.modified = false;
+        let _ = self
+            .task_result_sender
+            .as_ref()
+            .unwrap()
+            .send(AsyncAction::Navigate(None));
+        ActionResult::consumed(false)
+    }
+    fn cycle_rebase_verb(&mut self) -> ActionResult {
+        if self.buffer.git_file != Some(GitFile::RebaseTodo) {
+            return ActionResult::not_consumed(false);
+        }
+        let text_area = &mut self.buffer.text_area;
+        let (row, col) = text_area.cursor();
+        let Some(line) = cycle_rebase_verb(&text_area.lines()[row]) else {
+            return ActionResult::consumed(false);
+        };
+        let yank = text_area.yank_text();
+        text_area.cancel_selection();
+        text_area.move_cursor(CursorMove::Head);
+        text_area.delete_line_by_end();
+        text_area.insert_str(line);
+        text_area.move_cursor(CursorMove::Jump(row as u16, col as u16));
+        text_area.set_yank_text(yank);
+        self.buffer.modified = true;
         ActionResult::consumed(true)
     }
     fn begin_insert_mode(&mut self) -> ActionResult {
@@ -198,6 +531,7 @@ impl EditorComponent<'_> {
     }
     fn paste_text(&mut self, text: &str) -> ActionResult {
         let changed = self.buffer.text_area.insert_str(text);
+        self.buffer.modified |= changed;
         ActionResult::consumed(changed)
     }
     fn select_all(&mut self) -> ActionResult {
@@ -211,8 +545,14 @@ impl EditorComponent<'_> {
                 SaveFileResult::Saved(path) => {
                     self.file_history_saver.push_to_history(path);
                     self.notification.notify_text("File saved");
-                    self.buffer.change_path(path.clone());
-                    self.buffer.modified = false;
+                    let read_only = self.is_read_only(path);
+                    let buffer = match self.buffer.position_of(path) {
+                        Some(index) => self.buffer.get_mut(index).unwrap(),
+                        None => &mut self.buffer,
+                    };
+                    buffer.change_path(path.clone());
+                    buffer.modified = false;
+                    buffer.read_only = read_only;
                 }
                 SaveFileResult::Error(error) => self.notification.notify_error(error),
                 SaveFileResult::MissingName => return self.open_file_dialog(SelectorType::NewFile),
@@ -229,11 +569,11 @@ impl EditorComponent<'_> {
         ActionResult::consumed(true)
     }
     fn page_up(&mut self) -> ActionResult {
-        self.buffer.text_area.move_cursor(CursorMove::Top);
+        self.buffer.scroll_page(false);
         ActionResult::consumed(true)
     }
     fn page_down(&mut self) -> ActionResult {
-        self.buffer.text_area.move_cursor(CursorMove::Down);
+        self.buffer.scroll_page(true);
         ActionResult::consumed(true)
     }
     fn move_next_word(&mut self) -> ActionResult {
@@ -251,8 +591,16 @@ impl EditorComponent<'_> {
             .show(TITLE, MESSAGE, Action::Save);
         ActionResult::consumed(true)
     }
-    fn line_number_style() -> Style {
-        Style::default().fg(Color::DarkGray)
+    /// Draws the text area of the current buffer with the styles of the theme.
+    fn apply_theme(&mut self) {
+        let theme = &self.config.theme;
+        let text_area = &mut self.buffer.text_area;
+        text_area.set_style(theme.text);
+        text_area.set_selection_style(theme.selection);
+        text_area.set_search_style(theme.search_matches);
+        if text_area.line_number_style().is_some() {
+            text_area.set_line_number_style(theme.line_numbers);
+        }
     }
     fn toggle_line_number(&mut self) -> ActionResult {
         if self.buffer.text_area.line_number_style().is_some() {
@@ -260,10 +608,23 @@ impl EditorComponent<'_> {
         } else {
             self.buffer
                 .text_area
-                .set_line_number_style(Self::line_number_style());
+                .set_line_number_style(self.config.theme.line_numbers);
         }
         ActionResult::consumed(true)
     }
+    /// Opens the quick switcher, offering the buffers other than the visible one.
+    fn open_quick_switcher(&mut self) -> ActionResult {
+        let current = self.buffer.current_index();
+        let buffers: Vec<PathBuf> = self
+            .buffer
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != current)
+            .filter_map(|(_, buffer)| buffer.file_path.clone())
+            .collect();
+        self.quick_switcher.show(&buffers);
+        ActionResult::consumed(true)
+    }
     fn begin_search(&mut self) -> ActionResult {
         self.search_box_component.toggle();
         ActionResult::consumed(true)
@@ -281,6 +642,10 @@ impl EditorComponent<'_> {
         if res.is_consumed() {
             return res;
         }
+        let res = self.quick_switcher.handle_action(action);
+        if res.is_consumed() {
+            return res;
+        }
         let res = self
             .search_box_component
             .handle_action(action, &mut self.buffer.text_area);
@@ -294,10 +659,12 @@ impl EditorComponent<'_> {
         ActionResult::not_consumed(false)
     }
     pub fn save_to_history(&mut self) {
-        if let Some(path) = self.buffer.file_path.as_ref()
-            && path.is_file()
-        {
-            self.file_history_saver.push_to_history(path);
+        for buffer in self.buffer.iter() {
+            if let Some(path) = buffer.file_path.as_ref()
+                && path.is_file()
+            {
+                self.file_history_saver.push_to_history(path);
+            }
         }
     }
 }
@@ -311,11 +678,19 @@ impl Component for EditorComponent<'_> {
             .register_config(config, &AppComponent::Editor);
         self.search_box_component
             .register_config(config, &AppComponent::Editor);
-        self.help_component
+        self.quick_switcher
             .register_config(config, &AppComponent::Editor);
+        self.notification
+            .register_config(config, &AppComponent::Editor);
+        self.help_component
+            .register_config(config, self.keybinds_id());
+        // Reloading the config keeps the history changes that are not saved yet
+        let data_dir_changed = self.config.config.data_dir != config.config.data_dir;
         self.config = config.clone();
-        self.file_history_saver.load_from_config(config);
-        self.save_to_history();
+        if data_dir_changed {
+            self.file_history_saver.load_from_config(config);
+            self.save_to_history();
+        }
     }
     fn register_action_sender(&mut self, sender: ActionSender) {
         self.action_sender = Some(sender.clone());
@@ -334,6 +709,8 @@ impl Component for EditorComponent<'_> {
             .register_async_action_sender(sender.clone());
         self.help_component
             .register_async_action_sender(sender.clone());
+        self.quick_switcher
+            .register_async_action_sender(sender.clone());
         self.file_dialog.register_async_action_sender(sender);
     }
     fn override_keybind_id(&self, key_event: KeyEvent) -> Option<&AppComponent> {
@@ -343,13 +720,25 @@ impl Component for EditorComponent<'_> {
         if let Some(a) = self.confirm_dialog_component.override_keybind_id(key_event) {
             return Some(a);
         };
-        Some(&AppComponent::Editor)
+        if let Some(a) = self.quick_switcher.override_keybind_id(key_event) {
+            return Some(a);
+        };
+        if self.search_box_component.visible() {
+            return Some(&AppComponent::Editor);
+        }
+        Some(self.keybinds_id())
     }
     fn handle_action(&mut self, action: &Action) -> ActionResult {
+        if action == &Action::Tick {
+            self.read_followed_file();
+        }
         let child = self.child_handle_action(action);
         if child.is_consumed() {
             return child;
         }
+        if self.buffer.read_only && action.is_editing_action() {
+            return self.reject_edit();
+        }
         match action {
             Action::Tick => return self.notification.handle_tick_action(),
             Action::Character(char) => return self.add_char(*char),
@@ -391,6 +780,9 @@ impl Component for EditorComponent<'_> {
                 return self.move_cursor(CursorMove::Down);
             }
             Action::Cancel => {
+                if self.buffer.follow {
+                    return self.toggle_follow();
+                }
                 if self.buffer.text_area.is_selecting() {
                     self.buffer.text_area.cancel_selection();
                     return ActionResult::consumed(true);
@@ -408,24 +800,27 @@ impl Component for EditorComponent<'_> {
             Action::SelectAll => return self.select_all(),
             Action::Save => return self.handle_save_file(),
             Action::SaveTo => return self.handle_save_to(),
+            Action::SaveUncompressed => return self.save_uncompressed(),
             Action::Redo => {
                 if self.buffer.text_area.redo() {
+                    self.buffer.modified = true;
                     return ActionResult::consumed(true);
                 }
             }
             Action::Undo => {
                 if self.buffer.text_area.undo() {
+                    self.buffer.modified = true;
                     return ActionResult::consumed(true);
                 }
             }
-            Action::Return => {
-                let _ = self
-                    .task_result_sender
-                    .as_ref()
-                    .unwrap()
-                    .send(AsyncAction::Navigate(None));
-            }
+            Action::Return => return self.handle_return(),
+            Action::ConfirmReturn => return self.close_buffer(),
+            Action::NextBuffer => return self.next_buffer(),
+            Action::CycleRebaseVerb => return self.cycle_rebase_verb(),
+            Action::Follow => return self.toggle_follow(),
+            Action::PreviousBuffer => return self.previous_buffer(),
             Action::OpenFile => return self.open_file_dialog(SelectorType::PickFile),
+            Action::QuickSwitch => return self.open_quick_switcher(),
             Action::PageUp => return self.page_up(),
             Action::PageDown => return self.page_down(),
             Action::EndOfWord => return self.move_next_word(),
@@ -440,30 +835,82 @@ impl Component for EditorComponent<'_> {
         if f.is_consumed() {
             return f;
         }
+        let f = self.quick_switcher.handle_async_action(action);
+        if f.is_consumed() {
+            return f;
+        }
         match action {
-            AsyncAction::LoadFileContents(string) => {
-                return self.load_file_contents(string.clone());
+            AsyncAction::LoadFileContents(path, string, codec) => {
+                return self.load_file_contents(path, string.clone(), *codec);
+            }
+            AsyncAction::AppendFileContents(path, length, text) => {
+                return self.append_file_contents(path, *length, text);
             }
             AsyncAction::SavedFile(result) => return self.handle_file_saved(result),
             AsyncAction::Error(msg) => {
                 self.notification.notify_error(msg);
                 return ActionResult::consumed(true);
             }
+            AsyncAction::FileError(path, msg) => return self.handle_file_error(path, msg),
             AsyncAction::SelectPath(path, selector) => {
                 return self.handle_selector(path.clone(), *selector);
             }
+            AsyncAction::SelectPaths(paths) => {
+                for path in paths {
+                    self.open_file(path.clone());
+                }
+                return ActionResult::consumed(true);
+            }
             _ => {}
         }
         Default::default()
     }
     fn init(&mut self) {
-        self.load_file();
+        self.load_all_files();
+        if let Some(directory) = self.starting_directory.take() {
+            self.file_dialog.show(directory, SelectorType::PickFile);
+        }
+    }
+    fn exit(&mut self) {
+        self.send_stdout_contents();
+        let session = self.session();
+        for position in &session.buffers {
+            self.file_history_saver.save_position(position);
+        }
+        let session = AsyncAction::SessionState(session);
+        let _ = self.task_result_sender.as_ref().unwrap().send(session);
+        let unsaved = self.buffer.iter().any(|b| b.modified);
+        let exit_code = if unsaved { EXIT_UNSAVED } else { 0 };
+        let _ = self
+            .task_result_sender
+            .as_ref()
+            .unwrap()
+            .send(AsyncAction::ExitCode(exit_code));
     }
     fn render(&mut self, frame: &mut Frame, area: Rect) {
-        let file_title = format!(" {} ", self.buffer.file_name());
+        let mut file_title = format!(" {}", self.buffer.file_name());
+        if self.buffer.len() > 1 {
+            let index = self.buffer.current_index() + 1;
+            file_title += &format!(" [{index}/{}]", self.buffer.len());
+        }
+        if let Some(codec) = self.buffer.codec {
+            file_title += &format!(" [{codec}]");
+        }
+        if self.buffer.read_only {
+            file_title += " [read-only]";
+        }
+        file_title += " ";
         let file_title = Line::from(file_title).centered();
-        let mut block = default_block().title_top(file_title);
-        let mode_title = if self.insert { " Insert " } else { " Normal " };
+        let mut block = default_block(&self.config.theme).title_top(file_title);
+        let mode_title = if self.buffer.follow {
+            " Following "
+        } else if self.pager {
+            " Pager "
+        } else if self.insert {
+            " Insert "
+        } else {
+            " Normal "
+        };
         let help_title = format!(" [{}] Help ", self.help_component.help_key());
         let help_title = Line::from(help_title).right_aligned();
         let mode_title = Line::raw(mode_title).left_aligned();
@@ -485,17 +932,33 @@ impl Component for EditorComponent<'_> {
         let [block_area] = Layout::default()
             .constraints([Constraint::Fill(1)])
             .areas(block_area);
-        if self.loading {
+        if self.buffer.loading {
             let area = center(block_area);
             let loader = Throbber::default().throbber_set(BRAILLE_SIX_DOUBLE);
             frame.render_widget(loader, area);
         } else {
+            if let Some(position) = self.buffer.restored_position.take() {
+                self.restore_position(frame, block_area, position);
+            }
+            self.apply_theme();
+            self.buffer.update_viewport(block_area);
             frame.render_widget(&self.buffer.text_area, block_area);
+            if let Some(git_file) = self.buffer.git_file {
+                git_file.decorate(
+                    frame.buffer_mut(),
+                    block_area,
+                    self.buffer.text_area.lines(),
+                    self.buffer.viewport,
+                    self.buffer.gutter_width(),
+                    &self.config.theme,
+                );
+            }
         }
         self.help_component.render(frame, block_area);
         self.search_box_component.render(frame, block_area);
         self.notification.render(frame, block_area);
         self.file_dialog.render(frame, area);
+        self.quick_switcher.render(frame, area);
         self.confirm_dialog_component.render(frame, block_area);
     }
 }
@@ -0,0 +1,94 @@
+use crate::config::theme::Theme;
+use ratatui::buffer::Buffer as FrameBuffer;
+use ratatui::layout::Rect;
+use ratatui::style::Modifier;
+use std::path::Path;
+
+/// Columns marked by the ruler in commit messages: the subject and the body line limits.
+const COMMIT_RULER_COLUMNS: [u16; 2] = [50, 72];
+const COMMIT_MESSAGE_FILES: [&str; 4] = [
+    "COMMIT_EDITMSG",
+    "MERGE_MSG",
+    "TAG_EDITMSG",
+    "EDIT_DESCRIPTION",
+];
+const REBASE_TODO_FILE: &str = "git-rebase-todo";
+/// Rebase verbs, in the order they are cycled through.
+const REBASE_VERBS: [&str; 6] = ["pick", "reword", "edit", "squash", "fixup", "drop"];
+const COMMENT_PREFIX: char = '#';
+
+/// Files git asks an editor to open, which get some extra help while editing.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(super) enum GitFile {
+    CommitMessage,
+    RebaseTodo,
+}
+
+impl GitFile {
+    pub(super) fn detect(path: &Path) -> Option<Self> {
+        let file_name = path.file_name()?.to_str()?;
+        if COMMIT_MESSAGE_FILES.contains(&file_name) {
+            Some(Self::CommitMessage)
+        } else if file_name == REBASE_TODO_FILE {
+            Some(Self::RebaseTodo)
+        } else {
+            None
+        }
+    }
+    /// Draws the column ruler and dims comment lines of the text already rendered in `area`.
+    ///
+    /// `lines` are the buffer lines, `viewport` the first visible row and column and
+    /// `gutter` the width taken by line numbers.
+    pub(super) fn decorate(
+        &self,
+        buf: &mut FrameBuffer,
+        area: Rect,
+        lines: &[String],
+        viewport: (u16, u16),
+        gutter: u16,
+        theme: &Theme,
+    ) {
+        let (top_row, top_col) = viewport;
+        for y in 0..area.height {
+            let Some(line) = lines.get((top_row + y) as usize) else {
+                break;
+            };
+            let row_y = area.y + y;
+            if line.starts_with(COMMENT_PREFIX) {
+                for x in area.x + gutter..area.right() {
+                    buf[(x, row_y)].set_style(theme.comment);
+                }
+                continue;
+            }
+            if *self != Self::CommitMessage {
+                continue;
+            }
+            for column in COMMIT_RULER_COLUMNS {
+                let Some(x) = (column + gutter + area.x).checked_sub(top_col) else {
+                    continue;
+                };
+                if x >= area.x + gutter && x < area.right() {
+                    let cell = &mut buf[(x, row_y)];
+                    cell.set_style(theme.ruler);
+                    if !cell.symbol().trim().is_empty() {
+                        cell.modifier.insert(Modifier::BOLD);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Replaces the verb at the start of a rebase todo line with the next one.
+///
+/// Returns `None` if the line does not start with a rebase verb.
+pub(super) fn cycle_rebase_verb(line: &str) -> Option<String> {
+    let verb = line.split_whitespace().next()?;
+    let index = REBASE_VERBS
+        .iter()
+        .position(|v| *v == verb || (verb.len() == 1 && v.starts_with(verb)))?;
+    let next = REBASE_VERBS[(index + 1) % REBASE_VERBS.len()];
+    let start = line.find(verb)?;
+    let rest = &line[start + verb.len()..];
+    Some(format!("{}{next}{rest}", &line[..start]))
+}
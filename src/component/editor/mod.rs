@@ -1,3 +1,6 @@
+pub mod batch;
 mod buffer;
 pub mod component;
+mod git;
+pub mod options;
 mod search_box;
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// How the editor should be opened when launched from the command line.
+#[derive(Clone, Default, Debug, Deserialize, Eq, PartialEq, Hash, Serialize)]
+pub struct EditorOptions {
+    /// Files opened in their own buffers
+    pub files: Vec<String>,
+    /// Directory where the file selector is opened once the editor starts
+    pub directory: Option<String>,
+    /// Text read from stdin, loaded into an unnamed buffer before the files
+    pub stdin: Option<String>,
+    /// Writes the first buffer to stdout when saving or quitting, instead of a file
+    pub stdout: bool,
+    /// Quits the application when the last buffer is closed, instead of returning home
+    pub quit_on_close: bool,
+    /// Opens the buffers read-only, browsed with the pager keybindings
+    pub pager: bool,
+    /// Opens every buffer read-only, so they can only be saved elsewhere
+    pub read_only: bool,
+}
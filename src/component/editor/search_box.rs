@@ -2,11 +2,11 @@ use crate::action::{Action, ActionResult, AsyncActionSender};
 use crate::component::component_utils::default_block;
 use crate::component::effect_runner::EffectRunner;
 use crate::component::{AppComponent, Component};
-use crate::config::effects::floating_component_enter_effect;
+use crate::config::effects::{floating_component_enter_effect, AnimationEvent};
 use crate::config::keybindings::key_event_to_string;
+use crate::config::theme::Theme;
 use crate::config::Config;
 use ratatui::layout::{Constraint, Direction, Flex, Layout, Rect};
-use ratatui::style::{Color, Style};
 use ratatui::text::Line;
 use ratatui::widgets::{Block, Clear};
 use ratatui::Frame;
@@ -20,6 +20,7 @@ pub(super) struct SearchBoxComponent<'a> {
     effect_runner: EffectRunner,
     up_key: String,
     down_key: String,
+    theme: Theme,
 }
 
 impl<'a> SearchBoxComponent<'a> {
@@ -30,7 +31,7 @@ impl<'a> SearchBoxComponent<'a> {
             let text_area = TextArea::default();
             self.text_area = Some(text_area);
             self.effect_runner
-                .add_effect(floating_component_enter_effect());
+                .add_effect(AnimationEvent::Popup, floating_component_enter_effect());
             self.update_text_area_placeholder();
         }
     }
@@ -45,6 +46,7 @@ impl<'a> SearchBoxComponent<'a> {
             return;
         }
         let block = self.search_block();
+        let error_style = self.theme.error;
         let search_text_area = self.text_area.as_mut().unwrap();
         let search = &search_text_area.lines()[0];
         let search = if self.regex {
@@ -55,7 +57,7 @@ impl<'a> SearchBoxComponent<'a> {
         let r = search_area.set_search_pattern(search);
         self.error = r.is_err();
         if self.error {
-            search_text_area.set_block(block.style(Color::Red))
+            search_text_area.set_block(block.style(error_style))
         } else {
             search_text_area.set_block(block)
         }
@@ -67,10 +69,11 @@ impl<'a> SearchBoxComponent<'a> {
     }
     fn update_text_area_placeholder(&mut self) {
         let block = self.search_block();
+        let placeholder_style = self.theme.muted;
         let text_area = self.text_area.as_mut().unwrap();
         let placeholder = if self.regex { "Regex" } else { "Text" };
         text_area.set_placeholder_text(placeholder);
-        text_area.set_placeholder_style(Style::new().fg(Color::DarkGray));
+        text_area.set_placeholder_style(placeholder_style);
         text_area.set_block(block);
     }
     fn search_block(&self) -> Block<'static> {
@@ -78,7 +81,9 @@ impl<'a> SearchBoxComponent<'a> {
         let line = Line::raw(TITLE).left_aligned();
         let actions_title = format!(" {} {} select ", self.up_key, self.down_key);
         let actions_title = Line::raw(actions_title).right_aligned();
-        default_block().title_top(line).title_bottom(actions_title)
+        default_block(&self.theme)
+            .title_top(line)
+            .title_bottom(actions_title)
     }
     fn start_selection(&mut self) -> ActionResult {
         let text_area = self.text_area.as_mut().unwrap();
@@ -196,6 +201,8 @@ impl Component for SearchBoxComponent<'_> {
             .unwrap_or_default();
         self.up_key = up_key;
         self.down_key = down_key;
+        self.theme = config.theme.clone();
+        self.effect_runner.register_config(config, parent_comp);
     }
     fn register_async_action_sender(&mut self, sender: AsyncActionSender) {
         self.effect_runner.register_async_action_sender(sender)
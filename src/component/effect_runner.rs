@@ -1,7 +1,11 @@
 use crate::action::{AsyncAction, AsyncActionSender};
-use crate::component::Component;
+use crate::component::{AppComponent, Component};
+use crate::config::app_config::AnimationsConfig;
+use crate::config::effects::{configured_effect, AnimationEvent};
+use crate::config::Config;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
+use ratatui::style::Color;
 use ratatui::Frame;
 use tachyonfx::{Effect, EffectManager};
 use tokio::time::Instant;
@@ -14,6 +18,9 @@ pub struct EffectRunner {
     last_frame: Instant,
     async_sender: Option<AsyncActionSender>,
     running: bool,
+    animations: AnimationsConfig,
+    /// Color effects fade from and to
+    background: Color,
 }
 
 impl Default for EffectRunner {
@@ -23,6 +30,8 @@ impl Default for EffectRunner {
             last_frame: Instant::now(),
             async_sender: None,
             running: false,
+            animations: AnimationsConfig::default(),
+            background: Color::Reset,
         }
     }
 }
@@ -31,19 +40,28 @@ impl EffectRunner {
     pub fn is_running(&self) -> bool {
         self.running
     }
-    pub fn add_effect(&mut self, effect: Effect) {
+    /// Plays the effect configured for `event`, which is `effect` unless another one is.
+    ///
+    /// Returns whether an effect is playing, which is not the case when the event is not
+    /// animated.
+    pub fn add_effect(&mut self, event: AnimationEvent, effect: Effect) -> bool {
+        let Some(effect) = configured_effect(&self.animations, event, effect, self.background)
+        else {
+            return false;
+        };
         self.effect_manager.add_effect(effect);
-        let _ = self
-            .async_sender
-            .as_ref()
-            .expect(SENDER_MISSING_ERROR_MSG)
-            .send(AsyncAction::StartAnimation);
+        let sender = self.async_sender.as_ref().expect(SENDER_MISSING_ERROR_MSG);
         if !self.running {
+            let _ = sender.send(AsyncAction::StartAnimation);
             self.last_frame = Instant::now();
         }
         self.running = true;
+        true
     }
     pub fn cancel(&mut self) {
+        if !self.running {
+            return;
+        }
         self.running = false;
         let sender = self.async_sender.as_ref().expect(SENDER_MISSING_ERROR_MSG);
         let _ = sender.send(AsyncAction::StopAnimation);
@@ -56,7 +74,7 @@ impl EffectRunner {
         if !self.running {
             return false;
         }
-        let elapsed = self.last_frame.elapsed();
+        let elapsed = self.last_frame.elapsed().mul_f32(self.animations.speed);
         self.last_frame = Instant::now();
         self.effect_manager
             .process_effects(elapsed.into(), buffer, area);
@@ -70,6 +88,11 @@ impl EffectRunner {
 }
 
 impl Component for EffectRunner {
+    fn register_config(&mut self, config: &Config, parent_comp: &AppComponent) {
+        let _ = parent_comp;
+        self.animations = config.config.animations.clone();
+        self.background = config.theme.background.bg.unwrap_or(Color::Reset);
+    }
     fn register_async_action_sender(&mut self, sender: AsyncActionSender) {
         self.async_sender = Some(sender);
     }
@@ -77,3 +100,13 @@ impl Component for EffectRunner {
         self.process(frame.buffer_mut(), area);
     }
 }
+
+impl Drop for EffectRunner {
+    fn drop(&mut self) {
+        if self.running
+            && let Some(sender) = &self.async_sender
+        {
+            let _ = sender.send(AsyncAction::StopAnimation);
+        }
+    }
+}
@@ -1,35 +1,113 @@
 pub(crate) use crate::action::{
-    Action, ActionResult, AsyncAction, AsyncActionSender, SelectorType,
+    Action, ActionResult, ActionSender, AsyncAction, AsyncActionSender, SelectorType,
 };
-use crate::component::component_utils::{center_horizontally, center_vertically, default_block};
+use crate::archive::{is_archive, split_archive_path, ArchiveListing};
+use crate::component::component_utils::{
+    center_horizontally, center_vertically, default_block, key_label_format,
+};
+use crate::component::confirm_dialog::ConfirmDialogComponent;
 use crate::component::effect_runner::EffectRunner;
-use crate::component::file_selector::input::FileSelectorInput;
+use crate::component::file_selector::file_finder::FileFinder;
+use crate::component::file_selector::file_operations::{
+    move_to_trash, FileOperation, OperationPrompt,
+};
+use crate::component::file_selector::input::{resolve_typed_path, FileSelectorInput};
+use crate::component::file_selector::places::Places;
+use crate::component::file_selector::sort::sort_children;
 use crate::component::file_selector::{render_preview_if_able, PathChild, HIGHLIGHT_SYMBOL};
 use crate::component::preview_component::PreviewComponent;
 use crate::component::{AppComponent, Component};
-use crate::config::effects::dialog_enter;
+use crate::config::app_config::{Column, FileSelectorConfig, SortKey};
+use crate::config::effects::{dialog_enter, AnimationEvent};
+use crate::config::theme::Theme;
+use crate::config::Config;
 use crossterm::event::KeyEvent;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Text};
 use ratatui::widgets::{Clear, HighlightSpacing, List, ListDirection, ListState};
 use ratatui::Frame;
 use std::path::{Path, PathBuf};
 use tui_textarea::CursorMove;
 
+/// Width of the places panel
+const PLACES_WIDTH: u16 = 24;
+/// Narrowest list next to which the places panel is shown without being focused
+const MIN_WIDTH_FOR_PLACES: u16 = 64;
+
 /// A file selector component. Shows a list of all contents inside `current_path` and allows the
 /// user to change directories or select files through it.
+///
+/// When picking a file, it can also find files anywhere under `current_path`, fuzzy matching
+/// them against the filter.
+///
+/// A panel on its side lists places to jump to, like bookmarked folders and the folders files
+/// were recently picked from.
+///
+/// Folders are listed before files, sorted by `sort_key`, and can show details like the size of
+/// the files next to their names.
+///
+/// Files and folders can also be created, renamed, duplicated, moved or sent to the trash from
+/// it. Anything that would remove a file is confirmed first.
+///
+/// Archives are browsed like folders when picking a file. Their entries can be previewed,
+/// opened and extracted, but not changed.
 #[derive(Default)]
 pub struct FileSelectorComponent<'a> {
     action_sender: Option<AsyncActionSender>,
     current_path: PathBuf,
     children: Vec<PathChild>,
     filtered_paths: Option<Vec<PathChild>>,
+    finder: Option<FileFinder>,
     input: FileSelectorInput<'a>,
     visible: bool,
     list_state: ListState,
     preview_component: PreviewComponent,
+    /// Entries of the archive browsed last, so moving between its folders reads it once
+    archive_listing: Option<ArchiveListing>,
     effect_runner: EffectRunner,
+    /// Asks for the name of the file an operation creates
+    prompt: Option<OperationPrompt<'a>>,
+    /// Entries marked to be opened or operated on together, in any directory
+    marked: Vec<PathBuf>,
+    /// Operation waiting to be confirmed, with the path typed for it
+    pending_operation: Option<(FileOperation, PathBuf)>,
+    confirm_dialog: ConfirmDialogComponent,
+    /// Why the last operation failed
+    error: Option<String>,
+    sort_key: SortKey,
+    show_hidden: bool,
+    show_columns: bool,
+    columns: Vec<Column>,
+    /// File selector settings of the config registered last
+    settings: Option<FileSelectorConfig>,
+    places: Places,
+    keybinds: FileSelectorKeybinds,
+    theme: Theme,
+}
+
+#[derive(Default)]
+struct FileSelectorKeybinds {
+    new_file: String,
+    new_folder: String,
+    rename: String,
+    duplicate: String,
+    move_to: String,
+    trash: String,
+    extract: String,
+}
+
+impl FileSelectorKeybinds {
+    fn register_keybinds(&mut self, config: &Config) {
+        let keybinds = &config.keybindings;
+        let app_component = &AppComponent::FileDialog;
+        self.new_file = keybinds.get_key_string_or_default(Action::NewFile, app_component);
+        self.new_folder = keybinds.get_key_string_or_default(Action::NewFolder, app_component);
+        self.rename = keybinds.get_key_string_or_default(Action::Rename, app_component);
+        self.duplicate = keybinds.get_key_string_or_default(Action::Duplicate, app_component);
+        self.move_to = keybinds.get_key_string_or_default(Action::Move, app_component);
+        self.trash = keybinds.get_key_string_or_default(Action::Trash, app_component);
+        self.extract = keybinds.get_key_string_or_default(Action::Extract, app_component);
+    }
 }
 
 impl FileSelectorComponent<'_> {
@@ -37,72 +115,127 @@ impl FileSelectorComponent<'_> {
         self.input.change_type(selector_type);
         self.visible = true;
         self.select_dir(dir);
-        self.effect_runner
-            .add_effect(dialog_enter(Color::from_u32(0x1d2021)));
+        let effect = dialog_enter(self.theme.dialog_color());
+        self.effect_runner.add_effect(AnimationEvent::Dialog, effect);
     }
     pub fn select_dir<P: AsRef<Path>>(&mut self, dir: P) {
         let dir_path = dir.as_ref();
-        let Ok(read_dir) = dir_path.read_dir() else {
+        let children = match split_archive_path(dir_path) {
+            Some((archive, inner)) => self.list_archive_dir(archive, inner),
+            None => self.list_dir(dir_path),
+        };
+        let Some(children) = children else {
             return;
         };
         self.preview_component.change_dir(None);
         self.list_state.select(None);
+        self.finder = None;
         self.children.clear();
         self.children.push(PathChild::MoveUp);
+        self.children.extend(children);
         self.current_path = dir_path.to_path_buf();
+        sort_children(&mut self.children, self.sort_key);
+        self.refresh_filtered_items();
+    }
+    fn list_dir(&self, dir: &Path) -> Option<Vec<PathChild>> {
+        let read_dir = dir.read_dir().ok()?;
+        let mut children = Vec::new();
         for entry in read_dir.flatten() {
             let path = entry.path();
             let name = entry.file_name().to_string_lossy().to_string();
+            if !self.show_hidden && name.starts_with('.') {
+                continue;
+            }
             let c = if path.is_dir() {
-                PathChild::Folder(name)
+                PathChild::folder(name)
             } else if self.input.selector_type().show_files() {
-                PathChild::file(name, path)
+                PathChild::file(name, path.clone())
             } else {
                 continue;
             };
-            self.children.push(c);
+            let c = match path.metadata().or_else(|_| entry.metadata()) {
+                Ok(metadata) => c.with_metadata(&metadata),
+                Err(_) => c,
+            };
+            children.push(c);
         }
-        self.refresh_filtered_items();
+        Some(children)
+    }
+    fn list_archive_dir(&mut self, archive: &Path, dir: &Path) -> Option<Vec<PathChild>> {
+        let listing = match self.archive_listing.take() {
+            Some(listing) if listing.is_current(archive) => listing,
+            _ => ArchiveListing::read(archive).ok()?,
+        };
+        let entries = self.archive_listing.insert(listing).list_dir(dir);
+        let mut children = Vec::new();
+        for entry in entries {
+            let name = entry.path.file_name()?.to_string_lossy().to_string();
+            if !self.show_hidden && name.starts_with('.') {
+                continue;
+            }
+            let c = if entry.is_dir {
+                PathChild::folder(name)
+            } else if self.input.selector_type().show_files() {
+                PathChild::file(name, entry.path.clone())
+            } else {
+                continue;
+            };
+            children.push(c.with_metadata(&entry));
+        }
+        Some(children)
+    }
+    /// Whether the current folder is inside an archive.
+    fn in_archive(&self) -> bool {
+        split_archive_path(&self.current_path).is_some()
     }
     pub fn hide(&mut self) {
         self.visible = false;
+        self.finder = None;
+        self.prompt = None;
+        self.pending_operation = None;
+        self.archive_listing = None;
+        self.marked.clear();
+        self.error = None;
         self.input.clear();
         self.list_state = ListState::default();
     }
     fn child_path(&self, index: usize) -> Option<PathBuf> {
-        let child = self.children.get(index)?;
-        let path = match child {
-            PathChild::File {
-                full_file_name,
-                icon: _,
-            } => self.current_path.join(full_file_name),
-            PathChild::Folder(f) => self.current_path.join(f),
-            PathChild::MoveUp => return None,
-        };
-        Some(path)
+        match self.active_list().get(index)? {
+            PathChild::MoveUp => None,
+            child => Some(self.current_path.join(child.name())),
+        }
     }
     fn select(&mut self, folder: bool) -> ActionResult {
         let Some(index) = self.list_state.selected() else {
             let path = if let Some(text_area) = self.input.current_input() {
-                self.current_path.join(text_area)
+                resolve_typed_path(&self.current_path, &text_area)
             } else {
                 self.current_path.clone()
             };
+            if self.input.selector_type() == SelectorType::PickFile && !self.marked.is_empty() {
+                return self.open_marked(None);
+            }
+            self.visit_current_dir();
             let sender = self.action_sender.as_ref().unwrap();
             let _ = sender.send(AsyncAction::SelectPath(path, self.input.selector_type()));
             return ActionResult::consumed(true);
         };
-        let Some(child) = self.children.get(index) else {
+        let Some(child) = self.active_list().get(index) else {
             return ActionResult::consumed(false);
         };
         let can_pick_folder = folder && self.input.selector_type().can_pick_folder();
         let path = match child {
-            PathChild::File {
-                full_file_name,
-                icon: _,
-            } => self.current_path.join(full_file_name),
-            PathChild::Folder(f) => {
-                let path = self.current_path.join(f);
+            PathChild::File { full_file_name, .. } => {
+                let path = self.current_path.join(full_file_name);
+                let picking_file = self.input.selector_type() == SelectorType::PickFile;
+                if picking_file && is_archive(&path) && path.is_file() {
+                    self.select_dir(path);
+                    return ActionResult::consumed(true);
+                }
+                path
+            }
+            PathChild::Folder { name, .. } => {
+                let path = self.current_path.join(name);
                 if !can_pick_folder {
                     self.select_dir(path);
                     return ActionResult::consumed(true);
@@ -115,12 +248,30 @@ impl FileSelectorComponent<'_> {
                 return ActionResult::consumed(true);
             }
         };
+        if self.input.selector_type() == SelectorType::PickFile && !self.marked.is_empty() {
+            return self.open_marked(Some(path));
+        }
+        self.visit_current_dir();
         self.hide();
         let sender = self.action_sender.as_ref().unwrap();
         let _ = sender.send(AsyncAction::SelectPath(path, self.input.selector_type()));
         ActionResult::consumed(true)
     }
+    /// Remembers the current folder as recently visited, telling why if it cannot be saved.
+    fn visit_current_dir(&mut self) {
+        if let Err(e) = self.places.visit(&self.current_path) {
+            let msg = format!("Unable to remember the folder: {e}");
+            let sender = self.action_sender.as_ref().unwrap();
+            let _ = sender.send(AsyncAction::Error(msg));
+        }
+    }
     fn refresh_filtered_items(&mut self) -> ActionResult {
+        if let Some(finder) = self.finder.as_mut() {
+            finder.set_filter(self.input.current_filter());
+            self.filtered_paths = Some(finder.matches());
+            self.keep_selection_in_bounds();
+            return ActionResult::consumed(true);
+        }
         self.filtered_paths = None;
         let Some(filter) = self.input.current_filter() else {
             return ActionResult::consumed(false);
@@ -137,6 +288,7 @@ impl FileSelectorComponent<'_> {
     }
     fn handle_character(&mut self, character: char) -> ActionResult {
         if self.input.handle_character(character) {
+            self.follow_typed_path();
             self.refresh_filtered_items();
             return ActionResult::consumed(true);
         }
@@ -144,6 +296,7 @@ impl FileSelectorComponent<'_> {
     }
     fn handle_backspace(&mut self) -> ActionResult {
         if self.input.backspace() {
+            self.follow_typed_path();
             self.refresh_filtered_items();
             return ActionResult::consumed(true);
         }
@@ -151,11 +304,113 @@ impl FileSelectorComponent<'_> {
     }
     fn handle_delete(&mut self) -> ActionResult {
         if self.input.delete() {
+            self.follow_typed_path();
             self.refresh_filtered_items();
             return ActionResult::consumed(true);
         }
         Default::default()
     }
+    fn keep_selection_in_bounds(&mut self) {
+        let len = self.active_list().len();
+        match self.list_state.selected() {
+            Some(_) if len == 0 => {
+                self.preview_component.change_dir(None);
+                self.list_state.select(None);
+            }
+            Some(index) if index >= len => {
+                self.update_preview(len - 1);
+                self.list_state.select(Some(len - 1));
+            }
+            _ => {}
+        }
+    }
+    /// Lists the directory the typed path is in once it crosses a directory boundary, keeping
+    /// only the rest of the path in the input.
+    fn follow_typed_path(&mut self) {
+        if self.finder.is_some() {
+            return;
+        }
+        let Some(text) = self.input.current_text() else {
+            return;
+        };
+        let Some(slash) = text.rfind('/') else {
+            return;
+        };
+        let (dir, rest) = text.split_at(slash + 1);
+        let dir = resolve_typed_path(&self.current_path, dir);
+        if dir.is_dir() {
+            let rest = rest.to_string();
+            self.input.set_text(&rest);
+            self.select_dir(dir);
+        }
+    }
+    /// Completes the typed name against the listed entries, as far as they all agree. The
+    /// entries it could be completed to are listed when there is more than one.
+    fn complete(&mut self) -> ActionResult {
+        if self.finder.is_some() {
+            return ActionResult::consumed(false);
+        }
+        let Some(text) = self.input.current_text() else {
+            return ActionResult::consumed(false);
+        };
+        if matches!(text, "~" | "..") {
+            let text = format!("{text}/");
+            self.input.set_text(&text);
+            self.follow_typed_path();
+            self.refresh_filtered_items();
+            return ActionResult::consumed(true);
+        }
+        let lowercase = text.to_lowercase();
+        let candidates: Vec<PathChild> = self
+            .children
+            .iter()
+            .filter(|child| !matches!(child, PathChild::MoveUp))
+            .filter(|child| child.name().to_lowercase().starts_with(&lowercase))
+            .cloned()
+            .collect();
+        let completed = match candidates.as_slice() {
+            [] => return ActionResult::consumed(false),
+            [PathChild::Folder { name, .. }] => format!("{name}/"),
+            [candidate] => candidate.name().to_string(),
+            [first, rest @ ..] => rest.iter().fold(first.name().to_string(), |prefix, child| {
+                common_prefix(&prefix, child.name()).to_string()
+            }),
+        };
+        if completed.len() > text.len() {
+            self.input.set_text(&completed);
+            self.follow_typed_path();
+            self.refresh_filtered_items();
+        }
+        if candidates.len() > 1 {
+            self.preview_component.change_dir(None);
+            self.list_state.select(None);
+            self.filtered_paths = Some(candidates);
+        }
+        ActionResult::consumed(true)
+    }
+    /// Switches between listing `current_path` and finding files anywhere under it.
+    fn toggle_finder(&mut self) -> ActionResult {
+        if self.input.selector_type() != SelectorType::PickFile {
+            return ActionResult::consumed(false);
+        }
+        self.preview_component.change_dir(None);
+        self.list_state.select(None);
+        if self.finder.take().is_none() {
+            let sender = self.action_sender.clone().unwrap();
+            self.finder = Some(FileFinder::start(self.current_path.clone(), sender));
+        }
+        self.refresh_filtered_items();
+        ActionResult::consumed(true)
+    }
+    fn add_found_files(&mut self, root: &Path, files: &[PathBuf]) -> ActionResult {
+        let Some(finder) = self.finder.as_mut() else {
+            return ActionResult::not_consumed(false);
+        };
+        if !finder.add_files(root, files) {
+            return ActionResult::not_consumed(false);
+        }
+        self.refresh_filtered_items()
+    }
     fn active_list(&self) -> &Vec<PathChild> {
         self.filtered_paths.as_ref().unwrap_or(&self.children)
     }
@@ -208,6 +463,268 @@ impl FileSelectorComponent<'_> {
         }
         ActionResult::default()
     }
+    /// Keys of the operations available on what is marked or selected.
+    fn operation_hints(&self) -> Vec<String> {
+        let keybinds = &self.keybinds;
+        if self.in_archive() {
+            vec![key_label_format(&keybinds.extract, "Extract")]
+        } else if !self.marked.is_empty() {
+            vec![
+                key_label_format(&keybinds.duplicate, "Copy"),
+                key_label_format(&keybinds.move_to, "Move"),
+                key_label_format(&keybinds.trash, "Trash"),
+            ]
+        } else if self.selected_path().is_some() {
+            vec![
+                key_label_format(&keybinds.rename, "Rename"),
+                key_label_format(&keybinds.duplicate, "Duplicate"),
+                key_label_format(&keybinds.move_to, "Move"),
+                key_label_format(&keybinds.trash, "Trash"),
+            ]
+        } else {
+            vec![
+                key_label_format(&keybinds.new_file, "New file"),
+                key_label_format(&keybinds.new_folder, "New folder"),
+            ]
+        }
+    }
+    fn selected_path(&self) -> Option<PathBuf> {
+        self.child_path(self.list_state.selected()?)
+    }
+    /// The marked entries, or the selected one if none are marked.
+    fn operation_sources(&self) -> Option<Vec<PathBuf>> {
+        if !self.marked.is_empty() {
+            return Some(self.marked.clone());
+        }
+        Some(vec![self.selected_path()?])
+    }
+    fn toggle_mark(&mut self) -> ActionResult {
+        let Some(path) = self.selected_path() else {
+            return ActionResult::consumed(false);
+        };
+        match self.marked.iter().position(|marked| marked == &path) {
+            Some(index) => {
+                self.marked.remove(index);
+            }
+            None => self.marked.push(path),
+        }
+        self.move_cursor_down();
+        ActionResult::consumed(true)
+    }
+    /// Opens the marked files together with the selected one, leaving out marked folders.
+    /// Does nothing if only folders are marked.
+    fn open_marked(&mut self, selected: Option<PathBuf>) -> ActionResult {
+        let mut paths: Vec<PathBuf> = self
+            .marked
+            .iter()
+            .filter(|path| !path.is_dir())
+            .cloned()
+            .collect();
+        if let Some(selected) = selected
+            && !paths.contains(&selected)
+        {
+            paths.push(selected);
+        }
+        if paths.is_empty() {
+            return ActionResult::consumed(false);
+        }
+        self.visit_current_dir();
+        self.hide();
+        let sender = self.action_sender.as_ref().unwrap();
+        let _ = sender.send(AsyncAction::SelectPaths(paths));
+        ActionResult::consumed(true)
+    }
+    /// Asks where the operation should put the marked or selected entries. Does nothing if
+    /// there are none.
+    fn open_prompt<F>(&mut self, operation: F) -> ActionResult
+    where
+        F: FnOnce(Vec<PathBuf>) -> FileOperation,
+    {
+        let Some(sources) = self.operation_sources() else {
+            return ActionResult::consumed(false);
+        };
+        self.show_prompt(operation(sources))
+    }
+    fn open_rename_prompt(&mut self) -> ActionResult {
+        let Some(path) = self.selected_path() else {
+            return ActionResult::consumed(false);
+        };
+        self.show_prompt(FileOperation::Rename(path))
+    }
+    fn show_prompt(&mut self, operation: FileOperation) -> ActionResult {
+        self.error = None;
+        self.prompt = Some(OperationPrompt::new(
+            operation,
+            &self.current_path,
+            &self.theme,
+        ));
+        ActionResult::consumed(true)
+    }
+    fn confirm_trash(&mut self) -> ActionResult {
+        let Some(sources) = self.operation_sources() else {
+            return ActionResult::consumed(false);
+        };
+        let message = match sources.as_slice() {
+            [path] => {
+                let name = path.file_name().unwrap_or_default().display();
+                format!("Move {name} to the trash?")
+            }
+            _ => format!("Move {} items to the trash?", sources.len()),
+        };
+        self.confirm_dialog
+            .show(" Delete ", &message, Action::ConfirmFileOperation);
+        let target = self.current_path.clone();
+        self.pending_operation = Some((FileOperation::Trash(sources), target));
+        ActionResult::consumed(true)
+    }
+    /// Applies the operation of the prompt, asking first if it would replace existing files.
+    fn confirm_prompt(&mut self) -> ActionResult {
+        let Some(prompt) = self.prompt.take() else {
+            return ActionResult::consumed(false);
+        };
+        if prompt.input().is_empty() {
+            return ActionResult::consumed(true);
+        }
+        let target = resolve_typed_path(&self.current_path, prompt.input());
+        if let Err(e) = prompt.operation.validate(&target) {
+            self.error = Some(e.to_string());
+            return ActionResult::consumed(true);
+        }
+        let destinations = prompt.operation.destinations(&target);
+        let existing: Vec<&PathBuf> = destinations
+            .iter()
+            .filter(|destination| destination.symlink_metadata().is_ok())
+            .collect();
+        if !existing.is_empty() {
+            let message = match existing.as_slice() {
+                [path] => {
+                    let name = path.file_name().unwrap_or_default().display();
+                    format!("Replace {name}?")
+                }
+                _ => format!("Replace {} files?", existing.len()),
+            };
+            self.confirm_dialog
+                .show(" Replace ", &message, Action::ConfirmFileOperation);
+            self.pending_operation = Some((prompt.operation, target));
+            return ActionResult::consumed(true);
+        }
+        self.apply_operation(prompt.operation, target)
+    }
+    /// Applies the confirmed operation, first moving the files it replaces to the trash.
+    fn apply_pending_operation(&mut self) -> ActionResult {
+        let Some((operation, target)) = self.pending_operation.take() else {
+            return ActionResult::consumed(false);
+        };
+        for destination in operation.destinations(&target) {
+            if destination.symlink_metadata().is_err() {
+                continue;
+            }
+            if let Err(e) = move_to_trash(&destination) {
+                self.error = Some(e.to_string());
+                return ActionResult::consumed(true);
+            }
+        }
+        self.apply_operation(operation, target)
+    }
+    /// Applies the operation and lists the current directory again, selecting what was created.
+    fn apply_operation(&mut self, operation: FileOperation, target: PathBuf) -> ActionResult {
+        let applied = operation.apply(&target);
+        if operation.sources().next().is_some() {
+            self.marked.clear();
+        }
+        if let Err(e) = applied {
+            self.error = Some(e.to_string());
+            let current_path = self.current_path.clone();
+            self.select_dir(&current_path);
+            return ActionResult::consumed(true);
+        }
+        self.error = None;
+        let current_path = self.current_path.clone();
+        self.select_dir(&current_path);
+        if let Some(created) = operation.destinations(&target).first() {
+            self.select_child(created);
+        }
+        ActionResult::consumed(true)
+    }
+    fn select_child(&mut self, path: &Path) {
+        let index =
+            (0..self.active_list().len()).find(|i| self.child_path(*i).as_deref() == Some(path));
+        if let Some(index) = index {
+            self.update_preview(index);
+            self.list_state.select(Some(index));
+        }
+    }
+    fn handle_prompt_action(&mut self, action: &Action) -> ActionResult {
+        let prompt = self.prompt.as_mut().unwrap();
+        match action {
+            Action::Confirm => self.confirm_prompt(),
+            Action::Cancel => {
+                self.prompt = None;
+                ActionResult::consumed(true)
+            }
+            action => prompt.handle_action(action),
+        }
+    }
+    /// Lists the current directory again, keeping the same entry selected.
+    fn relist(&mut self) -> ActionResult {
+        let selected = self.selected_path();
+        let current_path = self.current_path.clone();
+        self.select_dir(&current_path);
+        if let Some(selected) = selected {
+            self.select_child(&selected);
+        }
+        ActionResult::consumed(true)
+    }
+    fn cycle_sort(&mut self) -> ActionResult {
+        self.sort_key = self.sort_key.next();
+        let selected = self.selected_path();
+        sort_children(&mut self.children, self.sort_key);
+        self.refresh_filtered_items();
+        if let Some(selected) = selected {
+            self.select_child(&selected);
+        }
+        ActionResult::consumed(true)
+    }
+    fn toggle_hidden(&mut self) -> ActionResult {
+        self.show_hidden = !self.show_hidden;
+        self.relist()
+    }
+    fn toggle_columns(&mut self) -> ActionResult {
+        self.show_columns = !self.show_columns;
+        ActionResult::consumed(true)
+    }
+    fn toggle_bookmark(&mut self) -> ActionResult {
+        if let Err(e) = self.places.toggle_bookmark(&self.current_path) {
+            self.error = Some(e.to_string());
+        }
+        ActionResult::consumed(true)
+    }
+    fn handle_places_action(&mut self, action: &Action) -> ActionResult {
+        match action {
+            Action::Up => ActionResult::consumed(self.places.move_up()),
+            Action::Down => ActionResult::consumed(self.places.move_down()),
+            Action::Confirm => {
+                if let Some(dir) = self.places.selected() {
+                    self.places.set_focused(false);
+                    self.select_dir(dir);
+                }
+                ActionResult::consumed(true)
+            }
+            Action::Cancel | Action::Places => {
+                self.places.set_focused(false);
+                ActionResult::consumed(true)
+            }
+            Action::Bookmark => self.toggle_bookmark(),
+            Action::Remove => match self.places.remove_selected() {
+                Ok(removed) => ActionResult::consumed(removed),
+                Err(e) => {
+                    self.error = Some(e.to_string());
+                    ActionResult::consumed(true)
+                }
+            },
+            _ => ActionResult::consumed(false),
+        }
+    }
     fn handle_cancel(&mut self) -> ActionResult {
         if self.input.cancel() {
             self.refresh_filtered_items();
@@ -218,35 +735,99 @@ impl FileSelectorComponent<'_> {
             self.list_state.select(None);
             return ActionResult::consumed(true);
         }
+        if !self.marked.is_empty() {
+            self.marked.clear();
+            return ActionResult::consumed(true);
+        }
         self.hide();
         ActionResult::consumed(true)
     }
 }
 
+/// The longest start both strings share, ignoring case.
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let length = a
+        .char_indices()
+        .zip(b.chars())
+        .find(|((_, a), b)| !a.to_lowercase().eq(b.to_lowercase()))
+        .map_or_else(|| a.len().min(b.len()), |((i, _), _)| i);
+    &a[..length]
+}
+
 impl Component for FileSelectorComponent<'_> {
+    fn register_config(&mut self, config: &Config, parent_comp: &AppComponent) {
+        self.confirm_dialog.register_config(config, parent_comp);
+        self.keybinds.register_keybinds(config);
+        self.places.load(config);
+        self.preview_component.register_config(config, parent_comp);
+        self.input.set_theme(config.theme.clone());
+        self.theme = config.theme.clone();
+        self.effect_runner.register_config(config, parent_comp);
+        // Reloading an unchanged config keeps what was toggled since
+        let file_selector = &config.config.file_selector;
+        if self.settings.as_ref() == Some(file_selector) {
+            return;
+        }
+        self.sort_key = file_selector.sort_by;
+        self.show_hidden = file_selector.show_hidden;
+        self.show_columns = file_selector.show_columns;
+        self.columns = file_selector.columns.clone();
+        self.settings = Some(file_selector.clone());
+    }
+    fn register_action_sender(&mut self, sender: ActionSender) {
+        self.confirm_dialog.register_action_sender(sender);
+    }
     fn register_async_action_sender(&mut self, sender: AsyncActionSender) {
         self.preview_component
             .register_async_action_sender(sender.clone());
         self.effect_runner
             .register_async_action_sender(sender.clone());
+        self.confirm_dialog
+            .register_async_action_sender(sender.clone());
         self.action_sender = Some(sender)
     }
     fn override_keybind_id(&self, key_event: KeyEvent) -> Option<&AppComponent> {
-        let _ = key_event;
-        if self.visible {
-            Some(&AppComponent::FileDialog)
-        } else {
-            None
+        if !self.visible {
+            return None;
+        }
+        if let Some(a) = self.confirm_dialog.override_keybind_id(key_event) {
+            return Some(a);
         }
+        Some(&AppComponent::FileDialog)
     }
     fn handle_action(&mut self, action: &Action) -> ActionResult {
         if !self.visible {
             return Default::default();
         }
+        if self.confirm_dialog.visible() {
+            return self.confirm_dialog.handle_action(action);
+        }
+        if let Action::ConfirmFileOperation = action {
+            return self.apply_pending_operation();
+        }
+        if self.prompt.is_some() {
+            return self.handle_prompt_action(action);
+        }
+        if self.places.focused() {
+            return self.handle_places_action(action);
+        }
         let p = self.preview_component.handle_action(action);
         if p.is_consumed() {
             return p;
         }
+        let changes_files = matches!(
+            action,
+            Action::NewFile
+                | Action::NewFolder
+                | Action::Rename
+                | Action::Duplicate
+                | Action::Move
+                | Action::Trash
+        );
+        if changes_files && self.in_archive() {
+            self.error = Some("Archives can only be extracted".to_string());
+            return ActionResult::consumed(true);
+        }
         match action {
             Action::Up => return self.move_cursor_up(),
             Action::Down => return self.move_cursor_down(),
@@ -257,7 +838,27 @@ impl Component for FileSelectorComponent<'_> {
             Action::Cancel => return self.handle_cancel(),
             Action::Backspace => return self.handle_backspace(),
             Action::Search => return self.input.toggle_filter(),
+            Action::FindFiles => return self.toggle_finder(),
             Action::Delete => return self.handle_delete(),
+            Action::Tab => return self.complete(),
+            Action::NewFile => return self.show_prompt(FileOperation::CreateFile),
+            Action::NewFolder => return self.show_prompt(FileOperation::CreateFolder),
+            Action::Rename => return self.open_rename_prompt(),
+            Action::Duplicate => return self.open_prompt(FileOperation::Duplicate),
+            Action::Move => return self.open_prompt(FileOperation::Move),
+            Action::Trash => return self.confirm_trash(),
+            Action::Extract if self.in_archive() => {
+                return self.open_prompt(FileOperation::Extract);
+            }
+            Action::CycleSort => return self.cycle_sort(),
+            Action::ToggleHidden => return self.toggle_hidden(),
+            Action::ToggleColumns => return self.toggle_columns(),
+            Action::Places => {
+                self.places.set_focused(true);
+                return ActionResult::consumed(true);
+            }
+            Action::Bookmark => return self.toggle_bookmark(),
+            Action::Mark => return self.toggle_mark(),
             Action::Character(char) => return self.handle_character(*char),
             _ => {}
         }
@@ -267,22 +868,60 @@ impl Component for FileSelectorComponent<'_> {
         if !self.visible {
             return ActionResult::not_consumed(false);
         }
+        if let AsyncAction::FoundFiles(root, files) = action {
+            return self.add_found_files(root, files);
+        }
         self.preview_component.handle_async_action(action)
     }
     fn render(&mut self, frame: &mut Frame, area: Rect) {
         if self.visible {
             let title = match self.input.selector_type() {
+                _ if self.finder.is_some() => " Find file ",
                 SelectorType::PickFolder => " Select Folder ",
                 SelectorType::PickFile => " Open file ",
                 SelectorType::NewFile => " Save file ",
             };
             let title = Line::raw(title).centered();
-            let path_title = format!(" {} ", self.current_path.to_str().unwrap_or("/"));
+            let path = self.current_path.to_str().unwrap_or("/");
+            let bookmark = if self.places.is_bookmarked(&self.current_path) {
+                " "
+            } else {
+                ""
+            };
+            let marked = match self.marked.len() {
+                0 => String::new(),
+                marked => format!(" ({marked} marked)"),
+            };
+            let path_title = match &self.finder {
+                Some(finder) => format!(" {path}{bookmark} ({} files){marked} ", finder.found()),
+                None => format!(" {path}{bookmark}{marked} "),
+            };
             let path_line = Line::from(path_title).left_aligned();
-            let block = default_block().title_bottom(path_line).title_top(title);
+            let path_width = path_line.width();
+            let hints_line = match &self.error {
+                Some(error) => Line::styled(format!(" {error} "), self.theme.error),
+                None => Line::styled(self.operation_hints().concat(), self.theme.muted),
+            };
+            let sort_line = Line::from(format!(" by {} ", self.sort_key)).right_aligned();
+            let block = default_block(&self.theme)
+                .title_bottom(path_line)
+                .title_top(title)
+                .title_top(sort_line.style(self.theme.muted));
             let children = self.active_list();
+            let columns: &[Column] = if self.show_columns {
+                &self.columns
+            } else {
+                &[]
+            };
             let items = children.iter().enumerate().map(|(i, v)| {
-                let text = v.to_path_line();
+                let marked = self.child_path(i).is_some_and(|p| self.marked.contains(&p));
+                let marker = match (self.marked.is_empty(), marked) {
+                    (true, _) => "",
+                    (false, true) => "● ",
+                    (false, false) => "  ",
+                };
+                let text = v.to_columns(columns) + &v.to_path_line();
+                let text = format!("{marker}{text}");
                 let text = if self
                     .list_state
                     .selected()
@@ -293,15 +932,20 @@ impl Component for FileSelectorComponent<'_> {
                     text
                 };
                 let text = Text::from(text);
-                text.dark_gray()
+                if marked {
+                    text.style(self.theme.accent)
+                } else {
+                    text.style(self.theme.muted)
+                }
             });
             let list = List::new(items)
                 .direction(ListDirection::TopToBottom)
-                .highlight_style(Style::default().fg(Color::White).italic())
+                .highlight_style(self.theme.highlight)
                 .highlight_spacing(HighlightSpacing::Always)
                 .highlight_symbol(HIGHLIGHT_SYMBOL)
                 .scroll_padding(3)
                 .block(block);
+            let dialog_area = area;
             let area = center_horizontally(area, Constraint::Percentage(70));
             let area = center_vertically(area, Constraint::Percentage(70));
             frame.render_widget(Clear, area);
@@ -309,15 +953,39 @@ impl Component for FileSelectorComponent<'_> {
                 .direction(Direction::Vertical)
                 .constraints([Constraint::Length(3), Constraint::Fill(1)])
                 .areas(area);
+            let list_area = if list_area.width >= MIN_WIDTH_FOR_PLACES || self.places.focused() {
+                let [places_area, list_area] =
+                    Layout::horizontal([Constraint::Length(PLACES_WIDTH), Constraint::Fill(1)])
+                        .areas(list_area);
+                self.places.render(frame, places_area, &self.current_path);
+                list_area
+            } else {
+                list_area
+            };
             let list_area = render_preview_if_able(
                 frame,
                 list_area,
                 &mut self.preview_component,
                 self.list_state.selected().is_some(),
             );
-            self.input.render(frame, input_area);
+            match &self.prompt {
+                Some(prompt) => prompt.render(frame, input_area),
+                None => self.input.render(frame, input_area),
+            }
             frame.render_stateful_widget(list, list_area, &mut self.list_state);
+            // Hints are left out rather than drawn over the path, errors are always shown
+            let hints_area = Rect {
+                x: list_area.x + 1,
+                y: list_area.bottom().saturating_sub(1),
+                width: list_area.width.saturating_sub(2),
+                height: 1,
+            };
+            let fits = path_width + hints_line.width() < hints_area.width as usize;
+            if fits || self.error.is_some() {
+                frame.render_widget(hints_line.right_aligned(), hints_area);
+            }
             self.effect_runner.process(frame.buffer_mut(), area);
+            self.confirm_dialog.render(frame, dialog_area);
         }
     }
 }
@@ -0,0 +1,100 @@
+use crate::action::AsyncActionSender;
+use crate::component::file_selector::fuzzy::fuzzy_match;
+use crate::component::file_selector::PathChild;
+use crate::util::find_files;
+use std::path::{Path, PathBuf};
+use tokio_util::sync::CancellationToken;
+
+/// Most files walked under the directory
+const MAX_FOUND_FILES: usize = 100_000;
+/// Most matches listed at once
+const MAX_SHOWN: usize = 500;
+
+/// Files found under a directory while it is walked in the background, ranked by how well they
+/// match the filter.
+///
+/// Files are listed by their path relative to the directory, so they can be joined to it like
+/// the children of the file selector. The walk stops once the finder is dropped.
+pub(super) struct FileFinder {
+    root: PathBuf,
+    files: Vec<PathChild>,
+    filter: String,
+    /// Score and index of the best matching files, best first
+    ranked: Vec<(i64, usize)>,
+    cancellation_token: CancellationToken,
+}
+
+impl FileFinder {
+    pub fn start(root: PathBuf, sender: AsyncActionSender) -> Self {
+        let cancellation_token = CancellationToken::new();
+        find_files(
+            root.clone(),
+            MAX_FOUND_FILES,
+            sender,
+            cancellation_token.clone(),
+        );
+        Self {
+            root,
+            files: Vec::new(),
+            filter: String::new(),
+            ranked: Vec::new(),
+            cancellation_token,
+        }
+    }
+    pub fn found(&self) -> usize {
+        self.files.len()
+    }
+    /// Adds files found under `root`, returning false if they were found for another finder.
+    pub fn add_files(&mut self, root: &Path, files: &[PathBuf]) -> bool {
+        if root != self.root {
+            return false;
+        }
+        let start = self.files.len();
+        for file in files {
+            let Ok(relative) = file.strip_prefix(&self.root) else {
+                continue;
+            };
+            let name = relative.display().to_string();
+            self.files.push(PathChild::file(name, file.clone()));
+        }
+        self.rank(start);
+        true
+    }
+    pub fn set_filter(&mut self, filter: Option<String>) {
+        let filter = filter.unwrap_or_default();
+        if filter != self.filter {
+            self.filter = filter;
+            self.ranked.clear();
+            self.rank(0);
+        }
+    }
+    /// The best matching files, best first, or the first files found if there is no filter.
+    pub fn matches(&self) -> Vec<PathChild> {
+        self.ranked
+            .iter()
+            .map(|(_, index)| self.files[*index].clone())
+            .collect()
+    }
+    /// Ranks the files from `start` onwards among the best matches.
+    fn rank(&mut self, start: usize) {
+        let new_matches = self.files[start..]
+            .iter()
+            .enumerate()
+            .filter_map(|(i, file)| {
+                let PathChild::File { full_file_name, .. } = file else {
+                    return None;
+                };
+                let matched = fuzzy_match(&self.filter, full_file_name)?;
+                Some((matched.score, start + i))
+            });
+        self.ranked.extend(new_matches);
+        self.ranked.sort_by(|(a, _), (b, _)| b.cmp(a));
+        self.ranked.truncate(MAX_SHOWN);
+    }
+}
+
+impl Drop for FileFinder {
+    fn drop(&mut self) {
+        self.cancellation_token.cancel();
+    }
+}
@@ -1,22 +1,25 @@
-use crate::action::{Action, ActionResult, AsyncAction, AsyncActionSender, SelectorType};
+use crate::action::{
+    Action, ActionResult, ActionSender, AsyncAction, AsyncActionSender, SelectorType,
+};
 use crate::component::component_utils::{
     center, center_horizontally, center_vertically, default_block, key_label_format,
 };
+use crate::component::confirm_dialog::ConfirmDialogComponent;
 use crate::component::effect_runner::EffectRunner;
+use crate::component::file_selector::file_history_saver::{now, FileHistorySaver};
 use crate::component::file_selector::{label_for_file, render_preview_if_able, HIGHLIGHT_SYMBOL};
 use crate::component::preview_component::PreviewComponent;
 use crate::component::{AppComponent, Component};
-use crate::config::effects::dialog_enter;
+use crate::config::effects::{dialog_enter, AnimationEvent};
+use crate::config::theme::Theme;
 use crate::config::Config;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyEvent;
-use ratatui::layout::{Constraint, Rect};
-use ratatui::prelude::Color;
+use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::style::Stylize;
-use ratatui::text::{Line, Text};
+use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{Clear, HighlightSpacing, List, ListDirection, ListItem, ListState};
 use ratatui::Frame;
-use std::fs::read_to_string;
 use std::path::PathBuf;
 
 pub(super) const HISTORY_FILE_NAME: &str = "file_history.txt";
@@ -27,15 +30,22 @@ struct FileHistoryKeybinds {
     down: String,
     confirm: String,
     cancel: String,
+    pin: String,
+    remove: String,
+    clear: String,
 }
 
 impl FileHistoryKeybinds {
-    fn register_keybinds(&mut self, app_component: &AppComponent, config: &Config) {
+    fn register_keybinds(&mut self, config: &Config) {
         let keybinds = &config.keybindings;
+        let app_component = &AppComponent::FileHistory;
         self.up = keybinds.get_key_string_or_default(Action::Up, app_component);
         self.down = keybinds.get_key_string_or_default(Action::Down, app_component);
         self.confirm = keybinds.get_key_string_or_default(Action::Confirm, app_component);
         self.cancel = keybinds.get_key_string_or_default(Action::Cancel, app_component);
+        self.pin = keybinds.get_key_string_or_default(Action::Pin, app_component);
+        self.remove = keybinds.get_key_string_or_default(Action::Remove, app_component);
+        self.clear = keybinds.get_key_string_or_default(Action::ClearHistory, app_component);
     }
 }
 
@@ -43,30 +53,60 @@ struct FileHistory {
     label: String,
     path: PathBuf,
     parent_label: Option<String>,
+    /// When the file was last opened, like "2h ago"
+    opened_label: Option<String>,
+    pinned: bool,
 }
 
+/// How long before `now` the unix timestamp was, like "5m ago".
+fn relative_time(timestamp: u64, now: u64) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+    const WEEK: u64 = 7 * DAY;
+    let seconds = now.saturating_sub(timestamp);
+    match seconds {
+        0..MINUTE => "just now".to_string(),
+        MINUTE..HOUR => format!("{}m ago", seconds / MINUTE),
+        HOUR..DAY => format!("{}h ago", seconds / HOUR),
+        DAY..WEEK => format!("{}d ago", seconds / DAY),
+        _ => format!("{}w ago", seconds / WEEK),
+    }
+}
+
+/// A dialog listing the recently opened files, most recent first.
+///
+/// Typing filters the list by path. Entries can be pinned to the top, removed, or all cleared
+/// except for the pinned ones.
 #[derive(Default)]
 pub struct FileHistoryComponent {
     opened: bool,
     data_dir: PathBuf,
+    history_limit: usize,
+    history: FileHistorySaver,
     files: Vec<FileHistory>,
+    filter: String,
     preview_component: PreviewComponent,
     list_state: ListState,
     async_action_sender: Option<AsyncActionSender>,
     effect_runner: EffectRunner,
+    confirm_dialog_component: ConfirmDialogComponent,
     keybinds: FileHistoryKeybinds,
+    theme: Theme,
 }
 
 impl FileHistoryComponent {
     pub fn show(&mut self) -> Result<()> {
         self.opened = true;
-        self.effect_runner
-            .add_effect(dialog_enter(Color::from_u32(0x1d2021)));
+        let effect = dialog_enter(self.theme.dialog_color());
+        self.effect_runner.add_effect(AnimationEvent::Dialog, effect);
         self.load_files()?;
         Ok(())
     }
     pub fn hide(&mut self) {
         self.files.clear();
+        self.filter.clear();
+        self.list_state.select(None);
         self.opened = false;
     }
     pub fn showing(&self) -> bool {
@@ -80,22 +120,46 @@ impl FileHistoryComponent {
         if !self.files.is_empty() {
             return Ok(());
         }
-        let file = self.data_dir.join(HISTORY_FILE_NAME);
-        let file = read_to_string(&file)?;
-        for line in file.lines() {
-            let path = PathBuf::from(line);
-            if path.is_file() {
-                let full_path = path.parent().map(|p| p.display().to_string());
-                let label = label_for_file(&path);
-                let file = FileHistory {
-                    label,
+        self.history
+            .load_from_data_dir(&self.data_dir, self.history_limit);
+        self.refresh_files();
+        Ok(())
+    }
+    /// Lists the entries of the history matching the filter, keeping the selection in bounds.
+    fn refresh_files(&mut self) {
+        let filter = self.filter.to_lowercase();
+        let now = now();
+        self.files = self
+            .history
+            .entries()
+            .iter()
+            .filter(|entry| {
+                let path = entry.path.to_string_lossy().to_lowercase();
+                path.contains(&filter)
+            })
+            .map(|entry| {
+                let path = entry.path.clone();
+                let parent_label = path.parent().map(|p| p.display().to_string());
+                let opened_label =
+                    (entry.opened_at > 0).then(|| relative_time(entry.opened_at, now));
+                FileHistory {
+                    label: label_for_file(&path),
                     path,
-                    parent_label: full_path,
-                };
-                self.files.push(file);
+                    parent_label,
+                    opened_label,
+                    pinned: entry.pinned,
+                }
+            })
+            .collect();
+        match self.list_state.selected() {
+            Some(_) if self.files.is_empty() => self.list_state.select(None),
+            Some(selected) => {
+                let selected = selected.min(self.files.len() - 1);
+                self.update_preview(selected);
+                self.list_state.select(Some(selected));
             }
+            None => {}
         }
-        Ok(())
     }
     fn update_preview(&mut self, index: usize) {
         let path = &self.files[index].path;
@@ -146,6 +210,56 @@ impl FileHistoryComponent {
         self.hide();
         ActionResult::consumed(true)
     }
+    fn selected_path(&self) -> Option<PathBuf> {
+        let selected = self.list_state.selected()?;
+        Some(self.files[selected].path.clone())
+    }
+    fn toggle_pin(&mut self) -> ActionResult {
+        let Some(path) = self.selected_path() else {
+            return ActionResult::consumed(false);
+        };
+        self.history.toggle_pin(&path);
+        self.save_history();
+        let selected = self.files.iter().position(|file| file.path == path);
+        self.list_state.select(selected);
+        ActionResult::consumed(true)
+    }
+    fn remove(&mut self) -> ActionResult {
+        let Some(path) = self.selected_path() else {
+            return ActionResult::consumed(false);
+        };
+        self.history.remove(path);
+        self.save_history();
+        ActionResult::consumed(true)
+    }
+    fn confirm_clear(&mut self) -> ActionResult {
+        const TITLE: &str = " Clear History ";
+        const MESSAGE: &str = "Remove unpinned files?";
+        self.confirm_dialog_component
+            .show(TITLE, MESSAGE, Action::ConfirmClearHistory);
+        ActionResult::consumed(true)
+    }
+    fn clear(&mut self) -> ActionResult {
+        self.history.clear();
+        self.save_history();
+        ActionResult::consumed(true)
+    }
+    fn save_history(&mut self) {
+        let _ = self.history.write();
+        self.refresh_files();
+    }
+    fn handle_character(&mut self, character: char) -> ActionResult {
+        self.filter.push(character);
+        self.refresh_files();
+        ActionResult::consumed(true)
+    }
+    fn handle_backspace(&mut self) -> ActionResult {
+        if self.filter.pop().is_none() {
+            return ActionResult::consumed(false);
+        }
+        self.refresh_files();
+        ActionResult::consumed(true)
+    }
     fn cancel(&mut self) -> ActionResult {
         if self.list_state.selected().is_some() {
             self.list_state.select(None);
@@ -154,18 +268,30 @@ impl FileHistoryComponent {
         }
         ActionResult::consumed(true)
     }
-    fn map_to_list_item(selected: Option<usize>, file: &'_ FileHistory, i: usize) -> ListItem<'_> {
+    fn map_to_list_item<'a>(
+        theme: &Theme,
+        selected: Option<usize>,
+        file: &'a FileHistory,
+        i: usize,
+    ) -> ListItem<'a> {
         let label = file.label.clone();
-        let mut lines = Vec::with_capacity(3);
+        let mut lines = Vec::with_capacity(4);
         let label = if selected.is_some_and(|s| s == i) {
-            label.white()
+            Span::styled(label, theme.active)
         } else {
-            label.dark_gray()
+            Span::styled(label, theme.muted)
         };
         lines.push(label);
+        if file.pinned {
+            lines.push(Span::styled(" (pinned)", theme.accent));
+        }
         if let Some(parent) = &file.parent_label {
             let label = "   ".to_string() + parent;
-            lines.push(label.dark_gray().italic());
+            lines.push(Span::styled(label, theme.muted.italic()));
+        }
+        if let Some(opened) = &file.opened_label {
+            let label = "   ".to_string() + opened;
+            lines.push(Span::styled(label, theme.muted));
         }
         let line = Line::from(lines);
         ListItem::new(line)
@@ -175,20 +301,32 @@ impl FileHistoryComponent {
 impl Component for FileHistoryComponent {
     fn register_config(&mut self, config: &Config, parent_comp: &AppComponent) {
         self.data_dir = config.config.data_dir.clone();
-        self.keybinds.register_keybinds(parent_comp, config);
-        let _ = parent_comp;
+        self.history_limit = config.config.history_limit;
+        self.keybinds.register_keybinds(config);
+        self.theme = config.theme.clone();
+        self.effect_runner.register_config(config, parent_comp);
+        self.preview_component.register_config(config, parent_comp);
+        self.confirm_dialog_component
+            .register_config(config, parent_comp);
+    }
+    fn register_action_sender(&mut self, sender: ActionSender) {
+        self.confirm_dialog_component.register_action_sender(sender);
     }
     fn register_async_action_sender(&mut self, sender: AsyncActionSender) {
         self.effect_runner
             .register_async_action_sender(sender.clone());
+        self.confirm_dialog_component
+            .register_async_action_sender(sender.clone());
         self.preview_component
             .register_async_action_sender(sender.clone());
         self.async_action_sender = Some(sender)
     }
     fn override_keybind_id(&self, key_event: KeyEvent) -> Option<&AppComponent> {
-        let _ = key_event;
+        if let Some(a) = self.confirm_dialog_component.override_keybind_id(key_event) {
+            return Some(a);
+        }
         if self.opened {
-            Some(&AppComponent::FileDialog)
+            Some(&AppComponent::FileHistory)
         } else {
             None
         }
@@ -197,6 +335,10 @@ impl Component for FileHistoryComponent {
         if !self.showing() {
             return ActionResult::not_consumed(false);
         }
+        let res = self.confirm_dialog_component.handle_action(action);
+        if res.is_consumed() {
+            return res;
+        }
         let prev = self.preview_component.handle_action(action);
         if prev.is_consumed() {
             return prev;
@@ -206,6 +348,12 @@ impl Component for FileHistoryComponent {
             Action::Down => return self.move_down(),
             Action::Confirm => return self.select(),
             Action::Cancel => return self.cancel(),
+            Action::Pin => return self.toggle_pin(),
+            Action::Remove => return self.remove(),
+            Action::ClearHistory => return self.confirm_clear(),
+            Action::ConfirmClearHistory => return self.clear(),
+            Action::Backspace => return self.handle_backspace(),
+            Action::Character(character) => return self.handle_character(*character),
             _ => {}
         }
         ActionResult::consumed(false)
@@ -223,10 +371,17 @@ impl Component for FileHistoryComponent {
         let area = center_horizontally(area, Constraint::Percentage(60));
         let area = center_vertically(area, Constraint::Percentage(60));
         frame.render_widget(Clear, area);
-        let title = Line::raw(" File History ").centered();
+        let title = if self.filter.is_empty() {
+            " File History ".to_string()
+        } else {
+            format!(" File History: {} ", self.filter)
+        };
+        let title = Line::raw(title).centered();
         let up_down_title = format!(" {} {} ", self.keybinds.up, self.keybinds.down);
         let up_down_title = Line::from(up_down_title).centered();
-        let mut block = default_block().title_top(title).title_bottom(up_down_title);
+        let mut block = default_block(&self.theme)
+            .title_top(title)
+            .title_bottom(up_down_title);
         if self.list_state.selected().is_some() {
             let label = key_label_format(&self.keybinds.confirm, "Open");
             let enter_title = Line::from(label).right_aligned();
@@ -237,7 +392,12 @@ impl Component for FileHistoryComponent {
         if self.files.is_empty() {
             let block_area = block.inner(area);
             let center = center(block_area);
-            let text = Text::raw("No files have been opened yet...").centered();
+            let text = if self.filter.is_empty() {
+                "No files have been opened yet..."
+            } else {
+                "No files match the filter..."
+            };
+            let text = Text::raw(text).centered();
             frame.render_widget(block, area);
             frame.render_widget(text, center);
         } else {
@@ -246,21 +406,32 @@ impl Component for FileHistoryComponent {
                 .files
                 .iter()
                 .enumerate()
-                .map(|(i, file)| Self::map_to_list_item(selected, file, i));
+                .map(|(i, file)| Self::map_to_list_item(&self.theme, selected, file, i));
             let list = List::new(mapped)
                 .direction(ListDirection::TopToBottom)
                 .highlight_symbol(HIGHLIGHT_SYMBOL)
                 .highlight_spacing(HighlightSpacing::Always)
-                .scroll_padding(5)
-                .block(block);
+                .scroll_padding(5);
             let list_area = render_preview_if_able(
                 frame,
                 area,
                 &mut self.preview_component,
                 self.list_state.selected().is_some(),
             );
-            frame.render_stateful_widget(list, list_area, &mut self.list_state);
+            let inner_area = block.inner(list_area);
+            let [inner_area, hints_area] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner_area);
+            let hints = [
+                key_label_format(&self.keybinds.pin, "Pin"),
+                key_label_format(&self.keybinds.remove, "Remove"),
+                key_label_format(&self.keybinds.clear, "Clear"),
+            ];
+            let hints = Line::styled(hints.concat(), self.theme.muted);
+            frame.render_widget(block, list_area);
+            frame.render_stateful_widget(list, inner_area, &mut self.list_state);
+            frame.render_widget(hints, hints_area);
         }
         self.effect_runner.render(frame, area);
+        self.confirm_dialog_component.render(frame, frame.area());
     }
 }
@@ -1,84 +1,551 @@
 use crate::component::file_selector::file_history::HISTORY_FILE_NAME;
+use crate::config::app_config::default_history_limit;
 use crate::config::Config;
-use std::collections::HashSet;
+use crate::session::BufferSession;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::{self, BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Separates the fields of an entry in the history file
+const FIELD_SEPARATOR: char = '\t';
+/// Appended to the history file name for the file locked while writing it
+const LOCK_EXTENSION: &str = ".lock";
+/// Appended to the history file name for the file written before replacing it
+const TEMP_EXTENSION: &str = ".tmp";
+
+/// A file of the history, with where it was left when last closed.
+///
+/// Written as a line of the history file, with the path followed by the cursor row and column,
+/// the first visible row, the time it was last opened, whether it is pinned and how many times
+/// it was opened, separated by tabs. Lines holding only a path are read as entries without a
+/// position.
+#[derive(Clone, Debug, Default)]
+pub struct HistoryEntry {
+    pub path: PathBuf,
+    /// Row and column of the cursor
+    pub cursor: (usize, usize),
+    /// First visible row
+    pub top_row: u16,
+    /// Seconds since the unix epoch when the file was last opened
+    pub opened_at: u64,
+    /// Pinned entries stay at the top of the history and are never dropped from it
+    pub pinned: bool,
+    /// Number of times the file was opened
+    pub open_count: u32,
+}
+
+impl HistoryEntry {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            opened_at: now(),
+            ..Default::default()
+        }
+    }
+    fn parse(line: &str) -> Self {
+        Self::parse_fields(line, 7)
+            .or_else(|| Self::parse_fields(line, 6))
+            .or_else(|| Self::parse_fields(line, 5))
+            .unwrap_or_else(|| Self {
+                path: PathBuf::from(line),
+                ..Default::default()
+            })
+    }
+    /// Parses a line with `count` fields, the first one being the path.
+    fn parse_fields(line: &str, count: usize) -> Option<Self> {
+        let mut fields: Vec<&str> = line.rsplitn(count, FIELD_SEPARATOR).collect();
+        if fields.len() != count {
+            return None;
+        }
+        fields.reverse();
+        Some(Self {
+            path: PathBuf::from(fields[0]),
+            cursor: (fields[1].parse().ok()?, fields[2].parse().ok()?),
+            top_row: fields[3].parse().ok()?,
+            opened_at: fields[4].parse().ok()?,
+            pinned: fields.get(5).is_some_and(|pinned| *pinned == "1"),
+            open_count: fields.get(6).and_then(|count| count.parse().ok()).unwrap_or(1),
+        })
+    }
+    fn to_line(&self) -> String {
+        let (row, col) = self.cursor;
+        let path = self.path.to_string_lossy();
+        let pinned = u8::from(self.pinned);
+        let sep = FIELD_SEPARATOR;
+        format!(
+            "{path}{sep}{row}{sep}{col}{sep}{}{sep}{}{sep}{pinned}{sep}{}",
+            self.top_row, self.opened_at, self.open_count
+        )
+    }
+    /// How likely the file is to be opened again, from how often and how recently it was.
+    pub fn frecency(&self, now: u64) -> f64 {
+        const HOUR: u64 = 60 * 60;
+        const DAY: u64 = 24 * HOUR;
+        const WEEK: u64 = 7 * DAY;
+        const MONTH: u64 = 30 * DAY;
+        const SEASON: u64 = 3 * MONTH;
+        let weight = match now.saturating_sub(self.opened_at) {
+            0..HOUR => 100.0,
+            HOUR..DAY => 70.0,
+            DAY..WEEK => 50.0,
+            WEEK..MONTH => 30.0,
+            MONTH..SEASON => 10.0,
+            _ => 1.0,
+        };
+        f64::from(self.open_count.max(1)) * weight
+    }
+    /// Reads the entries of the history file whose file still exists.
+    pub fn read_all(history_file: &Path) -> Vec<Self> {
+        let Ok(file_content) = fs::read_to_string(history_file) else {
+            return Vec::new();
+        };
+        file_content
+            .lines()
+            .map(Self::parse)
+            .filter(|entry| entry.path.is_file())
+            .collect()
+    }
+}
+
+/// Seconds since the unix epoch.
+pub(super) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Exclusive lock on the history, released when dropped.
+///
+/// Held on a separate file, since the history itself is replaced when written.
+struct HistoryLock(File);
+
+impl HistoryLock {
+    fn acquire(history_file: &Path) -> io::Result<Self> {
+        let mut lock_file = history_file.as_os_str().to_owned();
+        lock_file.push(LOCK_EXTENSION);
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_file)?;
+        file.lock()?;
+        Ok(Self(file))
+    }
+}
+
+impl Drop for HistoryLock {
+    fn drop(&mut self) {
+        let _ = self.0.unlock();
+    }
+}
+
+/// Fields of an entry changed since the history was last read, applied over the entry the
+/// history file holds when writing, so the fields changed by other instances are kept.
+#[derive(Clone, Debug, Default)]
+struct EntryChange {
+    opened_at: Option<u64>,
+    /// Number of times the file was opened since
+    opens: u32,
+    /// Cursor and first visible row
+    position: Option<((usize, usize), u16)>,
+    pinned: Option<bool>,
+}
+
+impl EntryChange {
+    fn apply(&self, entry: &mut HistoryEntry) {
+        if let Some(opened_at) = self.opened_at {
+            entry.opened_at = entry.opened_at.max(opened_at);
+        }
+        entry.open_count += self.opens;
+        if let Some((cursor, top_row)) = self.position {
+            entry.cursor = cursor;
+            entry.top_row = top_row;
+        }
+        if let Some(pinned) = self.pinned {
+            entry.pinned = pinned;
+        }
+    }
+}
 
 /// Utility for adding newly opened or saved files to the file history
-/// Files already present are re-added to the top of the list.
-/// Stores newly added files and only writes to the file when this struct is dropped.
-#[derive(Default)]
+/// Files already present are moved back to the top of the list.
+/// Entries are kept from the most to the least recently opened, pinned ones first, and only
+/// the most recent unpinned ones are kept up to the configured limit.
+/// Changes are only written to the file when this struct is dropped, or when asked to.
+///
+/// Other instances may write to the history in the meantime, so only the fields changed and
+/// the entries removed here are applied over what the file holds when writing.
 pub struct FileHistorySaver {
     data_file_dir: PathBuf,
-    current: HashSet<PathBuf>,
-    new: HashSet<PathBuf>,
+    limit: usize,
+    entries: Vec<HistoryEntry>,
+    /// Entries changed since the history was last read
+    changed: HashMap<PathBuf, EntryChange>,
+    /// Entries removed since the history was last read
+    removed: HashSet<PathBuf>,
+}
+
+/// Keeps as many entries as the default config, so nothing is dropped if it is written before
+/// the config is loaded.
+impl Default for FileHistorySaver {
+    fn default() -> Self {
+        Self {
+            data_file_dir: PathBuf::default(),
+            limit: default_history_limit(),
+            entries: Vec::default(),
+            changed: HashMap::default(),
+            removed: HashSet::default(),
+        }
+    }
 }
 
 impl From<&Config> for FileHistorySaver {
     fn from(value: &Config) -> Self {
-        Self::new(value.config.data_dir.clone())
+        Self::new(value.config.data_dir.clone(), value.config.history_limit)
     }
 }
 
 impl FileHistorySaver {
-    pub fn new(data_dir: PathBuf) -> FileHistorySaver {
+    pub fn new(data_dir: PathBuf, limit: usize) -> FileHistorySaver {
         let mut saver = Self::default();
-        saver.load_from_data_dir(&data_dir);
+        saver.load_from_data_dir(&data_dir, limit);
         saver
     }
     pub fn load_from_config(&mut self, config: &Config) {
-        self.load_from_data_dir(&config.config.data_dir);
+        self.load_from_data_dir(&config.config.data_dir, config.config.history_limit);
     }
-    pub fn load_from_data_dir(&mut self, data_dir: &Path) {
+    pub fn load_from_data_dir(&mut self, data_dir: &Path, limit: usize) {
         let file = data_dir.join(HISTORY_FILE_NAME);
-        if let Ok(file_content) = fs::read_to_string(&file) {
-            let file_lines = file_content.lines();
-            for line in file_lines {
-                let path = PathBuf::from(line);
-                if path.is_file() {
-                    self.current.insert(path);
-                }
-            }
-        }
+        self.entries = HistoryEntry::read_all(&file);
         self.data_file_dir = file;
-        self.new = HashSet::new();
+        self.limit = limit;
+        self.changed.clear();
+        self.removed.clear();
+        self.sort();
+    }
+    /// Entries from the most to the least recently opened, pinned ones first.
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
     }
     pub fn push_to_history<P: AsRef<Path>>(&mut self, file: P) {
-        let file = file.as_ref().to_path_buf();
-        self.current.remove(&file);
-        self.new.insert(file);
+        let mut entry = self.take_entry(file.as_ref());
+        entry.opened_at = now();
+        entry.open_count += 1;
+        let change = self.change(&entry.path);
+        change.opened_at = Some(entry.opened_at);
+        change.opens += 1;
+        self.put_entry(entry);
+    }
+    /// Remembers where a file was left, to put the cursor back there when it is opened again.
+    pub fn save_position(&mut self, position: &BufferSession) {
+        let mut entry = self.take_entry(&position.path);
+        entry.cursor = position.cursor;
+        entry.top_row = position.top_row;
+        self.change(&entry.path).position = Some((entry.cursor, entry.top_row));
+        self.put_entry(entry);
+    }
+    /// Where the file was left the last time it was closed, if it is in the history.
+    pub fn position_of<P: AsRef<Path>>(&self, file: P) -> Option<BufferSession> {
+        let file = file.as_ref();
+        let entry = self.entries.iter().find(|entry| entry.path == file)?;
+        Some(BufferSession {
+            path: entry.path.clone(),
+            cursor: entry.cursor,
+            top_row: entry.top_row,
+            search: None,
+        })
+    }
+    /// Pins or unpins the file, returning whether it is now pinned.
+    pub fn toggle_pin<P: AsRef<Path>>(&mut self, file: P) -> bool {
+        let file = file.as_ref();
+        if !self.entries.iter().any(|entry| entry.path == file) {
+            return false;
+        }
+        let mut entry = self.take_entry(file);
+        entry.pinned = !entry.pinned;
+        let pinned = entry.pinned;
+        self.change(file).pinned = Some(pinned);
+        self.put_entry(entry);
+        pinned
+    }
+    pub fn remove<P: AsRef<Path>>(&mut self, file: P) {
+        let file = file.as_ref();
+        self.entries.retain(|entry| entry.path != file);
+        self.changed.remove(file);
+        self.removed.insert(file.to_path_buf());
+    }
+    /// Removes every entry that is not pinned.
+    pub fn clear(&mut self) {
+        let (pinned, unpinned) = self.entries.drain(..).partition(|entry| entry.pinned);
+        self.entries = pinned;
+        for entry in unpinned {
+            self.changed.remove(&entry.path);
+            self.removed.insert(entry.path);
+        }
+    }
+    fn take_entry(&mut self, file: &Path) -> HistoryEntry {
+        match self.entries.iter().position(|entry| entry.path == file) {
+            Some(index) => self.entries.remove(index),
+            None => HistoryEntry::new(file.to_path_buf()),
+        }
+    }
+    fn put_entry(&mut self, entry: HistoryEntry) {
+        self.entries.insert(0, entry);
+        self.sort();
+    }
+    /// Changes made to the entry of the file. A removed entry stays removed from the history
+    /// file, and is written again from these changes alone.
+    fn change(&mut self, file: &Path) -> &mut EntryChange {
+        self.changed.entry(file.to_path_buf()).or_default()
+    }
+    /// Puts pinned entries first, then the most recently opened, and drops the unpinned
+    /// entries past the limit.
+    fn sort(&mut self) {
+        self.entries
+            .sort_by(|a, b| b.pinned.cmp(&a.pinned).then(b.opened_at.cmp(&a.opened_at)));
+        let mut unpinned = 0;
+        let limit = self.limit;
+        self.entries.retain(|entry| {
+            unpinned += usize::from(!entry.pinned);
+            entry.pinned || unpinned <= limit
+        });
     }
     pub fn awaiting_write(&self) -> bool {
-        !self.new.is_empty()
+        !self.changed.is_empty() || !self.removed.is_empty()
     }
-    fn save_new_files(&mut self) -> color_eyre::Result<()> {
+    /// Applies the changes made here over the history file, holding a lock on it so parallel
+    /// instances don't overwrite each other's entries.
+    ///
+    /// The file is replaced at once, so it is never seen half written. The changes are kept
+    /// until it is, so they are written again by the next call if it fails.
+    pub fn write(&mut self) -> color_eyre::Result<()> {
         if !self.awaiting_write() {
             return Ok(());
         }
-        let f = File::options()
-            .create(true)
-            .truncate(true)
-            .write(true)
-            .open(&self.data_file_dir)?;
-        let mut buf = BufWriter::new(&f);
-        for new in self.new.iter() {
-            let path = new.to_string_lossy().to_string();
-            writeln!(buf, "{path}")?;
+        if let Some(parent) = self.data_file_dir.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let _lock = HistoryLock::acquire(&self.data_file_dir)?;
+        let mut entries = HistoryEntry::read_all(&self.data_file_dir);
+        entries.retain(|entry| !self.removed.contains(&entry.path));
+        for (path, change) in &self.changed {
+            if let Some(entry) = entries.iter_mut().find(|entry| entry.path == *path) {
+                change.apply(entry);
+                continue;
+            }
+            // Entries missing from the file are written as they are known here
+            let known = self.entries.iter().find(|entry| entry.path == *path);
+            entries.push(known.cloned().unwrap_or_else(|| {
+                let mut entry = HistoryEntry::new(path.clone());
+                change.apply(&mut entry);
+                entry
+            }));
         }
-        for current in self.current.iter() {
-            let path = current.to_string_lossy().to_string();
-            writeln!(buf, "{path}")?;
+        self.entries = entries;
+        self.sort();
+        let mut temp_file = self.data_file_dir.as_os_str().to_owned();
+        temp_file.push(format!(".{}{TEMP_EXTENSION}", process::id()));
+        let f = File::create(&temp_file)?;
+        let mut buf = BufWriter::new(&f);
+        for entry in self.entries.iter() {
+            writeln!(buf, "{}", entry.to_line())?;
         }
         buf.flush()?;
-        self.current.extend(self.new.drain());
+        f.sync_all()?;
+        fs::rename(&temp_file, &self.data_file_dir)?;
+        self.changed.clear();
+        self.removed.clear();
         Ok(())
     }
 }
 
 impl Drop for FileHistorySaver {
     fn drop(&mut self) {
-        let _ = self.save_new_files();
+        let _ = self.write();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn paths(dir: &Path) -> Vec<PathBuf> {
+        let history = HistoryEntry::read_all(&dir.join(HISTORY_FILE_NAME));
+        let mut paths: Vec<PathBuf> = history.into_iter().map(|entry| entry.path).collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn interleaved_writers_keep_each_others_entries() {
+        let dir = TempDir::with_files("interleaved", &["a", "b"]);
+        let mut first = FileHistorySaver::new(dir.to_path_buf(), 10);
+        let mut second = FileHistorySaver::new(dir.to_path_buf(), 10);
+        first.push_to_history(dir.join("a"));
+        second.push_to_history(dir.join("b"));
+        first.write().unwrap();
+        second.write().unwrap();
+        assert_eq!(paths(&dir), vec![dir.join("a"), dir.join("b")]);
+        assert_eq!(second.entries().len(), 2);
+    }
+
+    #[test]
+    fn changes_are_kept_until_they_are_written() {
+        let dir = TempDir::with_files("failed", &["a"]);
+        let mut saver = FileHistorySaver::new(dir.to_path_buf(), 10);
+        saver.push_to_history(dir.join("a"));
+        let history = dir.join(HISTORY_FILE_NAME);
+        fs::create_dir(&history).unwrap();
+        assert!(saver.write().is_err());
+        assert!(saver.awaiting_write());
+        fs::remove_dir(&history).unwrap();
+        saver.write().unwrap();
+        assert!(!saver.awaiting_write());
+        assert_eq!(paths(&dir), vec![dir.join("a")]);
+    }
+
+    #[test]
+    fn removed_entries_stay_removed_when_another_writer_adds() {
+        let dir = TempDir::with_files("removed", &["a", "b", "c"]);
+        let mut seed = FileHistorySaver::new(dir.to_path_buf(), 10);
+        seed.push_to_history(dir.join("a"));
+        seed.push_to_history(dir.join("b"));
+        seed.write().unwrap();
+        let mut first = FileHistorySaver::new(dir.to_path_buf(), 10);
+        let mut second = FileHistorySaver::new(dir.to_path_buf(), 10);
+        first.remove(dir.join("a"));
+        second.push_to_history(dir.join("c"));
+        second.write().unwrap();
+        first.write().unwrap();
+        assert_eq!(paths(&dir), vec![dir.join("b"), dir.join("c")]);
+    }
+
+    #[test]
+    fn clear_only_removes_entries_it_knew_about() {
+        let dir = TempDir::with_files("clear", &["a", "b", "c"]);
+        let mut seed = FileHistorySaver::new(dir.to_path_buf(), 10);
+        seed.push_to_history(dir.join("a"));
+        seed.push_to_history(dir.join("b"));
+        seed.toggle_pin(dir.join("b"));
+        seed.write().unwrap();
+        let mut first = FileHistorySaver::new(dir.to_path_buf(), 10);
+        let mut second = FileHistorySaver::new(dir.to_path_buf(), 10);
+        first.clear();
+        second.push_to_history(dir.join("c"));
+        second.write().unwrap();
+        first.write().unwrap();
+        assert_eq!(paths(&dir), vec![dir.join("b"), dir.join("c")]);
+    }
+
+    #[test]
+    fn changed_positions_are_kept_over_older_entries() {
+        let dir = TempDir::with_files("positions", &["a", "b"]);
+        let mut first = FileHistorySaver::new(dir.to_path_buf(), 10);
+        let mut second = FileHistorySaver::new(dir.to_path_buf(), 10);
+        let position = BufferSession {
+            path: dir.join("a"),
+            cursor: (3, 1),
+            top_row: 2,
+            search: None,
+        };
+        second.push_to_history(dir.join("a"));
+        first.save_position(&position);
+        second.push_to_history(dir.join("b"));
+        second.write().unwrap();
+        first.write().unwrap();
+        let reloaded = FileHistorySaver::new(dir.to_path_buf(), 10);
+        let restored = reloaded.position_of(dir.join("a")).unwrap();
+        assert_eq!(restored.cursor, (3, 1));
+        assert_eq!(restored.top_row, 2);
+        assert!(reloaded.position_of(dir.join("b")).is_some());
+    }
+
+    #[test]
+    fn stale_writers_keep_the_fields_changed_by_others() {
+        let dir = TempDir::with_files("stale", &["a"]);
+        let mut seed = FileHistorySaver::new(dir.to_path_buf(), 10);
+        seed.push_to_history(dir.join("a"));
+        seed.write().unwrap();
+        let mut first = FileHistorySaver::new(dir.to_path_buf(), 10);
+        let mut second = FileHistorySaver::new(dir.to_path_buf(), 10);
+        first.toggle_pin(dir.join("a"));
+        first.push_to_history(dir.join("a"));
+        first.write().unwrap();
+        let position = BufferSession {
+            path: dir.join("a"),
+            cursor: (5, 0),
+            top_row: 1,
+            search: None,
+        };
+        second.save_position(&position);
+        second.push_to_history(dir.join("a"));
+        second.write().unwrap();
+        let entries = HistoryEntry::read_all(&dir.join(HISTORY_FILE_NAME));
+        let [entry] = entries.as_slice() else {
+            panic!("expected a single entry, got {entries:?}");
+        };
+        assert!(entry.pinned);
+        assert_eq!(entry.open_count, 3);
+        assert_eq!(entry.cursor, (5, 0));
+    }
+
+    #[test]
+    fn parallel_writers_keep_every_entry() {
+        let names: Vec<String> = (0..8).map(|i| i.to_string()).collect();
+        let files: Vec<&str> = names.iter().map(String::as_str).collect();
+        let dir = TempDir::with_files("parallel", &files);
+        let writers: Vec<_> = names
+            .iter()
+            .map(|name| {
+                let dir = dir.to_path_buf();
+                let file = dir.join(name);
+                std::thread::spawn(move || {
+                    let mut saver = FileHistorySaver::new(dir, 10);
+                    saver.push_to_history(file);
+                    saver.write().unwrap();
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        assert_eq!(paths(&dir).len(), names.len());
+    }
+
+    #[test]
+    fn merged_history_is_capped() {
+        let dir = TempDir::with_files("capped", &["a", "b", "c"]);
+        let mut first = FileHistorySaver::new(dir.to_path_buf(), 2);
+        let mut second = FileHistorySaver::new(dir.to_path_buf(), 2);
+        first.push_to_history(dir.join("a"));
+        first.push_to_history(dir.join("b"));
+        second.push_to_history(dir.join("c"));
+        first.write().unwrap();
+        second.write().unwrap();
+        assert_eq!(paths(&dir).len(), 2);
+    }
+
+    #[test]
+    fn older_history_lines_are_read() {
+        let entry = HistoryEntry::parse("/tmp/file");
+        assert_eq!(entry.path, PathBuf::from("/tmp/file"));
+        assert!(!entry.pinned);
+        let entry = HistoryEntry::parse("/tmp/file\t4\t2\t1\t100");
+        assert_eq!(entry.cursor, (4, 2));
+        assert_eq!(entry.opened_at, 100);
+        assert!(!entry.pinned);
+        let entry = HistoryEntry::parse("/tmp/file\t4\t2\t1\t100\t1");
+        assert_eq!(entry.path, PathBuf::from("/tmp/file"));
+        assert!(entry.pinned);
+        assert_eq!(entry.open_count, 1);
+        let entry = HistoryEntry::parse("/tmp/file\t4\t2\t1\t100\t0\t7");
+        assert_eq!(entry.path, PathBuf::from("/tmp/file"));
+        assert_eq!(entry.open_count, 7);
     }
 }
@@ -0,0 +1,525 @@
+use crate::action::{Action, ActionResult};
+use crate::archive::{extract, split_archive_path};
+use crate::component::component_utils::default_block;
+use crate::config::theme::Theme;
+use directories::BaseDirs;
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::Frame;
+use std::fs;
+use std::fs::File;
+use std::io::{self, ErrorKind, Write};
+use std::path::{absolute, Path, PathBuf};
+use std::time::SystemTime;
+use tui_textarea::{CursorMove, TextArea};
+
+/// Where the trash is kept inside the user's data directory
+const TRASH_DIR_NAME: &str = "Trash";
+const TRASH_INFO_EXTENSION: &str = "trashinfo";
+
+/// A change to the file system made from the file selector.
+///
+/// Duplicating, moving, trashing and extracting apply to every marked file at once.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) enum FileOperation {
+    CreateFile,
+    CreateFolder,
+    Rename(PathBuf),
+    Duplicate(Vec<PathBuf>),
+    Move(Vec<PathBuf>),
+    Trash(Vec<PathBuf>),
+    /// Copies entries out of an archive into a folder
+    Extract(Vec<PathBuf>),
+}
+
+impl FileOperation {
+    fn title(&self) -> String {
+        match self {
+            FileOperation::CreateFile => " New file ".to_string(),
+            FileOperation::CreateFolder => " New folder ".to_string(),
+            FileOperation::Rename(_) => " Rename to ".to_string(),
+            FileOperation::Duplicate(sources) if sources.len() == 1 => " Duplicate as ".to_string(),
+            FileOperation::Duplicate(sources) => format!(" Copy {} items to ", sources.len()),
+            FileOperation::Move(sources) if sources.len() == 1 => " Move to ".to_string(),
+            FileOperation::Move(sources) => format!(" Move {} items to ", sources.len()),
+            FileOperation::Trash(_) => " Move to trash ".to_string(),
+            FileOperation::Extract(sources) if sources.len() == 1 => " Extract to ".to_string(),
+            FileOperation::Extract(sources) => format!(" Extract {} items to ", sources.len()),
+        }
+    }
+    /// Text the prompt for this operation starts with.
+    fn initial_input(&self, current_path: &Path) -> String {
+        match self {
+            FileOperation::CreateFile | FileOperation::CreateFolder => String::new(),
+            FileOperation::Rename(path) => file_name(path),
+            FileOperation::Duplicate(sources) if sources.len() == 1 => {
+                let path = &sources[0];
+                let stem = path.file_stem().unwrap_or_default().display();
+                match path.extension() {
+                    Some(extension) => format!("{stem} copy.{}", extension.display()),
+                    None => format!("{stem} copy"),
+                }
+            }
+            FileOperation::Duplicate(_) | FileOperation::Move(_) => {
+                format!("{}/", current_path.display())
+            }
+            FileOperation::Trash(_) => String::new(),
+            FileOperation::Extract(sources) => {
+                let archive = sources.first().and_then(|s| split_archive_path(s));
+                let folder = archive.and_then(|(archive, _)| archive.parent());
+                format!("{}/", folder.unwrap_or(current_path).display())
+            }
+        }
+    }
+    /// The files and folders the operation changes.
+    pub fn sources(&self) -> impl Iterator<Item = &PathBuf> {
+        match self {
+            FileOperation::CreateFile | FileOperation::CreateFolder => [].iter(),
+            FileOperation::Rename(source) => std::slice::from_ref(source).iter(),
+            FileOperation::Duplicate(sources)
+            | FileOperation::Move(sources)
+            | FileOperation::Trash(sources)
+            | FileOperation::Extract(sources) => sources.iter(),
+        }
+    }
+    /// The paths the operation creates when applied to `target`.
+    ///
+    /// Files duplicated or moved into a folder, or extracted, keep their names.
+    pub fn destinations(&self, target: &Path) -> Vec<PathBuf> {
+        match self {
+            FileOperation::CreateFile | FileOperation::CreateFolder | FileOperation::Rename(_) => {
+                vec![target.to_path_buf()]
+            }
+            FileOperation::Duplicate(sources) | FileOperation::Move(sources)
+                if target.is_dir() =>
+            {
+                let into_target = |source: &PathBuf| target.join(file_name(source));
+                sources.iter().map(into_target).collect()
+            }
+            FileOperation::Duplicate(sources) | FileOperation::Move(sources) => {
+                vec![target.to_path_buf(); sources.len()]
+            }
+            FileOperation::Trash(_) => Vec::new(),
+            FileOperation::Extract(sources) => {
+                let into_target = |source: &PathBuf| target.join(file_name(source));
+                sources.iter().map(into_target).collect()
+            }
+        }
+    }
+    /// Fails if the operation cannot be applied to `target`: files or folders created where
+    /// something already exists, several files copied or moved to something else than a
+    /// folder, files extracted from outside an archive, or what it changes being destroyed,
+    /// like a file replacing itself or a folder copied or moved inside itself.
+    ///
+    /// Checked before asking to replace the existing destinations, which are trashed first.
+    pub fn validate(&self, target: &Path) -> io::Result<()> {
+        if let FileOperation::CreateFile | FileOperation::CreateFolder = self
+            && fs::symlink_metadata(target).is_ok()
+        {
+            let message = format!("{} already exists", file_name(target));
+            return Err(io::Error::new(ErrorKind::AlreadyExists, message));
+        }
+        if let FileOperation::Duplicate(sources) | FileOperation::Move(sources) = self
+            && sources.len() > 1
+            && !target.is_dir()
+        {
+            let message = format!("{} is not a folder", target.display());
+            return Err(io::Error::new(ErrorKind::NotADirectory, message));
+        }
+        if let FileOperation::Extract(sources) = self
+            && let Some(source) = sources.iter().find(|s| split_archive_path(s).is_none())
+        {
+            let message = format!("{} is not in an archive", source.display());
+            return Err(io::Error::new(ErrorKind::InvalidInput, message));
+        }
+        let destinations = self.destinations(target);
+        for (source, destination) in self.sources().zip(&destinations) {
+            let name = file_name(source);
+            if destination == source {
+                let message = format!("{name} would replace itself");
+                return Err(io::Error::new(ErrorKind::InvalidInput, message));
+            }
+            let copied = matches!(self, FileOperation::Duplicate(_) | FileOperation::Move(_));
+            if copied && destination.starts_with(source) {
+                let message = format!("{name} can't be put inside itself");
+                return Err(io::Error::new(ErrorKind::InvalidInput, message));
+            }
+        }
+        Ok(())
+    }
+    /// Applies the operation, creating `target`. Stops at the first file that fails.
+    pub fn apply(&self, target: &Path) -> io::Result<()> {
+        self.validate(target)?;
+        match self {
+            FileOperation::CreateFile => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                File::create_new(target).map(|_| ())
+            }
+            FileOperation::CreateFolder => fs::create_dir_all(target),
+            FileOperation::Rename(source) => move_path(source, target),
+            FileOperation::Duplicate(sources) | FileOperation::Move(sources) => {
+                let destinations = self.destinations(target);
+                for (source, destination) in sources.iter().zip(destinations) {
+                    match self {
+                        FileOperation::Duplicate(_) => copy_recursively(source, &destination)?,
+                        _ => move_path(source, &destination)?,
+                    }
+                }
+                Ok(())
+            }
+            FileOperation::Trash(sources) => sources.iter().try_for_each(|s| move_to_trash(s)),
+            FileOperation::Extract(sources) => {
+                fs::create_dir_all(target)?;
+                for source in sources {
+                    let Some((archive, inner)) = split_archive_path(source) else {
+                        let message = format!("{} is not in an archive", source.display());
+                        return Err(io::Error::new(ErrorKind::InvalidInput, message));
+                    };
+                    extract(archive, inner, target)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A text box asking where a file operation should create its file.
+pub(super) struct OperationPrompt<'a> {
+    pub operation: FileOperation,
+    text_area: TextArea<'a>,
+}
+
+impl OperationPrompt<'_> {
+    pub fn new(operation: FileOperation, current_path: &Path, theme: &Theme) -> Self {
+        let title = Line::from(operation.title()).left_aligned();
+        let mut text_area = TextArea::default();
+        text_area.set_block(default_block(theme).title_top(title));
+        text_area.insert_str(operation.initial_input(current_path));
+        Self {
+            operation,
+            text_area,
+        }
+    }
+    pub fn input(&self) -> &str {
+        &self.text_area.lines()[0]
+    }
+    pub fn handle_action(&mut self, action: &Action) -> ActionResult {
+        let changed = match action {
+            Action::Character(c) => {
+                self.text_area.insert_char(*c);
+                true
+            }
+            Action::PasteText(text) => self.text_area.insert_str(text.replace('\n', "")),
+            Action::Backspace => self.text_area.delete_char(),
+            Action::Delete => self.text_area.delete_next_char(),
+            Action::Left => {
+                self.text_area.move_cursor(CursorMove::Back);
+                true
+            }
+            Action::Right => {
+                self.text_area.move_cursor(CursorMove::Forward);
+                true
+            }
+            _ => return ActionResult::consumed(false),
+        };
+        ActionResult::consumed(changed)
+    }
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(&self.text_area, area);
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().display().to_string()
+}
+
+/// Moves a file or folder, copying it over when it is moved to another file system.
+fn move_path(source: &Path, target: &Path) -> io::Result<()> {
+    match fs::rename(source, target) {
+        Err(e) if e.kind() == ErrorKind::CrossesDevices => {
+            copy_recursively(source, target)?;
+            remove_recursively(source)
+        }
+        result => result,
+    }
+}
+
+/// Copies a file, or a folder with everything inside it. Symbolic links are copied as links
+/// where they can be created.
+fn copy_recursively(source: &Path, target: &Path) -> io::Result<()> {
+    if target.exists() {
+        return Err(io::Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists", target.display()),
+        ));
+    }
+    let file_type = fs::symlink_metadata(source)?.file_type();
+    if file_type.is_symlink() {
+        copy_symlink(source, target)
+    } else if file_type.is_dir() {
+        fs::create_dir(target)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_recursively(&entry.path(), &target.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(source, target).map(|_| ())
+    }
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, target)
+}
+
+/// Copies what the link points to, since links need special rights on other platforms.
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, target: &Path) -> io::Result<()> {
+    fs::copy(source, target).map(|_| ())
+}
+
+fn remove_recursively(path: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Moves a file or folder to the user's trash, following the freedesktop.org trash
+/// specification, so file managers can restore it.
+pub(super) fn move_to_trash(path: &Path) -> io::Result<()> {
+    let Some(base_dirs) = BaseDirs::new() else {
+        return Err(io::Error::new(ErrorKind::NotFound, "No home directory"));
+    };
+    move_to_trash_at(path, &base_dirs.data_dir().join(TRASH_DIR_NAME))
+}
+
+fn move_to_trash_at(path: &Path, trash: &Path) -> io::Result<()> {
+    let path = absolute(path)?;
+    let files_dir = trash.join("files");
+    let info_dir = trash.join("info");
+    fs::create_dir_all(&files_dir)?;
+    fs::create_dir_all(&info_dir)?;
+    let name = file_name(&path);
+    // The info file is created first, so two deletions never pick the same name
+    let (trashed_name, mut info_file) = (1..)
+        .map(|i| match i {
+            1 => name.clone(),
+            i => format!("{name}.{i}"),
+        })
+        .find_map(|candidate| {
+            if files_dir.join(&candidate).exists() {
+                return None;
+            }
+            let info = info_dir.join(format!("{candidate}.{TRASH_INFO_EXTENSION}"));
+            match File::create_new(info) {
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => None,
+                result => Some(result.map(|file| (candidate, file))),
+            }
+        })
+        .unwrap()?;
+    let info = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        percent_encode(&path),
+        deletion_date()
+    );
+    let info_path = info_dir.join(format!("{trashed_name}.{TRASH_INFO_EXTENSION}"));
+    let moved = info_file
+        .write_all(info.as_bytes())
+        .and_then(|_| move_path(&path, &files_dir.join(&trashed_name)));
+    if moved.is_err() {
+        let _ = fs::remove_file(info_path);
+    }
+    moved
+}
+
+/// Encodes the path as the trash info file expects it, like a URL path.
+fn percent_encode(path: &Path) -> String {
+    let mut encoded = String::new();
+    for byte in path.as_os_str().as_encoded_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(*byte as char)
+            }
+            byte => encoded += &format!("%{byte:02X}"),
+        }
+    }
+    encoded
+}
+
+/// The current local time, formatted as `YYYY-MM-DDThh:mm:ss`.
+#[cfg(unix)]
+fn deletion_date() -> String {
+    let now = unix_time();
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    unsafe { libc::localtime_r(&(now as libc::time_t), &mut tm) };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+/// The current time, formatted the same way. Other platforms are only given it in UTC.
+#[cfg(not(unix))]
+fn deletion_date() -> String {
+    use crate::util::DateTime;
+    let now = DateTime::from_timestamp(unix_time());
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        now.year, now.month, now.day, now.hour, now.minute, now.second
+    )
+}
+
+fn unix_time() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn trashed_files_keep_where_they_were() {
+        let dir = TempDir::new("trash-info");
+        let trash = dir.join("Trash");
+        let file = dir.join("my file.txt");
+        fs::write(&file, "contents").unwrap();
+        move_to_trash_at(&file, &trash).unwrap();
+        assert!(!file.exists());
+        let trashed = fs::read_to_string(trash.join("files/my file.txt")).unwrap();
+        assert_eq!(trashed, "contents");
+        let info = fs::read_to_string(trash.join("info/my file.txt.trashinfo")).unwrap();
+        let expected_path = format!("Path={}", percent_encode(&file));
+        assert!(info.starts_with("[Trash Info]\n"));
+        assert!(info.contains(&expected_path));
+        assert!(expected_path.ends_with("my%20file.txt"));
+        assert!(info.contains("DeletionDate="));
+    }
+
+    #[test]
+    fn trashed_files_with_the_same_name_are_kept_apart() {
+        let dir = TempDir::new("trash-names");
+        let trash = dir.join("Trash");
+        for contents in ["first", "second"] {
+            let folder = dir.join("folder");
+            fs::create_dir_all(&folder).unwrap();
+            fs::write(folder.join("file"), contents).unwrap();
+            move_to_trash_at(&folder, &trash).unwrap();
+        }
+        let first = fs::read_to_string(trash.join("files/folder/file")).unwrap();
+        let second = fs::read_to_string(trash.join("files/folder.2/file")).unwrap();
+        assert_eq!((first.as_str(), second.as_str()), ("first", "second"));
+        assert!(trash.join("info/folder.2.trashinfo").is_file());
+    }
+
+    #[test]
+    fn duplicates_copy_folders_recursively() {
+        let dir = TempDir::new("duplicate");
+        let source = dir.join("source");
+        fs::create_dir_all(source.join("inner")).unwrap();
+        fs::write(source.join("inner/file"), "contents").unwrap();
+        let operation = FileOperation::Duplicate(vec![source.clone()]);
+        let target = dir.join("copy");
+        operation.apply(&target).unwrap();
+        let copied = fs::read_to_string(dir.join("copy/inner/file")).unwrap();
+        assert_eq!(copied, "contents");
+        operation.apply(&target).unwrap();
+        assert!(dir.join("copy/source/inner/file").is_file());
+        assert!(operation.apply(&target).is_err());
+    }
+
+    #[test]
+    fn marked_files_are_moved_into_the_folder() {
+        let dir = TempDir::new("move");
+        let folder = dir.join("folder");
+        fs::create_dir_all(&folder).unwrap();
+        let sources = vec![dir.join("a"), dir.join("b")];
+        for source in &sources {
+            fs::write(source, "contents").unwrap();
+        }
+        let operation = FileOperation::Move(sources);
+        assert!(operation.apply(&dir.join("missing")).is_err());
+        operation.apply(&folder).unwrap();
+        assert!(folder.join("a").is_file() && folder.join("b").is_file());
+        assert!(!dir.join("a").exists());
+    }
+
+    #[test]
+    fn operations_destroying_their_sources_are_rejected() {
+        let dir = TempDir::new("validate");
+        let folder = dir.join("folder");
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("file"), "contents").unwrap();
+        let rename = FileOperation::Rename(folder.clone());
+        assert!(rename.apply(&folder).is_err());
+        let duplicate = FileOperation::Duplicate(vec![folder.clone()]);
+        assert!(duplicate.apply(&folder.join("copy")).is_err());
+        let moved = FileOperation::Move(vec![folder.clone()]);
+        assert!(moved.apply(&folder).is_err());
+        assert!(duplicate.validate(&dir.join("copy")).is_ok());
+        assert!(folder.join("file").is_file());
+        assert!(!folder.join("copy").exists());
+    }
+
+    #[test]
+    fn files_and_folders_are_not_created_over_existing_ones() {
+        let dir = TempDir::new("create");
+        let folder = dir.join("folder");
+        fs::create_dir_all(&folder).unwrap();
+        let file = folder.join("file");
+        fs::write(&file, "contents").unwrap();
+        assert!(FileOperation::CreateFolder.apply(&folder).is_err());
+        assert!(FileOperation::CreateFile.apply(&file).is_err());
+        assert!(FileOperation::CreateFile.validate(&folder).is_err());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "contents");
+        let new = folder.join("new");
+        FileOperation::CreateFolder.apply(&new).unwrap();
+        assert!(new.is_dir());
+    }
+
+    #[test]
+    fn several_files_are_only_copied_into_folders() {
+        let dir = TempDir::with_files("validate", &["a", "b", "target"]);
+        let sources = vec![dir.join("a"), dir.join("b")];
+        let moved = FileOperation::Move(sources);
+        let error = moved.validate(&dir.join("target")).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotADirectory);
+        assert!(moved.validate(&dir.join("missing")).is_err());
+        fs::create_dir(dir.join("folder")).unwrap();
+        assert!(moved.validate(&dir.join("folder")).is_ok());
+        let single = FileOperation::Move(vec![dir.join("a")]);
+        assert!(single.validate(&dir.join("target")).is_ok());
+    }
+
+    #[test]
+    fn nothing_is_extracted_if_a_file_is_outside_archives() {
+        let dir = TempDir::with_files("extract", &["plain.txt"]);
+        let archive = dir.join("files.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("inner.txt", options).unwrap();
+        writer.write_all(b"inner").unwrap();
+        writer.finish().unwrap();
+
+        let sources = vec![archive.join("inner.txt"), dir.join("plain.txt")];
+        let target = dir.join("extracted");
+        assert!(FileOperation::Extract(sources).apply(&target).is_err());
+        assert!(!target.exists());
+        let extract = FileOperation::Extract(vec![archive.join("inner.txt")]);
+        extract.apply(&target).unwrap();
+        let extracted = fs::read_to_string(target.join("inner.txt")).unwrap();
+        assert_eq!(extracted, "inner");
+    }
+}
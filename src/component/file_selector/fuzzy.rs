@@ -0,0 +1,137 @@
+/// Score of every matched character
+const MATCH_SCORE: i64 = 1;
+/// Bonus for a character right after the previously matched one
+const CONSECUTIVE_BONUS: i64 = 5;
+/// Bonus for a character starting a path segment
+const SEGMENT_START_BONUS: i64 = 10;
+/// Bonus for a character starting a word inside a segment, like after `_` or in camel case
+const WORD_START_BONUS: i64 = 6;
+/// Bonus for a character of the file name, the last segment of the path
+const FILE_NAME_BONUS: i64 = 2;
+/// Penalty for every character skipped between two matched characters
+const GAP_PENALTY: i64 = 1;
+
+/// Where the characters of a pattern were found in a text, and how good of a match it is.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Indices of the matched characters in the text, in characters, not bytes
+    pub indices: Vec<usize>,
+}
+
+/// Matches the characters of the pattern in order anywhere in the path, ignoring case and
+/// whitespace in the pattern.
+///
+/// Of all the ways the pattern can be found, picks the best scoring one, preferring characters
+/// that start path segments or words, follow each other, or are part of the file name.
+pub fn fuzzy_match(pattern: &str, path: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch::default());
+    }
+    let text: Vec<char> = path.chars().collect();
+    let lower: Vec<char> = text
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    if pattern.len() > text.len() {
+        return None;
+    }
+    let file_name_start = text.iter().rposition(|c| *c == '/').map_or(0, |i| i + 1);
+    let bonuses: Vec<i64> = (0..text.len())
+        .map(|i| char_bonus(&text, i, file_name_start))
+        .collect();
+    // scores[i][j] is the best score of the pattern up to `i`, with `i` matched at `j`,
+    // reached from the character of the pattern before it being matched at `from[i][j]`
+    let mut scores: Vec<Vec<Option<i64>>> = vec![vec![None; text.len()]; pattern.len()];
+    let mut from: Vec<Vec<usize>> = vec![vec![0; text.len()]; pattern.len()];
+    for (i, pattern_char) in pattern.iter().enumerate() {
+        // Best score of the previous pattern character matched before `j - 1`, minus the gap
+        let mut gapped: Option<(i64, usize)> = None;
+        for j in 0..text.len() {
+            if lower[j] == *pattern_char {
+                let score = if i == 0 {
+                    Some((MATCH_SCORE + bonuses[j], 0))
+                } else {
+                    let consecutive = j
+                        .checked_sub(1)
+                        .and_then(|k| Some((scores[i - 1][k]? + CONSECUTIVE_BONUS, k)));
+                    [consecutive, gapped]
+                        .into_iter()
+                        .flatten()
+                        .max_by_key(|(score, _)| *score)
+                        .map(|(score, k)| (score + MATCH_SCORE + bonuses[j], k))
+                };
+                if let Some((score, k)) = score {
+                    scores[i][j] = Some(score);
+                    from[i][j] = k;
+                }
+            }
+            if i > 0 {
+                let previous = j.checked_sub(1).and_then(|k| Some((scores[i - 1][k]?, k)));
+                gapped = [gapped, previous]
+                    .into_iter()
+                    .flatten()
+                    .map(|(score, k)| (score - GAP_PENALTY, k))
+                    .max_by_key(|(score, _)| *score);
+            }
+        }
+    }
+    let last = pattern.len() - 1;
+    let (mut j, score) = scores[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| Some((j, (*score)?)))
+        .max_by_key(|(_, score)| *score)?;
+    let mut indices = vec![0; pattern.len()];
+    for i in (0..pattern.len()).rev() {
+        indices[i] = j;
+        j = from[i][j];
+    }
+    Some(FuzzyMatch { score, indices })
+}
+
+fn char_bonus(text: &[char], index: usize, file_name_start: usize) -> i64 {
+    let current = text[index];
+    let bonus = match index.checked_sub(1).map(|i| text[i]) {
+        None | Some('/') => SEGMENT_START_BONUS,
+        Some('_' | '-' | '.' | ' ') => WORD_START_BONUS,
+        Some(previous) if previous.is_lowercase() && current.is_uppercase() => WORD_START_BONUS,
+        _ => 0,
+    };
+    if index >= file_name_start {
+        bonus + FILE_NAME_BONUS
+    } else {
+        bonus
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn characters_must_appear_in_order() {
+        assert!(fuzzy_match("abc", "a/b/c").is_some());
+        assert!(fuzzy_match("cba", "a/b/c").is_none());
+        assert!(fuzzy_match("ABC", "xaxbxc").is_some());
+    }
+
+    #[test]
+    fn segment_starts_are_preferred() {
+        let matched = fuzzy_match("fm", "src/foo/fmt/main.rs").unwrap();
+        assert_eq!(matched.indices, vec![8, 12]);
+    }
+
+    #[test]
+    fn closer_matches_score_higher() {
+        let consecutive = fuzzy_match("app", "src/app.rs").unwrap();
+        let spread = fuzzy_match("app", "src/a/p/xp.rs").unwrap();
+        assert!(consecutive.score > spread.score);
+        assert_eq!(consecutive.indices, vec![4, 5, 6]);
+    }
+}
@@ -1,28 +1,61 @@
 use crate::action::{ActionResult, SelectorType};
 use crate::component::file_selector::create_default_text_area;
+use crate::config::theme::Theme;
+use directories::UserDirs;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::Frame;
+use std::path::{Component, Path, PathBuf};
 use tui_textarea::{CursorMove, TextArea};
 
 const FILE_NAME_TITLE: &str = " File Name ";
 const SEARCH_BOX_TITLE: &str = " Search ";
 
+/// Resolves a path typed by the user against `base`.
+///
+/// The path can be absolute, start with `~` for the home directory, or be relative to `base`.
+/// `.` and `..` are resolved without touching the file system.
+pub(super) fn resolve_typed_path(base: &Path, typed: &str) -> PathBuf {
+    let home = || UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+    let path = match typed.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => match home() {
+            Some(home) => home.join(rest.trim_start_matches('/')),
+            None => base.join(typed),
+        },
+        _ => base.join(typed),
+    };
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+    }
+    resolved
+}
+
 #[derive(Default)]
 pub(super) struct FileSelectorInput<'a> {
     text_area: Option<TextArea<'a>>,
     filter: Option<TextArea<'a>>,
     selector_type: SelectorType,
+    theme: Theme,
 }
 
 impl FileSelectorInput<'_> {
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
     pub fn change_type(&mut self, selector_type: SelectorType) {
         self.selector_type = selector_type;
         match self.selector_type {
             SelectorType::PickFolder | SelectorType::PickFile => {
-                self.filter = Some(create_default_text_area(SEARCH_BOX_TITLE))
+                self.filter = Some(create_default_text_area(SEARCH_BOX_TITLE, &self.theme))
             }
             SelectorType::NewFile => {
-                self.text_area = Some(create_default_text_area(FILE_NAME_TITLE))
+                self.text_area = Some(create_default_text_area(FILE_NAME_TITLE, &self.theme))
             }
         }
     }
@@ -49,6 +82,19 @@ impl FileSelectorInput<'_> {
         };
         None
     }
+    /// The text of the box being typed in, the filter if it is open.
+    pub fn current_text(&self) -> Option<&str> {
+        let text_area = self.filter.as_ref().or(self.text_area.as_ref())?;
+        Some(&text_area.lines()[0])
+    }
+    /// Replaces the text of the box being typed in.
+    pub fn set_text(&mut self, text: &str) {
+        if let Some(text_area) = self.filter.as_mut().or(self.text_area.as_mut()) {
+            text_area.move_cursor(CursorMove::End);
+            text_area.delete_line_by_head();
+            text_area.insert_str(text);
+        }
+    }
     pub fn delete(&mut self) -> bool {
         if let Some(text_area) = self.filter.as_mut() {
             return text_area.delete_next_char();
@@ -112,7 +158,7 @@ impl FileSelectorInput<'_> {
         if self.selector_type == SelectorType::PickFolder {
             return ActionResult::not_consumed(false);
         }
-        self.filter = Some(create_default_text_area(SEARCH_BOX_TITLE));
+        self.filter = Some(create_default_text_area(SEARCH_BOX_TITLE, &self.theme));
         ActionResult::consumed(true)
     }
     pub fn cancel(&mut self) -> bool {
@@ -122,3 +168,18 @@ impl FileSelectorInput<'_> {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_paths_are_resolved_against_the_base() {
+        let base = Path::new("/home/user/project");
+        let resolve = |typed| resolve_typed_path(base, typed);
+        assert_eq!(resolve("src/main.rs"), Path::new("/home/user/project/src/main.rs"));
+        assert_eq!(resolve("../other/./file"), Path::new("/home/user/other/file"));
+        assert_eq!(resolve("/etc/hosts"), Path::new("/etc/hosts"));
+        assert_eq!(resolve("~file"), Path::new("/home/user/project/~file"));
+    }
+}
@@ -1,22 +1,34 @@
+use crate::archive::{is_archive, ArchiveEntry};
 use crate::component::component_utils::default_block;
 use crate::component::preview_component::PreviewComponent;
 use crate::component::Component;
+use crate::config::app_config::Column;
+use crate::config::theme::Theme;
+use crate::util::DateTime;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::prelude::Line;
 use ratatui::Frame;
+use std::fs::Metadata;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use tui_textarea::TextArea;
 
 pub mod component;
 pub mod file_history;
 pub mod file_history_saver;
+mod file_finder;
+mod file_operations;
+mod fuzzy;
 mod input;
+mod places;
+pub mod quick_switcher;
+mod sort;
 
 pub(super) const HIGHLIGHT_SYMBOL: &str = " > ";
 
-pub(super) fn create_default_text_area(title: &'_ str) -> TextArea<'_> {
+pub(super) fn create_default_text_area<'a>(title: &'a str, theme: &Theme) -> TextArea<'a> {
     let title = Line::raw(title).left_aligned();
-    let block = default_block().title_top(title);
+    let block = default_block(theme).title_top(title);
     let mut text_area = TextArea::default();
     text_area.set_block(block);
     text_area
@@ -27,11 +39,128 @@ pub(super) enum PathChild {
     File {
         full_file_name: String,
         icon: Option<String>,
+        metadata: Option<ChildMetadata>,
+    },
+    Folder {
+        name: String,
+        metadata: Option<ChildMetadata>,
     },
-    Folder(String),
     MoveUp,
 }
 
+/// Size, modification time and permissions of a listed file or folder.
+#[derive(Clone, Debug)]
+pub(super) struct ChildMetadata {
+    size: u64,
+    modified: Option<SystemTime>,
+    is_dir: bool,
+    /// Permission bits, like `0o644`
+    permissions: u32,
+}
+
+impl From<&Metadata> for ChildMetadata {
+    fn from(metadata: &Metadata) -> Self {
+        Self {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            is_dir: metadata.is_dir(),
+            permissions: permission_bits(metadata),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn permission_bits(metadata: &Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o777
+}
+
+/// Other platforms only tell whether the file is read-only.
+#[cfg(not(unix))]
+fn permission_bits(metadata: &Metadata) -> u32 {
+    if metadata.permissions().readonly() {
+        0o444
+    } else {
+        0o666
+    }
+}
+
+impl From<&ArchiveEntry> for ChildMetadata {
+    fn from(entry: &ArchiveEntry) -> Self {
+        Self {
+            size: entry.size,
+            modified: entry.modified,
+            is_dir: entry.is_dir,
+            permissions: entry.permissions,
+        }
+    }
+}
+
+impl ChildMetadata {
+    pub(super) fn column(&self, column: Column) -> String {
+        match column {
+            Column::Permissions => {
+                let kind = if self.is_dir { 'd' } else { '-' };
+                let permissions = (0..9).map(|i| {
+                    let set = self.permissions & (0o400 >> i) != 0;
+                    match (set, i % 3) {
+                        (false, _) => '-',
+                        (true, 0) => 'r',
+                        (true, 1) => 'w',
+                        (true, _) => 'x',
+                    }
+                });
+                std::iter::once(kind).chain(permissions).collect()
+            }
+            Column::Size if self.is_dir => format!("{:>5}", "-"),
+            Column::Size => format!("{:>5}", human_size(self.size)),
+            Column::Modified => {
+                let Some(modified) = self.modified else {
+                    return blank_column(column);
+                };
+                let timestamp = modified
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs() as i64);
+                let time = DateTime::from_timestamp(timestamp);
+                format!(
+                    "{:04}-{:02}-{:02} {:02}:{:02}",
+                    time.year, time.month, time.day, time.hour, time.minute
+                )
+            }
+        }
+    }
+}
+
+fn column_width(column: Column) -> usize {
+    match column {
+        Column::Permissions => 10,
+        Column::Size => 5,
+        Column::Modified => 16,
+    }
+}
+
+fn blank_column(column: Column) -> String {
+    " ".repeat(column_width(column))
+}
+
+/// The size in bytes, like `512B`, `1.5K` or `34M`.
+fn human_size(size: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "K", "M", "G", "T"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{size}B")
+    } else if value < 10.0 {
+        format!("{value:.1}{}", UNITS[unit])
+    } else {
+        format!("{value:.0}{}", UNITS[unit])
+    }
+}
+
 impl PathChild {
     pub fn file(file_name: String, path_buf: PathBuf) -> Self {
         let extension = path_buf
@@ -39,20 +168,53 @@ impl PathChild {
             .unwrap_or_default()
             .display()
             .to_string();
-        let icon = icon_for_file(&file_name, &extension);
+        let icon = if is_archive(&path_buf) {
+            Some("".to_string())
+        } else {
+            icon_for_file(&file_name, &extension)
+        };
         Self::File {
             full_file_name: file_name,
             icon,
+            metadata: None,
+        }
+    }
+    pub fn folder(name: String) -> Self {
+        Self::Folder {
+            name,
+            metadata: None,
+        }
+    }
+    pub fn with_metadata<M: Into<ChildMetadata>>(mut self, metadata: M) -> Self {
+        if let PathChild::File { metadata: m, .. } | PathChild::Folder { metadata: m, .. } =
+            &mut self
+        {
+            *m = Some(metadata.into());
+        }
+        self
+    }
+    fn name(&self) -> &str {
+        match self {
+            PathChild::File { full_file_name, .. } => full_file_name,
+            PathChild::Folder { name, .. } => name,
+            PathChild::MoveUp => "..",
+        }
+    }
+    fn metadata(&self) -> Option<&ChildMetadata> {
+        match self {
+            PathChild::File { metadata, .. } | PathChild::Folder { metadata, .. } => {
+                metadata.as_ref()
+            }
+            PathChild::MoveUp => None,
         }
     }
     fn filter<F: AsRef<str>>(&self, filter: F) -> bool {
         let filter = filter.as_ref();
         match self {
-            PathChild::File {
-                full_file_name,
-                icon: _,
-            } => full_file_name.to_lowercase().contains(filter),
-            PathChild::Folder(f) => f.to_lowercase().contains(filter),
+            PathChild::File { full_file_name, .. } => {
+                full_file_name.to_lowercase().contains(filter)
+            }
+            PathChild::Folder { name, .. } => name.to_lowercase().contains(filter),
             PathChild::MoveUp => true,
         }
     }
@@ -62,6 +224,7 @@ impl PathChild {
             PathChild::File {
                 full_file_name,
                 icon,
+                ..
             } => {
                 if let Some(icon) = icon {
                     format!("{icon} {full_file_name}")
@@ -69,10 +232,23 @@ impl PathChild {
                     full_file_name.to_string()
                 }
             }
-            PathChild::Folder(path) => format!(" {}", path),
+            PathChild::Folder { name, .. } => format!(" {}", name),
             PathChild::MoveUp => "...".to_string(),
         }
     }
+    /// The columns shown before the name, left blank if the metadata is unknown.
+    fn to_columns(&self, columns: &[Column]) -> String {
+        let mut text = String::new();
+        for column in columns {
+            let value = match self.metadata() {
+                Some(metadata) => metadata.column(*column),
+                None => blank_column(*column),
+            };
+            text += &value;
+            text += "  ";
+        }
+        text
+    }
 }
 
 pub(super) fn label_for_file<P: AsRef<Path>>(path: P) -> String {
@@ -0,0 +1,308 @@
+use crate::component::component_utils::default_block;
+use crate::component::file_selector::HIGHLIGHT_SYMBOL;
+use crate::config::theme::Theme;
+use crate::config::Config;
+use directories::UserDirs;
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::widgets::{HighlightSpacing, List, ListItem, ListState};
+use ratatui::Frame;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const BOOKMARKS_FILE_NAME: &str = "bookmarks.txt";
+const RECENT_DIRECTORIES_FILE_NAME: &str = "recent_directories.txt";
+/// Most recently visited directories remembered
+const MAX_RECENT: usize = 10;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum PlaceKind {
+    Home,
+    Config,
+    Data,
+    Bookmark,
+    Recent,
+}
+
+#[derive(Clone)]
+struct Place {
+    kind: PlaceKind,
+    path: PathBuf,
+}
+
+impl Place {
+    fn label(&self) -> String {
+        let name = || {
+            let name = self.path.file_name().unwrap_or(self.path.as_os_str());
+            name.display().to_string()
+        };
+        match self.kind {
+            PlaceKind::Home => " Home".to_string(),
+            PlaceKind::Config => " Config".to_string(),
+            PlaceKind::Data => " Data".to_string(),
+            PlaceKind::Bookmark => format!(" {}", name()),
+            PlaceKind::Recent => format!(" {}", name()),
+        }
+    }
+}
+
+/// Directories to jump to from the file selector: the home, config and data directories, the
+/// folders bookmarked by the user and the ones files were recently picked from.
+///
+/// Bookmarks and recent directories are kept in `data_dir`, one path per line.
+#[derive(Default)]
+pub(super) struct Places {
+    data_dir: PathBuf,
+    fixed: Vec<Place>,
+    bookmarks: Vec<PathBuf>,
+    recent: Vec<PathBuf>,
+    focused: bool,
+    list_state: ListState,
+    theme: Theme,
+}
+
+impl Places {
+    pub fn load(&mut self, config: &Config) {
+        let app_config = &config.config;
+        self.data_dir = app_config.data_dir.clone();
+        self.theme = config.theme.clone();
+        let home = UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+        let fixed = [
+            (PlaceKind::Home, home),
+            (PlaceKind::Config, Some(app_config.config_dir.clone())),
+            (PlaceKind::Data, Some(app_config.data_dir.clone())),
+        ];
+        self.fixed = fixed
+            .into_iter()
+            .filter_map(|(kind, path)| Some(Place { kind, path: path? }))
+            .collect();
+        self.bookmarks = read_paths(&self.data_dir.join(BOOKMARKS_FILE_NAME));
+        self.recent = read_paths(&self.data_dir.join(RECENT_DIRECTORIES_FILE_NAME));
+    }
+    /// All places in the order they are listed. Recent directories that are already listed as
+    /// another place are left out.
+    fn places(&self) -> Vec<Place> {
+        let fixed = self.fixed.iter().cloned();
+        let bookmarks = self.bookmarks.iter().map(|path| Place {
+            kind: PlaceKind::Bookmark,
+            path: path.clone(),
+        });
+        let mut places: Vec<Place> = fixed.chain(bookmarks).collect();
+        for path in &self.recent {
+            if !places.iter().any(|place| &place.path == path) {
+                let path = path.clone();
+                places.push(Place {
+                    kind: PlaceKind::Recent,
+                    path,
+                });
+            }
+        }
+        places
+    }
+    pub fn focused(&self) -> bool {
+        self.focused
+    }
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        let selected = focused.then(|| self.list_state.selected().unwrap_or(0));
+        self.list_state.select(selected);
+    }
+    pub fn move_up(&mut self) -> bool {
+        match self.list_state.selected() {
+            Some(selected) if selected > 0 => {
+                self.list_state.select(Some(selected - 1));
+                true
+            }
+            _ => false,
+        }
+    }
+    pub fn move_down(&mut self) -> bool {
+        match self.list_state.selected() {
+            Some(selected) if selected + 1 < self.places().len() => {
+                self.list_state.select(Some(selected + 1));
+                true
+            }
+            _ => false,
+        }
+    }
+    pub fn selected(&self) -> Option<PathBuf> {
+        let selected = self.list_state.selected()?;
+        let place = self.places().into_iter().nth(selected)?;
+        Some(place.path)
+    }
+    pub fn is_bookmarked(&self, dir: &Path) -> bool {
+        self.bookmarks.iter().any(|bookmark| bookmark == dir)
+    }
+    /// Bookmarks the directory, or removes its bookmark if it already has one.
+    ///
+    /// Each file selector has its own places, so the bookmarks are read again first to keep
+    /// the ones changed by another selector since they were loaded. The same goes for the
+    /// other changes.
+    pub fn toggle_bookmark(&mut self, dir: &Path) -> io::Result<()> {
+        let file = self.data_dir.join(BOOKMARKS_FILE_NAME);
+        self.bookmarks = read_paths(&file);
+        if self.is_bookmarked(dir) {
+            self.bookmarks.retain(|bookmark| bookmark != dir);
+        } else {
+            self.bookmarks.push(dir.to_path_buf());
+        }
+        self.keep_selection_in_bounds();
+        write_paths(&file, &self.bookmarks)
+    }
+    /// Removes the selected bookmark or recent directory. The other places cannot be removed.
+    pub fn remove_selected(&mut self) -> io::Result<bool> {
+        let Some(selected) = self.list_state.selected() else {
+            return Ok(false);
+        };
+        let Some(place) = self.places().into_iter().nth(selected) else {
+            return Ok(false);
+        };
+        let (paths, file_name) = match place.kind {
+            PlaceKind::Bookmark => (&mut self.bookmarks, BOOKMARKS_FILE_NAME),
+            PlaceKind::Recent => (&mut self.recent, RECENT_DIRECTORIES_FILE_NAME),
+            _ => return Ok(false),
+        };
+        let file = self.data_dir.join(file_name);
+        *paths = read_paths(&file);
+        paths.retain(|path| path != &place.path);
+        write_paths(&file, paths)?;
+        self.keep_selection_in_bounds();
+        Ok(true)
+    }
+    /// Remembers the directory as the most recently visited one.
+    pub fn visit(&mut self, dir: &Path) -> io::Result<()> {
+        let file = self.data_dir.join(RECENT_DIRECTORIES_FILE_NAME);
+        self.recent = read_paths(&file);
+        self.recent.retain(|path| path != dir);
+        self.recent.insert(0, dir.to_path_buf());
+        self.recent.truncate(MAX_RECENT);
+        self.keep_selection_in_bounds();
+        write_paths(&file, &self.recent)
+    }
+    fn keep_selection_in_bounds(&mut self) {
+        let len = self.places().len();
+        if let Some(selected) = self.list_state.selected() {
+            self.list_state.select(Some(selected.min(len.saturating_sub(1))));
+        }
+    }
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, current_dir: &Path) {
+        let border_style = if self.focused {
+            self.theme.focused_border
+        } else {
+            self.theme.muted
+        };
+        let block = default_block(&self.theme)
+            .title_top(Line::raw(" Places ").left_aligned())
+            .border_style(border_style);
+        let items = self.places().into_iter().map(|place| {
+            let label = place.label();
+            let style = if place.path == current_dir {
+                self.theme.active
+            } else {
+                self.theme.muted
+            };
+            ListItem::new(label).style(style)
+        });
+        let list = List::new(items)
+            .highlight_style(self.theme.highlight)
+            .highlight_symbol(HIGHLIGHT_SYMBOL)
+            .highlight_spacing(HighlightSpacing::WhenSelected)
+            .block(block);
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+    }
+}
+
+fn read_paths(file: &Path) -> Vec<PathBuf> {
+    let Ok(contents) = fs::read_to_string(file) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+fn write_paths(file: &Path, paths: &[PathBuf]) -> io::Result<()> {
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let contents: String = paths
+        .iter()
+        .map(|path| format!("{}\n", path.display()))
+        .collect();
+    fs::write(file, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn places(dir: &Path) -> Places {
+        Places {
+            data_dir: dir.to_path_buf(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn paths_are_written_one_per_line() {
+        let dir = TempDir::new("places");
+        let file = dir.join("nested").join(BOOKMARKS_FILE_NAME);
+        let paths = vec![PathBuf::from("/a b"), PathBuf::from("/c")];
+        write_paths(&file, &paths).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "/a b\n/c\n");
+        assert_eq!(read_paths(&file), paths);
+        fs::write(&file, "/a\n\n/b\n").unwrap();
+        let expected = [PathBuf::from("/a"), PathBuf::from("/b")];
+        assert_eq!(read_paths(&file), expected);
+        assert!(read_paths(&dir.join("missing.txt")).is_empty());
+    }
+
+    #[test]
+    fn bookmarks_are_toggled() {
+        let dir = TempDir::new("places");
+        let mut places = places(&dir);
+        places.toggle_bookmark(Path::new("/a")).unwrap();
+        places.toggle_bookmark(Path::new("/b")).unwrap();
+        assert!(places.is_bookmarked(Path::new("/a")));
+        places.toggle_bookmark(Path::new("/a")).unwrap();
+        assert!(!places.is_bookmarked(Path::new("/a")));
+        let file = dir.join(BOOKMARKS_FILE_NAME);
+        assert_eq!(read_paths(&file), [PathBuf::from("/b")]);
+    }
+
+    #[test]
+    fn visited_directories_are_listed_most_recent_first() {
+        let dir = TempDir::new("places");
+        let mut places = places(&dir);
+        for i in 0..=MAX_RECENT {
+            places.visit(&PathBuf::from(format!("/{i}"))).unwrap();
+        }
+        places.visit(Path::new("/5")).unwrap();
+        let recent = read_paths(&dir.join(RECENT_DIRECTORIES_FILE_NAME));
+        assert_eq!(recent.len(), MAX_RECENT);
+        assert_eq!(recent[0], Path::new("/5"));
+        assert_eq!(recent[1], Path::new(&format!("/{MAX_RECENT}")));
+        let visits = recent.iter().filter(|path| *path == Path::new("/5"));
+        assert_eq!(visits.count(), 1);
+        assert!(!recent.contains(&PathBuf::from("/0")));
+    }
+
+    #[test]
+    fn changes_keep_the_places_saved_by_other_selectors() {
+        let dir = TempDir::new("places");
+        let mut home = places(&dir);
+        let mut editor = places(&dir);
+        home.toggle_bookmark(Path::new("/a")).unwrap();
+        editor.toggle_bookmark(Path::new("/b")).unwrap();
+        home.visit(Path::new("/c")).unwrap();
+        editor.visit(Path::new("/d")).unwrap();
+        let bookmarks = read_paths(&dir.join(BOOKMARKS_FILE_NAME));
+        assert_eq!(bookmarks, [PathBuf::from("/a"), PathBuf::from("/b")]);
+        let recent = read_paths(&dir.join(RECENT_DIRECTORIES_FILE_NAME));
+        assert_eq!(recent, [PathBuf::from("/d"), PathBuf::from("/c")]);
+    }
+}
@@ -0,0 +1,346 @@
+use crate::action::{Action, ActionResult, AsyncAction, AsyncActionSender, SelectorType};
+use crate::component::component_utils::{
+    center, center_horizontally, center_vertically, default_block, key_label_format,
+};
+use crate::component::effect_runner::EffectRunner;
+use crate::component::file_selector::file_history::HISTORY_FILE_NAME;
+use crate::component::file_selector::file_history_saver::{now, HistoryEntry};
+use crate::component::file_selector::fuzzy::fuzzy_match;
+use crate::component::file_selector::HIGHLIGHT_SYMBOL;
+use crate::component::{AppComponent, Component};
+use crate::config::effects::{dialog_enter, AnimationEvent};
+use crate::config::theme::Theme;
+use crate::config::Config;
+use crate::util::find_files;
+use crossterm::event::KeyEvent;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Clear, HighlightSpacing, List, ListDirection, ListItem, ListState};
+use ratatui::Frame;
+use std::env::current_dir;
+use std::path::{Path, PathBuf};
+use tokio_util::sync::CancellationToken;
+
+/// Most files listed from the working directory
+const MAX_DIRECTORY_FILES: usize = 5000;
+/// Most entries shown in the list
+const MAX_SHOWN: usize = 200;
+/// Frecency given to the open buffers, ranking them above the history
+const BUFFER_FRECENCY: f64 = 1000.0;
+/// How much the frecency of an entry weighs against how well it matches the filter
+const FRECENCY_WEIGHT: f64 = 2.0;
+
+#[derive(Default)]
+struct QuickSwitcherKeybinds {
+    up: String,
+    down: String,
+    confirm: String,
+    cancel: String,
+}
+
+impl QuickSwitcherKeybinds {
+    fn register_keybinds(&mut self, config: &Config) {
+        let keybinds = &config.keybindings;
+        let app_component = &AppComponent::FileDialog;
+        self.up = keybinds.get_key_string_or_default(Action::Up, app_component);
+        self.down = keybinds.get_key_string_or_default(Action::Down, app_component);
+        self.confirm = keybinds.get_key_string_or_default(Action::Confirm, app_component);
+        self.cancel = keybinds.get_key_string_or_default(Action::Cancel, app_component);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Source {
+    Buffer,
+    History,
+    Directory,
+}
+
+struct Candidate {
+    path: PathBuf,
+    /// Path shown in the list and matched against the filter
+    label: String,
+    source: Source,
+    frecency: f64,
+}
+
+/// A candidate matching the filter, with where the filter's characters are in its label.
+struct Match {
+    candidate: usize,
+    indices: Vec<usize>,
+}
+
+/// A popup to quickly jump to a file, listing the open buffers, the file history and the files
+/// under the working directory.
+///
+/// Files are ranked by frecency, how often and how recently they were opened, and fuzzy matched
+/// against what is typed.
+#[derive(Default)]
+pub struct QuickSwitcherComponent {
+    opened: bool,
+    data_dir: PathBuf,
+    root: PathBuf,
+    candidates: Vec<Candidate>,
+    matches: Vec<Match>,
+    filter: String,
+    list_state: ListState,
+    /// Stops listing the working directory once the switcher is hidden
+    cancellation_token: CancellationToken,
+    async_action_sender: Option<AsyncActionSender>,
+    effect_runner: EffectRunner,
+    keybinds: QuickSwitcherKeybinds,
+    theme: Theme,
+}
+
+impl QuickSwitcherComponent {
+    /// Shows the switcher with the given open buffers, and starts listing the working directory.
+    pub fn show(&mut self, buffers: &[PathBuf]) {
+        self.opened = true;
+        let effect = dialog_enter(self.theme.dialog_color());
+        self.effect_runner.add_effect(AnimationEvent::Dialog, effect);
+        self.root = current_dir().unwrap_or_default();
+        self.candidates.clear();
+        for path in buffers {
+            self.add_candidate(path.clone(), Source::Buffer, BUFFER_FRECENCY);
+        }
+        let now = now();
+        for entry in HistoryEntry::read_all(&self.data_dir.join(HISTORY_FILE_NAME)) {
+            let frecency = entry.frecency(now);
+            self.add_candidate(entry.path, Source::History, frecency);
+        }
+        self.refresh_matches();
+        self.cancellation_token = CancellationToken::new();
+        let sender = self.async_action_sender.clone().unwrap();
+        let token = self.cancellation_token.clone();
+        find_files(self.root.clone(), MAX_DIRECTORY_FILES, sender, token);
+    }
+    pub fn hide(&mut self) {
+        self.cancellation_token.cancel();
+        self.candidates.clear();
+        self.matches.clear();
+        self.filter.clear();
+        self.list_state.select(None);
+        self.opened = false;
+    }
+    pub fn showing(&self) -> bool {
+        self.opened
+    }
+    fn add_candidate(&mut self, path: PathBuf, source: Source, frecency: f64) {
+        if self.candidates.iter().any(|c| c.path == path) {
+            return;
+        }
+        let label = self.label_for(&path);
+        self.candidates.push(Candidate {
+            path,
+            label,
+            source,
+            frecency,
+        });
+    }
+    /// The path relative to the working directory, or the full path if it is outside of it.
+    fn label_for(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+    fn add_directory_files(&mut self, root: &Path, files: &[PathBuf]) -> ActionResult {
+        if root != self.root {
+            return ActionResult::consumed(false);
+        }
+        for file in files {
+            self.add_candidate(file.clone(), Source::Directory, 0.0);
+        }
+        let selected = self.list_state.selected();
+        self.refresh_matches();
+        if let Some(selected) = selected {
+            self.list_state
+                .select(Some(selected.min(self.matches.len().saturating_sub(1))));
+        }
+        ActionResult::consumed(true)
+    }
+    /// Ranks the candidates matching the filter, selecting the best one.
+    fn refresh_matches(&mut self) {
+        let mut ranked: Vec<(f64, Match)> = self
+            .candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, candidate)| {
+                let fuzzy = fuzzy_match(&self.filter, &candidate.label)?;
+                let score = fuzzy.score as f64 + FRECENCY_WEIGHT * candidate.frecency.ln_1p();
+                let matched = Match {
+                    candidate: i,
+                    indices: fuzzy.indices,
+                };
+                Some((score, matched))
+            })
+            .collect();
+        ranked.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        ranked.truncate(MAX_SHOWN);
+        self.matches = ranked.into_iter().map(|(_, matched)| matched).collect();
+        let selected = (!self.matches.is_empty()).then_some(0);
+        self.list_state.select(selected);
+    }
+    fn move_down(&mut self) -> ActionResult {
+        match self.list_state.selected() {
+            Some(selected) if selected + 1 < self.matches.len() => {
+                self.list_state.select(Some(selected + 1));
+                ActionResult::consumed(true)
+            }
+            _ => ActionResult::consumed(false),
+        }
+    }
+    fn move_up(&mut self) -> ActionResult {
+        match self.list_state.selected() {
+            Some(selected) if selected > 0 => {
+                self.list_state.select(Some(selected - 1));
+                ActionResult::consumed(true)
+            }
+            _ => ActionResult::consumed(false),
+        }
+    }
+    fn select(&mut self) -> ActionResult {
+        let Some(selected) = self.list_state.selected() else {
+            return ActionResult::consumed(false);
+        };
+        let candidate = &self.candidates[self.matches[selected].candidate];
+        let action = AsyncAction::SelectPath(candidate.path.clone(), SelectorType::PickFile);
+        let _ = self.async_action_sender.as_ref().unwrap().send(action);
+        self.hide();
+        ActionResult::consumed(true)
+    }
+    fn handle_character(&mut self, character: char) -> ActionResult {
+        self.filter.push(character);
+        self.refresh_matches();
+        ActionResult::consumed(true)
+    }
+    fn handle_backspace(&mut self) -> ActionResult {
+        if self.filter.pop().is_none() {
+            return ActionResult::consumed(false);
+        }
+        self.refresh_matches();
+        ActionResult::consumed(true)
+    }
+    fn map_to_list_item<'a>(
+        theme: &Theme,
+        candidate: &'a Candidate,
+        matched: &Match,
+        selected: bool,
+    ) -> ListItem<'a> {
+        let style = if selected { theme.active } else { theme.muted };
+        let highlight = theme.accent.bold();
+        let mut spans: Vec<Span> = candidate
+            .label
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let style = if matched.indices.contains(&i) {
+                    highlight
+                } else {
+                    style
+                };
+                Span::styled(c.to_string(), style)
+            })
+            .collect();
+        let source = match candidate.source {
+            Source::Buffer => "   open",
+            Source::History => "   recent",
+            Source::Directory => "",
+        };
+        spans.push(Span::styled(source, theme.muted.italic()));
+        ListItem::new(Line::from(spans))
+    }
+}
+
+impl Component for QuickSwitcherComponent {
+    fn register_config(&mut self, config: &Config, parent_comp: &AppComponent) {
+        let _ = parent_comp;
+        self.data_dir = config.config.data_dir.clone();
+        self.keybinds.register_keybinds(config);
+        self.theme = config.theme.clone();
+        self.effect_runner.register_config(config, parent_comp);
+    }
+    fn register_async_action_sender(&mut self, sender: AsyncActionSender) {
+        self.effect_runner
+            .register_async_action_sender(sender.clone());
+        self.async_action_sender = Some(sender)
+    }
+    fn override_keybind_id(&self, key_event: KeyEvent) -> Option<&AppComponent> {
+        let _ = key_event;
+        if self.opened {
+            Some(&AppComponent::FileDialog)
+        } else {
+            None
+        }
+    }
+    fn handle_action(&mut self, action: &Action) -> ActionResult {
+        if !self.showing() {
+            return ActionResult::not_consumed(false);
+        }
+        match action {
+            Action::Up => return self.move_up(),
+            Action::Down => return self.move_down(),
+            Action::Confirm => return self.select(),
+            Action::Cancel => {
+                self.hide();
+                return ActionResult::consumed(true);
+            }
+            Action::Backspace => return self.handle_backspace(),
+            Action::Character(character) => return self.handle_character(*character),
+            _ => {}
+        }
+        ActionResult::consumed(false)
+    }
+    fn handle_async_action(&mut self, action: &AsyncAction) -> ActionResult {
+        if !self.showing() {
+            return ActionResult::not_consumed(false);
+        }
+        if let AsyncAction::FoundFiles(root, files) = action {
+            return self.add_directory_files(root, files);
+        }
+        ActionResult::not_consumed(false)
+    }
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        if !self.showing() {
+            return;
+        }
+        let area = center_horizontally(area, Constraint::Percentage(60));
+        let area = center_vertically(area, Constraint::Percentage(60));
+        frame.render_widget(Clear, area);
+        let title = format!(" Switch to: {} ", self.filter);
+        let title = Line::raw(title).left_aligned();
+        let up_down_title = format!(" {} {} ", self.keybinds.up, self.keybinds.down);
+        let up_down_title = Line::from(up_down_title).centered();
+        let label = key_label_format(&self.keybinds.confirm, "Open");
+        let enter_title = Line::from(label).right_aligned();
+        let label = key_label_format(&self.keybinds.cancel, "Cancel");
+        let cancel_title = Line::from(label).left_aligned();
+        let block = default_block(&self.theme)
+            .title_top(title)
+            .title_bottom(up_down_title)
+            .title_bottom(enter_title)
+            .title_bottom(cancel_title);
+        if self.matches.is_empty() {
+            let block_area = block.inner(area);
+            let center = center(block_area);
+            let text = Text::raw("No files match...").centered();
+            frame.render_widget(block, area);
+            frame.render_widget(text, center);
+        } else {
+            let selected = self.list_state.selected();
+            let mapped = self.matches.iter().enumerate().map(|(i, matched)| {
+                let candidate = &self.candidates[matched.candidate];
+                Self::map_to_list_item(&self.theme, candidate, matched, selected == Some(i))
+            });
+            let list = List::new(mapped)
+                .direction(ListDirection::TopToBottom)
+                .highlight_symbol(HIGHLIGHT_SYMBOL)
+                .highlight_spacing(HighlightSpacing::Always)
+                .scroll_padding(5)
+                .block(block);
+            frame.render_stateful_widget(list, area, &mut self.list_state);
+        }
+        self.effect_runner.render(frame, area);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::component::file_selector::PathChild;
+use crate::config::app_config::SortKey;
+use std::cmp::Ordering;
+use std::path::Path;
+
+/// Sorts the children of a folder by `key`, the way up first, then folders, then files.
+///
+/// Entries that are equal by `key` are in the natural order of their names.
+pub(super) fn sort_children(children: &mut [PathChild], key: SortKey) {
+    children.sort_by(|a, b| {
+        group(a)
+            .cmp(&group(b))
+            .then_with(|| compare_by(a, b, key))
+            .then_with(|| natural_cmp(a.name(), b.name()))
+    });
+}
+
+fn group(child: &PathChild) -> u8 {
+    match child {
+        PathChild::MoveUp => 0,
+        PathChild::Folder { .. } => 1,
+        PathChild::File { .. } => 2,
+    }
+}
+
+fn compare_by(a: &PathChild, b: &PathChild, key: SortKey) -> Ordering {
+    match key {
+        SortKey::Name => Ordering::Equal,
+        SortKey::Size => {
+            let size = |child: &PathChild| match child {
+                PathChild::File { metadata, .. } => metadata.as_ref().map(|m| m.size),
+                _ => None,
+            };
+            size(b).cmp(&size(a))
+        }
+        SortKey::Modified => {
+            let modified = |child: &PathChild| child.metadata()?.modified;
+            modified(b).cmp(&modified(a))
+        }
+        SortKey::Extension => {
+            let extension = |child: &PathChild| {
+                let extension = Path::new(child.name()).extension().unwrap_or_default();
+                extension.to_string_lossy().to_lowercase()
+            };
+            natural_cmp(&extension(a), &extension(b))
+        }
+    }
+}
+
+/// Compares the strings ignoring case, and with runs of digits compared by their value, so that
+/// `file2` comes before `file10`.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    loop {
+        let ordering = match (a_chars.peek(), b_chars.peek()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a_char), Some(b_char)) if a_char.is_ascii_digit() && b_char.is_ascii_digit() => {
+                let a_number = take_digits(&mut a_chars);
+                let b_number = take_digits(&mut b_chars);
+                let a_number = a_number.trim_start_matches('0');
+                let b_number = b_number.trim_start_matches('0');
+                a_number
+                    .len()
+                    .cmp(&b_number.len())
+                    .then_with(|| a_number.cmp(b_number))
+            }
+            (Some(a_char), Some(b_char)) => {
+                let ordering = a_char.to_lowercase().cmp(b_char.to_lowercase());
+                a_chars.next();
+                b_chars.next();
+                ordering
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        digits.push(c);
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_are_compared_by_value() {
+        let mut names = vec!["file10", "file2", "File1", "file02b", "file"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, vec!["file", "File1", "file2", "file02b", "file10"]);
+    }
+
+    #[test]
+    fn folders_are_listed_before_files() {
+        let mut children = vec![
+            PathChild::file("b.txt".to_string(), "b.txt".into()),
+            PathChild::folder("z".to_string()),
+            PathChild::file("a.rs".to_string(), "a.rs".into()),
+            PathChild::MoveUp,
+            PathChild::folder("a".to_string()),
+        ];
+        sort_children(&mut children, SortKey::Extension);
+        let names: Vec<&str> = children.iter().map(PathChild::name).collect();
+        assert_eq!(names, vec!["..", "a", "z", "a.rs", "b.txt"]);
+    }
+}
@@ -2,13 +2,13 @@ use crate::action::{Action, ActionResult, AsyncActionSender};
 use crate::component::component_utils::default_block;
 use crate::component::effect_runner::EffectRunner;
 use crate::component::{AppComponent, Component};
-use crate::config::effects::floating_component_bottom_right_enter;
+use crate::config::effects::{floating_component_bottom_right_enter, AnimationEvent};
 use crate::config::keybindings::key_event_to_string;
+use crate::config::theme::Theme;
 use crate::config::Config;
 use color_eyre::eyre::{OptionExt, Result};
 use crossterm::event::KeyEvent;
 use ratatui::layout::{Constraint, Flex, Layout, Rect};
-use ratatui::style::Stylize;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Paragraph};
 use ratatui::Frame;
@@ -35,13 +35,13 @@ impl From<(&KeyEvent, &Action)> for KeyBind {
     }
 }
 
-impl<'a> From<&KeyBind> for Line<'a> {
-    fn from(value: &KeyBind) -> Self {
+impl KeyBind {
+    fn line<'a>(&self, theme: &Theme) -> Line<'a> {
         Line::from(vec![
             Span::raw("["),
-            Span::from(value.key.to_string()).gray(),
+            Span::styled(self.key.to_string(), theme.keys),
             Span::raw("] "),
-            Span::from(value.label.to_string()).white(),
+            Span::styled(self.label.to_string(), theme.active),
         ])
     }
 }
@@ -75,6 +75,7 @@ pub struct HelpComponent {
     effect_runner: EffectRunner,
     scroll_offset: u16,
     max_offset: u16,
+    theme: Theme,
 }
 
 impl Default for HelpComponent {
@@ -88,6 +89,7 @@ impl Default for HelpComponent {
             effect_runner: EffectRunner::default(),
             scroll_offset: 0,
             max_offset: 0,
+            theme: Theme::default(),
         }
     }
 }
@@ -110,6 +112,7 @@ impl HelpComponent {
             effect_runner: EffectRunner::default(),
             scroll_offset: 0,
             max_offset: 0,
+            theme: Theme::default(),
         };
         n.register_keybinds(keybinds);
         n
@@ -156,13 +159,13 @@ impl HelpComponent {
     }
     fn block<'a>(&self) -> Block<'a> {
         let line = Line::from(self.title.to_string()).left_aligned();
-        default_block().title_top(line)
+        default_block(&self.theme).title_top(line)
     }
     pub fn toggle_visible(&mut self) -> ActionResult {
         self.visible = !self.visible;
         if self.visible {
             self.effect_runner
-                .add_effect(floating_component_bottom_right_enter())
+                .add_effect(AnimationEvent::Popup, floating_component_bottom_right_enter());
         }
         ActionResult::consumed(true)
     }
@@ -196,6 +199,8 @@ impl Component for HelpComponent {
             .get_key_event_of_action(parent_comp, Action::ToggleHelp)
             .map(key_event_to_string);
         self.help_key = help_key.unwrap_or_default();
+        self.theme = config.theme.clone();
+        self.effect_runner.register_config(config, parent_comp);
         let _ = self.register_from_app_component(parent_comp, config);
     }
     fn register_async_action_sender(&mut self, sender: AsyncActionSender) {
@@ -228,7 +233,7 @@ impl Component for HelpComponent {
             .flex(Flex::End)
             .areas(area);
         let mut block = self.block();
-        let lines = self.keybinds.iter().map(Line::from).collect::<Vec<_>>();
+        let lines = self.keybinds.iter().map(|k| k.line(&self.theme)).collect::<Vec<_>>();
         let mut paragraph = Paragraph::new(lines).scroll((self.scroll_offset, 0));
         if self.scroll_offset > 0 {
             let arrow_up = Line::raw("  ").centered();
@@ -4,17 +4,20 @@ use crate::action::{
 use crate::component::component_utils::{center_horizontally, default_block, key_label_format};
 use crate::component::file_selector::component::FileSelectorComponent;
 use crate::component::file_selector::file_history::FileHistoryComponent;
-use crate::component::{AppComponent, Component};
+use crate::component::file_selector::quick_switcher::QuickSwitcherComponent;
+use crate::component::session_list::SessionListComponent;
+use crate::component::{AppComponent, Component, EditorOptions};
 use crate::config::keybindings::Keybindings;
-use crate::config::{get_config_file_dir, Config};
+use crate::config::theme::Theme;
+use crate::config::Config;
 use crossterm::event::KeyEvent;
 use ratatui::layout::{Alignment, Constraint, Direction, Flex, Layout, Rect};
-use ratatui::style::{Style, Stylize};
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{HighlightSpacing, List, ListDirection, ListItem, ListState};
 use ratatui::Frame;
 use std::collections::HashMap;
 use std::env::current_dir;
+use std::path::PathBuf;
 use strum::{EnumCount, EnumIter, EnumProperty, IntoEnumIterator};
 use tui_big_text::{BigText, PixelSize};
 
@@ -36,13 +39,18 @@ impl HomeKeybinds {
         let quit = keybinds.get_key_string_or_default(Action::Quit, app_component);
         let new_file = keybinds.get_key_string_or_default(Action::NewFile, app_component);
         let open_file = keybinds.get_key_string_or_default(Action::OpenFile, app_component);
+        let quick_switch = keybinds.get_key_string_or_default(Action::QuickSwitch, app_component);
         let file_history = keybinds.get_key_string_or_default(Action::FileHistory, app_component);
-        let config = keybinds.get_key_string_or_default(Action::Config, app_component);
+        let sessions = keybinds.get_key_string_or_default(Action::Sessions, app_component);
+        let settings = keybinds.get_key_string_or_default(Action::Config, app_component);
         self.options_keys.insert(HomeOptions::NewFile, new_file);
         self.options_keys.insert(HomeOptions::OpenFile, open_file);
+        self.options_keys
+            .insert(HomeOptions::QuickSwitch, quick_switch);
         self.options_keys
             .insert(HomeOptions::FileHistory, file_history);
-        self.options_keys.insert(HomeOptions::Config, config);
+        self.options_keys.insert(HomeOptions::Sessions, sessions);
+        self.options_keys.insert(HomeOptions::Settings, settings);
         self.options_keys.insert(HomeOptions::Quit, quit);
     }
 }
@@ -53,10 +61,14 @@ enum HomeOptions {
     NewFile,
     #[strum(props(title = "Open File"))]
     OpenFile,
+    #[strum(props(title = "Quick Switch"))]
+    QuickSwitch,
     #[strum(props(title = "File History"))]
     FileHistory,
-    #[strum(props(title = "Config"))]
-    Config,
+    #[strum(props(title = "Sessions"))]
+    Sessions,
+    #[strum(props(title = "Settings"))]
+    Settings,
     #[strum(props(title = "Quit"))]
     Quit,
 }
@@ -68,21 +80,31 @@ pub struct HomeComponent<'a> {
     async_action_sender: Option<AsyncActionSender>,
     file_selector_component: FileSelectorComponent<'a>,
     file_history_component: FileHistoryComponent,
+    quick_switcher: QuickSwitcherComponent,
+    session_list_component: SessionListComponent,
     keybinds: HomeKeybinds,
+    starting_directory: Option<PathBuf>,
+    theme: Theme,
 }
 
 impl HomeComponent<'_> {
     pub fn new() -> Self {
         HomeComponent::default()
     }
+    /// Starts with the file selector opened at `dir`
+    pub fn new_in_directory<S: AsRef<str>>(dir: S) -> Self {
+        Self {
+            starting_directory: Some(PathBuf::from(dir.as_ref())),
+            ..Default::default()
+        }
+    }
     fn navigate_new_file(&self) {
         let comp = AppComponent::Editor;
         let action = AsyncAction::Navigate(Some(comp));
         self.send_async_action(action);
     }
-    fn navigate_to_config(&self) {
-        let config_dir = get_config_file_dir().display().to_string();
-        let comp = AppComponent::OpenedEditor(config_dir);
+    fn navigate_to_settings(&self) {
+        let comp = AppComponent::Settings;
         self.send_async_action(AsyncAction::Navigate(Some(comp)));
     }
     fn exit_program(&self) {
@@ -98,22 +120,37 @@ impl HomeComponent<'_> {
         self.file_selector_component
             .show(current_dir().unwrap_or_default(), SelectorType::PickFile)
     }
+    fn open_quick_switcher(&mut self) {
+        self.quick_switcher.show(&[]);
+    }
     fn open_file_history(&mut self) {
         let _ = self.file_history_component.show();
     }
+    fn open_session_list(&mut self) {
+        self.session_list_component.show();
+    }
 }
 
 impl Component for HomeComponent<'_> {
     fn register_config(&mut self, config: &Config, parent_comp: &AppComponent) {
         let _ = parent_comp;
+        self.file_selector_component
+            .register_config(config, &AppComponent::HomeScreen);
         self.file_history_component
             .register_config(config, &AppComponent::HomeScreen);
+        self.session_list_component
+            .register_config(config, &AppComponent::HomeScreen);
+        self.quick_switcher
+            .register_config(config, &AppComponent::HomeScreen);
         self.keybinds
             .setup(&AppComponent::HomeScreen, &config.keybindings);
+        self.theme = config.theme.clone();
     }
     fn register_action_sender(&mut self, sender: ActionSender) {
         self.file_selector_component
             .register_action_sender(sender.clone());
+        self.file_history_component
+            .register_action_sender(sender.clone());
         self.action_sender = Some(sender);
     }
     fn register_async_action_sender(&mut self, sender: AsyncActionSender) {
@@ -121,14 +158,25 @@ impl Component for HomeComponent<'_> {
             .register_async_action_sender(sender.clone());
         self.file_history_component
             .register_async_action_sender(sender.clone());
+        self.session_list_component
+            .register_async_action_sender(sender.clone());
+        self.quick_switcher
+            .register_async_action_sender(sender.clone());
         self.async_action_sender = Some(sender)
     }
     fn override_keybind_id(&self, key_event: KeyEvent) -> Option<&AppComponent> {
         self.file_selector_component
             .override_keybind_id(key_event)
             .or_else(|| self.file_history_component.override_keybind_id(key_event))
+            .or_else(|| self.session_list_component.override_keybind_id(key_event))
+            .or_else(|| self.quick_switcher.override_keybind_id(key_event))
             .or(Some(&AppComponent::HomeScreen))
     }
+    fn init(&mut self) {
+        if let Some(dir) = self.starting_directory.take() {
+            self.file_selector_component.show(dir, SelectorType::PickFile);
+        }
+    }
     fn handle_action(&mut self, action: &Action) -> ActionResult {
         let r = self.file_selector_component.handle_action(action);
         if r.is_consumed() {
@@ -138,6 +186,14 @@ impl Component for HomeComponent<'_> {
         if r.is_consumed() {
             return r;
         }
+        let r = self.session_list_component.handle_action(action);
+        if r.is_consumed() {
+            return r;
+        }
+        let r = self.quick_switcher.handle_action(action);
+        if r.is_consumed() {
+            return r;
+        }
         match action {
             Action::Up => {
                 if let Some(index) = self.list_state.selected() {
@@ -167,9 +223,11 @@ impl Component for HomeComponent<'_> {
                     match option {
                         HomeOptions::NewFile => self.navigate_new_file(),
                         HomeOptions::OpenFile => self.open_file_picker(),
+                        HomeOptions::QuickSwitch => self.open_quick_switcher(),
                         HomeOptions::FileHistory => self.open_file_history(),
+                        HomeOptions::Sessions => self.open_session_list(),
                         HomeOptions::Quit => self.exit_program(),
-                        HomeOptions::Config => self.navigate_to_config(),
+                        HomeOptions::Settings => self.navigate_to_settings(),
                     }
                     return ActionResult::consumed(true);
                 }
@@ -188,12 +246,20 @@ impl Component for HomeComponent<'_> {
                 self.open_file_picker();
                 return ActionResult::consumed(true);
             }
+            Action::QuickSwitch => {
+                self.open_quick_switcher();
+                return ActionResult::consumed(true);
+            }
             Action::FileHistory => {
                 self.open_file_history();
                 return ActionResult::consumed(true);
             }
+            Action::Sessions => {
+                self.open_session_list();
+                return ActionResult::consumed(true);
+            }
             Action::Config => {
-                self.navigate_to_config();
+                self.navigate_to_settings();
                 return ActionResult::consumed(true);
             }
             _ => {}
@@ -209,6 +275,10 @@ impl Component for HomeComponent<'_> {
         if r.is_consumed() {
             return r;
         }
+        let r = self.quick_switcher.handle_async_action(action);
+        if r.is_consumed() {
+            return r;
+        }
         if let AsyncAction::SelectPath(path, _) = action {
             let path = path.display().to_string();
             let editor = AppComponent::OpenedEditor(path);
@@ -216,12 +286,22 @@ impl Component for HomeComponent<'_> {
             let _ = self.async_action_sender.as_ref().unwrap().send(action);
             return ActionResult::consumed(false);
         }
+        if let AsyncAction::SelectPaths(paths) = action {
+            let options = EditorOptions {
+                files: paths.iter().map(|p| p.display().to_string()).collect(),
+                ..Default::default()
+            };
+            let editor = AppComponent::OpenedFiles(options);
+            let action = AsyncAction::Navigate(Some(editor));
+            let _ = self.async_action_sender.as_ref().unwrap().send(action);
+            return ActionResult::consumed(false);
+        }
         Default::default()
     }
     fn render(&mut self, frame: &mut Frame, area: Rect) {
         let block_title = format!(" {} {} ", self.keybinds.up, self.keybinds.down);
         let block_title = Line::raw(block_title).centered();
-        let mut block = default_block().title_bottom(block_title);
+        let mut block = default_block(&self.theme).title_bottom(block_title);
         if self.list_state.selected().is_some() {
             let confirm_title = key_label_format(&self.keybinds.confirm, "Select");
             let confirm_title = Line::raw(confirm_title).right_aligned();
@@ -243,20 +323,21 @@ impl Component for HomeComponent<'_> {
         let title = BigText::builder()
             .centered()
             .pixel_size(PixelSize::Quadrant)
-            .style(Style::new())
+            .style(self.theme.text)
             .lines(vec!["Texti".into()])
             .build();
         frame.render_widget(title, title_area);
         let options_items = HomeOptions::iter().enumerate().map(|(i, v)| {
             let title = v.get_str("title").unwrap().to_string();
             let main_style = if self.list_state.selected().is_some_and(|v| v == i) {
-                Style::new().white()
+                self.theme.active
             } else {
-                Style::new().dark_gray()
+                self.theme.muted
             };
             let msg = if let Some(key) = self.keybinds.options_keys.get(&v) {
                 let title_span = Span::from(title).style(main_style);
-                let span = Line::from(vec![(key.to_string() + "  ").dark_gray(), title_span]);
+                let key_span = Span::styled(key.to_string() + "  ", self.theme.muted);
+                let span = Line::from(vec![key_span, title_span]);
                 Text::from(vec![span, Line::raw("")])
             } else {
                 Text::from(format!("\n{title}\n")).style(main_style)
@@ -270,5 +351,7 @@ impl Component for HomeComponent<'_> {
         frame.render_stateful_widget(list, options_area, &mut self.list_state);
         self.file_selector_component.render(frame, area);
         self.file_history_component.render(frame, area);
+        self.session_list_component.render(frame, area);
+        self.quick_switcher.render(frame, area);
     }
 }
@@ -1,32 +1,49 @@
 mod component_utils;
 mod confirm_dialog;
-mod editor;
+pub(crate) mod editor;
 mod effect_runner;
 mod file_selector;
 mod help;
 mod home;
 pub(crate) mod navigator;
 mod notification;
-mod preview_component;
+pub(crate) mod preview_component;
+mod session_list;
+mod settings;
 
 use crate::action::{Action, ActionResult, ActionSender, AsyncAction, AsyncActionSender};
+use crate::component::editor::options::EditorOptions;
 use crate::config::Config;
+use crate::session::Session;
 use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::layout::Rect;
 use ratatui::Frame;
 use serde::{Deserialize, Serialize};
 use std::ops::{Deref, DerefMut};
+use strum::VariantNames;
 
 const TICKS_UNTIL_REMOVE_POPUP: usize = 2;
 
-#[derive(Clone, Default, Debug, Deserialize, Eq, PartialEq, Hash, Serialize)]
+#[derive(Clone, Default, Debug, Deserialize, Eq, PartialEq, Hash, Serialize, VariantNames)]
 pub enum AppComponent {
     #[default]
     HomeScreen,
     OpenedEditor(String),
+    /// Opens the editor as described by its `EditorOptions`, usually from the command line
+    OpenedFiles(EditorOptions),
+    /// Opens the home screen with the file selector at this directory
+    OpenedDirectory(String),
+    /// Restores the buffers and screen of a saved session
+    RestoredSession(Box<Session>),
     FileDialog,
+    /// The list of recently opened files
+    FileHistory,
     Editor,
+    /// The editor opened read-only, browsed like a pager
+    Pager,
     Dialog,
+    /// The keybindings, listed to be changed
+    Settings,
 }
 
 #[derive(Debug)]
@@ -120,6 +137,15 @@ pub trait Component {
         let _ = action;
         ActionResult::default()
     }
+    /// Handles the key event before it is turned into an action by the keybindings, for
+    /// components reading keys themselves.
+    ///
+    /// Return `ActionResult::Consumed` to keep the key event from being turned into an action,
+    /// or `ActionResult::NotConsumed` to continue.
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> ActionResult {
+        let _ = key_event;
+        ActionResult::default()
+    }
     /// Handles the mouse action and update this state
     ///
     /// Return `ActionResult::Consumed` to stop passing the mouse event to other components,
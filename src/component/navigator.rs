@@ -2,12 +2,15 @@ use crate::action::{Action, ActionResult, ActionSender, AsyncAction, AsyncAction
 use crate::component::editor::component::EditorComponent;
 use crate::component::effect_runner::EffectRunner;
 use crate::component::home::HomeComponent;
+use crate::component::notification::NotificationComponent;
+use crate::component::settings::SettingsComponent;
 use crate::component::{AppComponent, Component};
-use crate::config::effects::{enter_next_screen_effect, init_effect, leave_effect};
+use crate::config::effects::{
+    enter_next_screen_effect, init_effect, leave_effect, AnimationEvent,
+};
 use crate::config::Config;
 use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Stylize};
 use ratatui::widgets::Block;
 use ratatui::Frame;
 
@@ -20,12 +23,11 @@ pub struct NavigatorComponent {
     effect_runner: EffectRunner,
     transitioning: Option<AppComponent>,
     config: Config,
+    /// Notifications about the whole application, kept across screens
+    notification: NotificationComponent,
 }
 
 impl NavigatorComponent {
-    pub fn new() -> Self {
-        Self::new_with_starting_component(AppComponent::default())
-    }
     pub fn new_with_starting_component(app_component: AppComponent) -> Self {
         let (app_comp, comp) = Self::map_component(app_component);
         Self {
@@ -37,8 +39,13 @@ impl NavigatorComponent {
             config: Config::default(),
             effect_runner: EffectRunner::default(),
             transitioning: None,
+            notification: NotificationComponent::default(),
         }
     }
+    /// Shows an error over the current screen.
+    pub fn notify_error<T: ToString>(&mut self, message: T) {
+        self.notification.notify_error(message);
+    }
     pub fn navigate(&mut self, app_component: AppComponent) {
         if self.current_component != app_component {
             self.start_leave_screen_transition(app_component);
@@ -56,14 +63,21 @@ impl NavigatorComponent {
         }
     }
     fn start_leave_screen_transition(&mut self, app_component: AppComponent) {
-        self.effect_runner.add_effect(leave_effect());
         self.transitioning = Some(app_component);
+        // Without an animation to wait for, the next screen is entered right away
+        if !self
+            .effect_runner
+            .add_effect(AnimationEvent::ScreenLeave, leave_effect())
+        {
+            self.start_enter_screen_transition();
+        }
     }
     fn start_enter_screen_transition(&mut self) {
         let Some(app_component) = self.transitioning.take() else {
             return;
         };
-        self.effect_runner.add_effect(enter_next_screen_effect());
+        self.effect_runner
+            .add_effect(AnimationEvent::ScreenEnter, enter_next_screen_effect());
         self.component.exit();
         let (app_comp, comp) = Self::map_component(app_component);
         self.current_component = app_comp;
@@ -82,7 +96,29 @@ impl NavigatorComponent {
             AppComponent::OpenedEditor(path) => {
                 (AppComponent::Editor, Box::new(EditorComponent::new(path)))
             }
+            AppComponent::OpenedFiles(options) => {
+                let app_component = if options.pager {
+                    AppComponent::Pager
+                } else {
+                    AppComponent::Editor
+                };
+                let editor = EditorComponent::new_with_options(options);
+                (app_component, Box::new(editor))
+            }
+            AppComponent::RestoredSession(session) if !session.buffers.is_empty() => {
+                let app_component = match session.component {
+                    AppComponent::Pager => AppComponent::Pager,
+                    _ => AppComponent::Editor,
+                };
+                let editor = EditorComponent::from_session(*session);
+                (app_component, Box::new(editor))
+            }
+            AppComponent::OpenedDirectory(dir) => {
+                let home = HomeComponent::new_in_directory(dir);
+                (AppComponent::HomeScreen, Box::new(home))
+            }
             AppComponent::Editor => (app_component, Box::new(EditorComponent::default())),
+            AppComponent::Settings => (app_component, Box::new(SettingsComponent::default())),
             _ => (AppComponent::HomeScreen, Box::new(HomeComponent::new())),
         }
     }
@@ -91,6 +127,8 @@ impl NavigatorComponent {
 impl Component for NavigatorComponent {
     fn register_config(&mut self, config: &Config, app_component: &AppComponent) {
         self.config = config.clone();
+        self.effect_runner.register_config(config, app_component);
+        self.notification.register_config(config, app_component);
         self.component.register_config(config, app_component);
     }
     fn register_action_sender(&mut self, sender: ActionSender) {
@@ -101,12 +139,18 @@ impl Component for NavigatorComponent {
         self.async_action_sender = Some(sender.clone());
         self.effect_runner
             .register_async_action_sender(sender.clone());
+        self.notification
+            .register_async_action_sender(sender.clone());
         self.component.register_async_action_sender(sender)
     }
     fn override_keybind_id(&self, key_event: KeyEvent) -> Option<&AppComponent> {
         self.component.override_keybind_id(key_event)
     }
     fn handle_action(&mut self, action: &Action) -> ActionResult {
+        if *action == Action::Tick && self.notification.handle_tick_action().should_rerender() {
+            let _ = self.component.handle_action(action);
+            return ActionResult::consumed(true);
+        }
         self.component.handle_action(action)
     }
     fn handle_async_action(&mut self, action: &AsyncAction) -> ActionResult {
@@ -121,13 +165,25 @@ impl Component for NavigatorComponent {
             }
             return ActionResult::consumed(true);
         }
-        self.component.handle_async_action(action)
+        let result = self.component.handle_async_action(action);
+        // Screens without notifications of their own, like the home screen, leave errors to it
+        if let AsyncAction::Error(msg) = action
+            && !result.is_consumed()
+        {
+            self.notify_error(msg);
+            return ActionResult::consumed(true);
+        }
+        result
+    }
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> ActionResult {
+        self.component.handle_key_event(key_event)
     }
     fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> ActionResult {
         self.component.handle_mouse_event(mouse_event)
     }
     fn init(&mut self) {
-        self.effect_runner.add_effect(init_effect());
+        self.effect_runner
+            .add_effect(AnimationEvent::Startup, init_effect());
         self.component.init();
     }
     fn exit(&mut self) {
@@ -135,12 +191,13 @@ impl Component for NavigatorComponent {
         self.component.exit();
     }
     fn render(&mut self, frame: &mut Frame, area: Rect) {
-        let block = Block::default().bg(Color::from_u32(0x1d2021));
+        let block = Block::default().style(self.config.theme.background);
         frame.render_widget(block, area);
         self.component.render(frame, area);
         let just_finished = self.effect_runner.process(frame.buffer_mut(), area);
         if just_finished {
             self.start_enter_screen_transition()
         }
+        self.notification.render(frame, area);
     }
 }
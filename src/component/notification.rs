@@ -1,10 +1,12 @@
 use crate::action::{Action, ActionResult, AsyncActionSender};
 use crate::component::component_utils::{center_horizontally, default_block};
 use crate::component::effect_runner::EffectRunner;
-use crate::component::{Component, TickCount};
-use crate::config::effects::show_notification_effect;
+use crate::component::{AppComponent, Component, TickCount};
+use crate::config::effects::{show_notification_effect, AnimationEvent};
+use crate::config::theme::Theme;
+use crate::config::Config;
 use ratatui::layout::{Constraint, Flex, Layout, Rect};
-use ratatui::prelude::{Color, Text};
+use ratatui::prelude::Text;
 use ratatui::widgets::{Clear, Paragraph};
 use ratatui::Frame;
 
@@ -18,6 +20,7 @@ pub struct Notification {
 pub struct NotificationComponent {
     notification: Option<Notification>,
     effect_runner: EffectRunner,
+    theme: Theme,
 }
 
 #[allow(dead_code)]
@@ -30,6 +33,7 @@ impl NotificationComponent {
         Self {
             notification: Some(notification),
             effect_runner: EffectRunner::default(),
+            theme: Theme::default(),
         }
     }
     pub fn new_with_count(text: String, error: bool, count: usize) -> Self {
@@ -40,6 +44,7 @@ impl NotificationComponent {
         Self {
             notification: Some(notification),
             effect_runner: EffectRunner::default(),
+            theme: Theme::default(),
         }
     }
     pub fn notify_text<T: ToString>(&mut self, text: T) {
@@ -48,7 +53,8 @@ impl NotificationComponent {
             error: false,
         };
         self.notification = Some(notification);
-        self.effect_runner.add_effect(show_notification_effect())
+        self.effect_runner
+            .add_effect(AnimationEvent::Notification, show_notification_effect());
     }
     pub fn notify_error<T: ToString>(&mut self, text: T) {
         let notification = Notification {
@@ -56,7 +62,8 @@ impl NotificationComponent {
             error: true,
         };
         self.notification = Some(notification);
-        self.effect_runner.add_effect(show_notification_effect())
+        self.effect_runner
+            .add_effect(AnimationEvent::Notification, show_notification_effect());
     }
     pub fn notify(&mut self, notification: Notification) {
         self.notification = Some(notification);
@@ -74,6 +81,10 @@ impl NotificationComponent {
 }
 
 impl Component for NotificationComponent {
+    fn register_config(&mut self, config: &Config, parent_comp: &AppComponent) {
+        self.theme = config.theme.clone();
+        self.effect_runner.register_config(config, parent_comp);
+    }
     fn register_async_action_sender(&mut self, sender: AsyncActionSender) {
         self.effect_runner.register_async_action_sender(sender)
     }
@@ -101,12 +112,12 @@ impl Component for NotificationComponent {
             frame.render_widget(Clear, pop_up_area);
             let text = Text::raw(&counter.title.value);
             let mut paragraph = Paragraph::new(text).centered();
-            let block = default_block();
+            let block = default_block(&self.theme);
             if counter.error {
-                let block = block.border_style(Color::Red);
-                paragraph = paragraph.style(Color::Red).block(block);
+                let block = block.border_style(self.theme.error);
+                paragraph = paragraph.style(self.theme.error).block(block);
             } else {
-                paragraph = paragraph.block(block);
+                paragraph = paragraph.style(self.theme.notification).block(block);
             };
             frame.render_widget(paragraph, pop_up_area);
             self.effect_runner.process(frame.buffer_mut(), pop_up_area);
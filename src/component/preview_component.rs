@@ -1,34 +1,75 @@
 use crate::action::{Action, ActionResult, AsyncAction, AsyncActionSender};
+use crate::archive::{read_entry_with, split_archive_path, ArchiveListing};
 use crate::component::component_utils::default_block;
-use crate::component::Component;
-use crate::util::read_dir_limited;
+use crate::component::file_selector::ChildMetadata;
+use crate::component::{AppComponent, Component};
+use crate::config::app_config::Column;
+use crate::config::theme::Theme;
+use crate::config::Config;
 use ratatui::layout::Rect;
-use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::widgets::Paragraph;
 use ratatui::Frame;
-use std::path::PathBuf;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use tokio::task::JoinHandle;
 
+/// Bytes of a line kept for the preview, the rest of longer lines is left out
+const MAX_LINE_BYTES: usize = 1024;
+/// Bytes looked at to tell binary files apart from text
+const BINARY_SNIFF_BYTES: usize = 8192;
+/// Bytes shown in each line of a hex dump
+const HEX_DUMP_WIDTH: usize = 16;
+
+/// Lines of a file or folder shown in the preview, starting at the line it is scrolled to.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Preview {
+    /// Size, modification time and permissions of the file or folder
+    header: Option<String>,
+    lines: Vec<String>,
+    /// Whether the last line of the file is in `lines`
+    reached_end: bool,
+}
+
+/// Previews the file or folder at `path_buf`: the text of text files, a hex dump of binary
+/// ones and the contents of folders, which may all be inside archives.
+///
+/// Only the lines that fit are read, from the one it is scrolled to, so that any part of big
+/// files can be previewed.
 #[derive(Debug)]
 pub struct PreviewComponent {
     path_buf: PathBuf,
-    contents: Option<String>,
+    /// Whether there is something at `path_buf` to preview
+    exists: bool,
+    preview: Option<Preview>,
+    /// Whether `preview` should be read again
+    stale: bool,
+    first_line: usize,
     task: JoinHandle<()>,
+    /// The last archive previewed, so scrolling through its entries does not list it again
+    archive_listing: Arc<Mutex<Option<ArchiveListing>>>,
     async_action_sender: Option<AsyncActionSender>,
     lines: usize,
     visible: bool,
+    theme: Theme,
 }
 
 impl Default for PreviewComponent {
     fn default() -> Self {
         Self {
             path_buf: Default::default(),
-            contents: None,
+            exists: false,
+            preview: None,
+            stale: true,
+            first_line: 0,
             task: tokio::spawn(async {}),
+            archive_listing: Default::default(),
             async_action_sender: None,
             lines: 0,
             visible: true,
+            theme: Theme::default(),
         }
     }
 }
@@ -36,44 +77,74 @@ impl Default for PreviewComponent {
 impl PreviewComponent {
     pub fn change_dir(&mut self, dir: Option<PathBuf>) {
         if let Some(dir) = dir {
+            self.exists = dir.exists() || split_archive_path(&dir).is_some();
             self.path_buf = dir;
         }
-        self.contents = None;
+        self.preview = None;
+        self.first_line = 0;
+        self.stale = true;
         self.task.abort();
     }
     fn read_lines(&mut self) {
-        if self.contents.is_some() {
+        if !self.stale || self.lines == 0 {
             return;
         }
+        self.stale = false;
         self.task.abort();
         let action_sender = self.async_action_sender.clone().unwrap();
         let path = self.path_buf.clone();
-        let lines = self.lines;
+        let (first_line, lines) = (self.first_line, self.lines);
+        let listing = self.archive_listing.clone();
         self.task = tokio::spawn(async move {
-            match read_dir_limited(&path, lines).await {
-                Ok(content) => {
-                    let action = AsyncAction::PreviewContents(Some(content));
-                    let _ = action_sender.send(action);
-                }
-                Err(err) => {
-                    let action = AsyncAction::Error(err.to_string());
-                    let _ = action_sender.send(action);
-                }
-            }
+            let read = tokio::task::spawn_blocking(move || {
+                read_preview(&path, first_line, lines, &listing)
+            });
+            let action = match read.await {
+                Ok(Ok(preview)) => AsyncAction::PreviewContents(Some(preview)),
+                Ok(Err(err)) => AsyncAction::Error(err.to_string()),
+                Err(err) => AsyncAction::Error(err.to_string()),
+            };
+            let _ = action_sender.send(action);
         });
     }
 
     pub fn visible(&self) -> bool {
-        self.visible && self.path_buf.is_file()
+        self.visible && self.exists
     }
 
     fn reload(&mut self) {
-        self.contents = None;
+        self.stale = true;
         self.read_lines();
     }
+    /// Scrolls a page up or down, keeping the current preview until the new lines are read.
+    fn scroll(&mut self, down: bool) -> ActionResult {
+        if !self.visible() {
+            return ActionResult::consumed(false);
+        }
+        let page = self.lines.max(1);
+        let first_line = if down {
+            let reached_end = self.preview.as_ref().is_none_or(|p| p.reached_end);
+            if reached_end {
+                return ActionResult::consumed(false);
+            }
+            self.first_line + page
+        } else {
+            self.first_line.saturating_sub(page)
+        };
+        if first_line == self.first_line {
+            return ActionResult::consumed(false);
+        }
+        self.first_line = first_line;
+        self.reload();
+        ActionResult::consumed(true)
+    }
 }
 
 impl Component for PreviewComponent {
+    fn register_config(&mut self, config: &Config, parent_comp: &AppComponent) {
+        let _ = parent_comp;
+        self.theme = config.theme.clone();
+    }
     fn register_async_action_sender(&mut self, sender: AsyncActionSender) {
         self.async_action_sender = Some(sender)
     }
@@ -87,8 +158,10 @@ impl Component for PreviewComponent {
                 self.reload();
                 return ActionResult::consumed(true);
             }
+            Action::ScrollPreviewUp => return self.scroll(false),
+            Action::ScrollPreviewDown => return self.scroll(true),
             Action::Resize(_, _) => {
-                self.reload();
+                self.stale = true;
                 return ActionResult::not_consumed(true);
             }
             _ => {}
@@ -97,8 +170,8 @@ impl Component for PreviewComponent {
     }
 
     fn handle_async_action(&mut self, action: &AsyncAction) -> ActionResult {
-        if let AsyncAction::PreviewContents(contents) = action {
-            self.contents = contents.clone();
+        if let AsyncAction::PreviewContents(preview) = action {
+            self.preview = preview.clone();
             return ActionResult::consumed(true);
         }
         Default::default()
@@ -106,11 +179,21 @@ impl Component for PreviewComponent {
 
     fn render(&mut self, frame: &mut Frame, area: Rect) {
         let title = Line::raw(" Preview ").left_aligned();
-        let block = default_block().title_top(title);
-        let text = self.contents.clone().unwrap_or_default();
-        let paragraph = Paragraph::new(text).block(block).gray();
+        let block = default_block(&self.theme).title_top(title);
+        let mut text = Vec::new();
+        if let Some(preview) = &self.preview {
+            let header = preview.header.as_deref().unwrap_or_default();
+            text.push(Line::styled(header, self.theme.muted));
+            text.extend(preview.lines.iter().map(|line| Line::raw(line.as_str())));
+        }
+        let paragraph = Paragraph::new(text).block(block).style(self.theme.preview);
         frame.render_widget(paragraph, area);
-        self.lines = area.height as usize - 2;
+        // The first line is taken by the header
+        let lines = area.height.saturating_sub(3) as usize;
+        if lines != self.lines {
+            self.lines = lines;
+            self.stale = true;
+        }
         self.read_lines();
     }
 }
@@ -120,3 +203,303 @@ impl Drop for PreviewComponent {
         self.task.abort();
     }
 }
+
+/// Reads `count` lines of the preview of `path`, from `first_line` on. The entries of
+/// archives are found in `listing`, which is read again if it lists another archive.
+fn read_preview(
+    path: &Path,
+    first_line: usize,
+    count: usize,
+    listing: &Mutex<Option<ArchiveListing>>,
+) -> io::Result<Preview> {
+    if let Some((archive, inner)) = split_archive_path(path) {
+        let mut cached = listing.lock().unwrap_or_else(|e| e.into_inner());
+        let listing = match cached.take() {
+            Some(listing) if listing.is_current(archive) => listing,
+            _ => ArchiveListing::read(archive)?,
+        };
+        let listing = cached.insert(listing);
+        return read_archive_preview(archive, inner, listing, first_line, count);
+    }
+    let metadata = fs::metadata(path)?;
+    let child_metadata = ChildMetadata::from(&metadata);
+    if metadata.is_dir() {
+        let mut entries: Vec<(bool, String)> = fs::read_dir(path)?
+            .flatten()
+            .map(|entry| {
+                let is_dir = entry.path().is_dir();
+                (is_dir, entry.file_name().to_string_lossy().to_string())
+            })
+            .collect();
+        entries.sort_by_key(|(is_dir, name)| (!is_dir, name.to_lowercase()));
+        return Ok(listing_preview(
+            &entries,
+            &child_metadata,
+            first_line,
+            count,
+        ));
+    }
+    let mut preview = read_file_preview(File::open(path)?, first_line, count)?;
+    preview.header = Some(metadata_header(&child_metadata, None));
+    Ok(preview)
+}
+
+/// Previews the file or folder `inner` of the archive. Files are read as a stream up to the
+/// lines shown, since their entries can't be seeked in.
+fn read_archive_preview(
+    archive: &Path,
+    inner: &Path,
+    listing: &ArchiveListing,
+    first_line: usize,
+    count: usize,
+) -> io::Result<Preview> {
+    match listing.entry(inner) {
+        Some(entry) if !entry.is_dir => {
+            let mut preview = read_entry_with(archive, inner, |reader| {
+                read_stream_preview(reader, first_line, count)
+            })?;
+            preview.header = Some(metadata_header(&ChildMetadata::from(entry), None));
+            Ok(preview)
+        }
+        entry => {
+            let entries: Vec<(bool, String)> = listing
+                .list_dir(inner)
+                .into_iter()
+                .map(|entry| {
+                    let name = entry.path.file_name().unwrap_or_default();
+                    (entry.is_dir, name.to_string_lossy().to_string())
+                })
+                .collect();
+            let metadata = match entry {
+                Some(entry) => ChildMetadata::from(entry),
+                None => ChildMetadata::from(&fs::metadata(archive)?),
+            };
+            Ok(listing_preview(&entries, &metadata, first_line, count))
+        }
+    }
+}
+
+/// Lists the folders and files of a folder, folders first and marked with a trailing `/`.
+fn listing_preview(
+    entries: &[(bool, String)],
+    metadata: &ChildMetadata,
+    first_line: usize,
+    count: usize,
+) -> Preview {
+    let lines = entries
+        .iter()
+        .skip(first_line)
+        .take(count)
+        .map(|(is_dir, name)| {
+            if *is_dir {
+                format!("{name}/")
+            } else {
+                name.clone()
+            }
+        })
+        .collect();
+    Preview {
+        header: Some(metadata_header(metadata, Some(entries.len()))),
+        lines,
+        reached_end: first_line + count >= entries.len(),
+    }
+}
+
+/// Size, or number of items of folders, modification time and permissions.
+fn metadata_header(metadata: &ChildMetadata, items: Option<usize>) -> String {
+    let size = match items {
+        Some(1) => "1 item".to_string(),
+        Some(items) => format!("{items} items"),
+        None => metadata.column(Column::Size).trim().to_string(),
+    };
+    let modified = metadata.column(Column::Modified);
+    let permissions = metadata.column(Column::Permissions);
+    format!("{size}  {}  {permissions}", modified.trim())
+}
+
+/// Reads the lines of a text file, or a hex dump of a binary one.
+fn read_file_preview<R: Read + Seek>(
+    mut reader: R,
+    first_line: usize,
+    count: usize,
+) -> io::Result<Preview> {
+    let start = read_start(&mut reader)?;
+    if is_binary(&start) {
+        reader.seek(SeekFrom::Start((first_line * HEX_DUMP_WIDTH) as u64))?;
+        return hex_dump(reader, first_line, count);
+    }
+    reader.seek(SeekFrom::Start(0))?;
+    read_text_preview(reader, first_line, count)
+}
+
+/// Like [`read_file_preview`], for readers that can't be seeked in, which skip what comes
+/// before the first line by reading it.
+fn read_stream_preview<R: Read>(
+    mut reader: R,
+    first_line: usize,
+    count: usize,
+) -> io::Result<Preview> {
+    let start = read_start(&mut reader)?;
+    let binary = is_binary(&start);
+    let mut reader = Cursor::new(start).chain(reader);
+    if binary {
+        let skipped = (first_line * HEX_DUMP_WIDTH) as u64;
+        io::copy(&mut reader.by_ref().take(skipped), &mut io::sink())?;
+        return hex_dump(reader, first_line, count);
+    }
+    read_text_preview(reader, first_line, count)
+}
+
+/// Reads the bytes binary files are told apart from text with.
+fn read_start<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut start = Vec::with_capacity(BINARY_SNIFF_BYTES);
+    reader
+        .take(BINARY_SNIFF_BYTES as u64)
+        .read_to_end(&mut start)?;
+    Ok(start)
+}
+
+fn read_text_preview<R: Read>(reader: R, first_line: usize, count: usize) -> io::Result<Preview> {
+    let mut reader = BufReader::new(reader);
+    for _ in 0..first_line {
+        if read_capped_line(&mut reader, 0)?.is_none() {
+            break;
+        }
+    }
+    let mut lines = Vec::with_capacity(count);
+    while lines.len() < count {
+        let Some(line) = read_capped_line(&mut reader, MAX_LINE_BYTES)? else {
+            break;
+        };
+        lines.push(line);
+    }
+    let reached_end = reader.fill_buf()?.is_empty();
+    Ok(Preview {
+        header: None,
+        lines,
+        reached_end,
+    })
+}
+
+/// Whether the start of a file has bytes that are not in text, like null bytes or invalid
+/// UTF-8. A character cut at the end is not counted.
+fn is_binary(start: &[u8]) -> bool {
+    if start.contains(&0) {
+        return true;
+    }
+    match std::str::from_utf8(start) {
+        Ok(_) => false,
+        Err(error) => error.error_len().is_some(),
+    }
+}
+
+/// Reads a line, keeping only its first `cap` bytes so that huge lines do not fill the memory.
+/// Returns `None` at the end of the file.
+fn read_capped_line<R: BufRead>(reader: &mut R, cap: usize) -> io::Result<Option<String>> {
+    let mut line = Vec::new();
+    let mut read_any = false;
+    let mut truncated = false;
+    loop {
+        let available = reader.fill_buf()?;
+        if available.is_empty() {
+            break;
+        }
+        read_any = true;
+        let newline = available.iter().position(|&byte| byte == b'\n');
+        let chunk = &available[..newline.unwrap_or(available.len())];
+        let room = cap.saturating_sub(line.len());
+        truncated |= chunk.len() > room;
+        line.extend_from_slice(&chunk[..chunk.len().min(room)]);
+        let consumed = newline.map_or(available.len(), |i| i + 1);
+        reader.consume(consumed);
+        if newline.is_some() {
+            break;
+        }
+    }
+    if !read_any {
+        return Ok(None);
+    }
+    let mut line = String::from_utf8_lossy(&line).into_owned();
+    if line.ends_with('\r') {
+        line.pop();
+    }
+    if truncated && cap > 0 {
+        line.push('…');
+    }
+    Ok(Some(line))
+}
+
+/// Shows the bytes in hex and as ASCII, `HEX_DUMP_WIDTH` bytes per line, starting at the line
+/// the reader was moved to.
+fn hex_dump<R: Read>(reader: R, first_line: usize, count: usize) -> io::Result<Preview> {
+    let mut bytes = Vec::with_capacity(count * HEX_DUMP_WIDTH);
+    reader
+        .take((count * HEX_DUMP_WIDTH) as u64)
+        .read_to_end(&mut bytes)?;
+    let lines = bytes
+        .chunks(HEX_DUMP_WIDTH)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let offset = (first_line + i) * HEX_DUMP_WIDTH;
+            let hex: Vec<String> = chunk.iter().map(|byte| format!("{byte:02x}")).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&byte| {
+                    if byte.is_ascii_graphic() || byte == b' ' {
+                        byte as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            let width = HEX_DUMP_WIDTH * 3 - 1;
+            format!("{offset:08x}  {:width$}  |{ascii}|", hex.join(" "))
+        })
+        .collect();
+    Ok(Preview {
+        header: None,
+        lines,
+        reached_end: bytes.len() < count * HEX_DUMP_WIDTH,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn huge_lines_are_cut_while_reading() {
+        let text = format!("{}\nshort\r\n", "a".repeat(MAX_LINE_BYTES * 4));
+        let mut reader = Cursor::new(text);
+        let long = read_capped_line(&mut reader, MAX_LINE_BYTES)
+            .unwrap()
+            .unwrap();
+        assert_eq!(long.len(), MAX_LINE_BYTES + '…'.len_utf8());
+        let short = read_capped_line(&mut reader, MAX_LINE_BYTES).unwrap();
+        assert_eq!(short.as_deref(), Some("short"));
+        assert_eq!(read_capped_line(&mut reader, MAX_LINE_BYTES).unwrap(), None);
+    }
+
+    #[test]
+    fn binary_files_are_previewed_as_a_hex_dump() {
+        let bytes: Vec<u8> = (0..40).collect();
+        let preview = read_file_preview(Cursor::new(bytes), 1, 5).unwrap();
+        assert_eq!(preview.lines.len(), 2);
+        assert!(preview.lines[0].starts_with("00000010  10 11 12"));
+        assert!(preview.lines[1].ends_with("| !\"#$%&'|"));
+        assert!(preview.reached_end);
+    }
+
+    #[test]
+    fn streams_are_previewed_like_files() {
+        let binary: Vec<u8> = (0..40).collect();
+        let text = (0..10).map(|i| format!("line {i}\n")).collect::<String>();
+        for contents in [binary, text.into_bytes()] {
+            for first_line in [0, 1, 8, 20] {
+                let file = read_file_preview(Cursor::new(&contents), first_line, 3).unwrap();
+                let stream = read_stream_preview(&contents[..], first_line, 3).unwrap();
+                assert_eq!(stream, file);
+            }
+        }
+    }
+}
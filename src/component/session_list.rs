@@ -0,0 +1,246 @@
+use crate::action::{Action, ActionResult, AsyncAction, AsyncActionSender};
+use crate::component::component_utils::{
+    center, center_horizontally, center_vertically, default_block, key_label_format,
+};
+use crate::component::effect_runner::EffectRunner;
+use crate::component::file_selector::HIGHLIGHT_SYMBOL;
+use crate::component::{AppComponent, Component};
+use crate::config::effects::{dialog_enter, AnimationEvent};
+use crate::config::theme::Theme;
+use crate::config::Config;
+use crate::session::Session;
+use crossterm::event::KeyEvent;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Clear, HighlightSpacing, List, ListDirection, ListItem, ListState};
+use ratatui::Frame;
+use std::env::current_dir;
+use std::path::PathBuf;
+
+const LAST_SESSION_LABEL: &str = "Last session here";
+
+#[derive(Default)]
+struct SessionListKeybinds {
+    up: String,
+    down: String,
+    confirm: String,
+    cancel: String,
+}
+
+impl SessionListKeybinds {
+    fn register_keybinds(&mut self, app_component: &AppComponent, config: &Config) {
+        let keybinds = &config.keybindings;
+        self.up = keybinds.get_key_string_or_default(Action::Up, app_component);
+        self.down = keybinds.get_key_string_or_default(Action::Down, app_component);
+        self.confirm = keybinds.get_key_string_or_default(Action::Confirm, app_component);
+        self.cancel = keybinds.get_key_string_or_default(Action::Cancel, app_component);
+    }
+}
+
+struct SessionEntry {
+    label: String,
+    files_label: String,
+    session: Session,
+}
+
+impl SessionEntry {
+    fn new(label: String, session: Session) -> Self {
+        let files: Vec<String> = session
+            .buffers
+            .iter()
+            .filter_map(|b| Some(b.path.file_name()?.to_string_lossy().to_string()))
+            .collect();
+        Self {
+            label,
+            files_label: files.join(", "),
+            session,
+        }
+    }
+}
+
+/// A dialog listing the saved sessions, navigating to the selected one.
+///
+/// The last session of the working directory is listed first, if there is one.
+#[derive(Default)]
+pub struct SessionListComponent {
+    opened: bool,
+    data_dir: PathBuf,
+    sessions: Vec<SessionEntry>,
+    list_state: ListState,
+    async_action_sender: Option<AsyncActionSender>,
+    effect_runner: EffectRunner,
+    keybinds: SessionListKeybinds,
+    theme: Theme,
+}
+
+impl SessionListComponent {
+    pub fn show(&mut self) {
+        self.opened = true;
+        let effect = dialog_enter(self.theme.dialog_color());
+        self.effect_runner.add_effect(AnimationEvent::Dialog, effect);
+        self.load_sessions();
+    }
+    pub fn hide(&mut self) {
+        self.sessions.clear();
+        self.list_state.select(None);
+        self.opened = false;
+    }
+    pub fn showing(&self) -> bool {
+        self.opened
+    }
+    fn load_sessions(&mut self) {
+        self.sessions.clear();
+        let last_session = current_dir()
+            .ok()
+            .and_then(|dir| Session::load_last(&self.data_dir, &dir));
+        if let Some(session) = last_session {
+            let entry = SessionEntry::new(LAST_SESSION_LABEL.to_string(), session);
+            self.sessions.push(entry);
+        }
+        for name in Session::names(&self.data_dir) {
+            if let Ok(Some(session)) = Session::load(&self.data_dir, &name) {
+                self.sessions.push(SessionEntry::new(name, session));
+            }
+        }
+    }
+    fn move_down(&mut self) -> ActionResult {
+        if self.sessions.is_empty() {
+            return ActionResult::consumed(false);
+        }
+        let i = match self.list_state.selected() {
+            Some(selected) if selected + 1 >= self.sessions.len() => {
+                return ActionResult::consumed(false);
+            }
+            Some(selected) => selected + 1,
+            None => 0,
+        };
+        self.list_state.select(Some(i));
+        ActionResult::consumed(true)
+    }
+    fn move_up(&mut self) -> ActionResult {
+        if self.sessions.is_empty() {
+            return ActionResult::consumed(false);
+        }
+        let i = match self.list_state.selected() {
+            Some(0) => return ActionResult::consumed(false),
+            Some(selected) => selected - 1,
+            None => self.sessions.len() - 1,
+        };
+        self.list_state.select(Some(i));
+        ActionResult::consumed(true)
+    }
+    fn select(&mut self) -> ActionResult {
+        let Some(selected) = self.list_state.selected() else {
+            return ActionResult::not_consumed(false);
+        };
+        let session = self.sessions[selected].session.clone();
+        let component = AppComponent::RestoredSession(Box::new(session));
+        let action = AsyncAction::Navigate(Some(component));
+        let _ = self.async_action_sender.as_ref().unwrap().send(action);
+        self.hide();
+        ActionResult::consumed(true)
+    }
+    fn cancel(&mut self) -> ActionResult {
+        if self.list_state.selected().is_some() {
+            self.list_state.select(None);
+        } else {
+            self.hide();
+        }
+        ActionResult::consumed(true)
+    }
+    fn map_to_list_item<'a>(
+        theme: &Theme,
+        selected: Option<usize>,
+        entry: &SessionEntry,
+        i: usize,
+    ) -> ListItem<'a> {
+        let style = if selected.is_some_and(|s| s == i) {
+            theme.active
+        } else {
+            theme.muted
+        };
+        let label = Span::styled(entry.label.clone(), style);
+        let files = "   ".to_string() + &entry.files_label;
+        let line = Line::from(vec![label, Span::styled(files, theme.muted.italic())]);
+        ListItem::new(line)
+    }
+}
+
+impl Component for SessionListComponent {
+    fn register_config(&mut self, config: &Config, parent_comp: &AppComponent) {
+        self.data_dir = config.config.data_dir.clone();
+        self.keybinds.register_keybinds(parent_comp, config);
+        self.theme = config.theme.clone();
+        self.effect_runner.register_config(config, parent_comp);
+    }
+    fn register_async_action_sender(&mut self, sender: AsyncActionSender) {
+        self.effect_runner
+            .register_async_action_sender(sender.clone());
+        self.async_action_sender = Some(sender)
+    }
+    fn override_keybind_id(&self, key_event: KeyEvent) -> Option<&AppComponent> {
+        let _ = key_event;
+        if self.opened {
+            Some(&AppComponent::FileDialog)
+        } else {
+            None
+        }
+    }
+    fn handle_action(&mut self, action: &Action) -> ActionResult {
+        if !self.showing() {
+            return ActionResult::not_consumed(false);
+        }
+        match action {
+            Action::Up => return self.move_up(),
+            Action::Down => return self.move_down(),
+            Action::Confirm => return self.select(),
+            Action::Cancel => return self.cancel(),
+            _ => {}
+        }
+        ActionResult::consumed(false)
+    }
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        if !self.showing() {
+            return;
+        }
+        let area = center_horizontally(area, Constraint::Percentage(60));
+        let area = center_vertically(area, Constraint::Percentage(60));
+        frame.render_widget(Clear, area);
+        let title = Line::raw(" Sessions ").centered();
+        let up_down_title = format!(" {} {} ", self.keybinds.up, self.keybinds.down);
+        let up_down_title = Line::from(up_down_title).centered();
+        let mut block = default_block(&self.theme)
+            .title_top(title)
+            .title_bottom(up_down_title);
+        if self.list_state.selected().is_some() {
+            let label = key_label_format(&self.keybinds.confirm, "Restore");
+            let enter_title = Line::from(label).right_aligned();
+            let label = key_label_format(&self.keybinds.cancel, "Cancel");
+            let cancel_title = Line::from(label).left_aligned();
+            block = block.title_bottom(enter_title).title_bottom(cancel_title);
+        }
+        if self.sessions.is_empty() {
+            let block_area = block.inner(area);
+            let center = center(block_area);
+            let text = Text::raw("No sessions have been saved yet...").centered();
+            frame.render_widget(block, area);
+            frame.render_widget(text, center);
+        } else {
+            let selected = self.list_state.selected();
+            let mapped = self
+                .sessions
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| Self::map_to_list_item(&self.theme, selected, entry, i));
+            let list = List::new(mapped)
+                .direction(ListDirection::TopToBottom)
+                .highlight_symbol(HIGHLIGHT_SYMBOL)
+                .highlight_spacing(HighlightSpacing::Always)
+                .scroll_padding(5)
+                .block(block);
+            frame.render_stateful_widget(list, area, &mut self.list_state);
+        }
+        self.effect_runner.render(frame, area);
+    }
+}
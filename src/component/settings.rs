@@ -0,0 +1,506 @@
+use crate::action::{Action, ActionResult, AsyncAction, AsyncActionSender};
+use crate::component::component_utils::{default_block, key_label_format};
+use crate::component::file_selector::HIGHLIGHT_SYMBOL;
+use crate::component::{AppComponent, Component};
+use crate::config::editing::{bind_key, remove_key, repeated_keys};
+use crate::config::keybindings::{key_event_to_config_string, key_event_to_string, Keybindings};
+use crate::config::theme::Theme;
+use crate::config::{Config, CONFIG_FILE_NAME};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{HighlightSpacing, List, ListDirection, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+/// Keybinding sections in the order they are listed, with their title
+const SECTIONS: [(AppComponent, &str); 7] = [
+    (AppComponent::HomeScreen, "Home screen"),
+    (AppComponent::FileDialog, "File selector"),
+    (AppComponent::FileHistory, "File history"),
+    (AppComponent::Editor, "Editor"),
+    (AppComponent::Pager, "Pager"),
+    (AppComponent::Dialog, "Dialogs"),
+    (AppComponent::Settings, "Settings"),
+];
+/// Rows skipped by `Action::PageUp` and `Action::PageDown`
+const PAGE_ROWS: usize = 10;
+/// Width the keys are padded to, so the actions line up
+const KEY_WIDTH: usize = 20;
+
+#[derive(Default)]
+struct SettingsKeybinds {
+    up: String,
+    down: String,
+    confirm: String,
+    remove: String,
+    config: String,
+    cancel: String,
+}
+
+impl SettingsKeybinds {
+    fn register_keybinds(&mut self, app_component: &AppComponent, config: &Config) {
+        let keybinds = &config.keybindings;
+        self.up = keybinds.get_key_string_or_default(Action::Up, app_component);
+        self.down = keybinds.get_key_string_or_default(Action::Down, app_component);
+        self.confirm = keybinds.get_key_string_or_default(Action::Confirm, app_component);
+        self.remove = keybinds.get_key_string_or_default(Action::Remove, app_component);
+        self.config = keybinds.get_key_string_or_default(Action::Config, app_component);
+        self.cancel = keybinds.get_key_string_or_default(Action::Cancel, app_component);
+    }
+}
+
+enum Row {
+    Section(&'static str),
+    Binding(AppComponent, KeyEvent, Action),
+}
+
+#[derive(Default, Eq, PartialEq)]
+enum Mode {
+    #[default]
+    Browse,
+    /// Waiting for the key the selected action is bound to instead
+    PressKey,
+    /// Waiting for this key, bound to another action, to be pressed again to take it over
+    Replace(KeyEvent),
+}
+
+/// Lists the keybindings of every screen, rebinding the selected one to the next key pressed.
+///
+/// Changes are written to the config file, keeping the rest of it as it is, and applied once
+/// it is reloaded. Keys of the default keybindings are bound to nothing there, unbinding them.
+#[derive(Default)]
+pub struct SettingsComponent {
+    keybindings: Keybindings,
+    default_keybindings: Keybindings,
+    config_file: PathBuf,
+    /// Keys bound on several lines of the config file, flagged as conflicts
+    repeated_keys: HashSet<(AppComponent, KeyEvent)>,
+    rows: Vec<Row>,
+    list_state: ListState,
+    mode: Mode,
+    /// Result of the last change, and whether it failed
+    status: Option<(String, bool)>,
+    async_action_sender: Option<AsyncActionSender>,
+    keybinds: SettingsKeybinds,
+    theme: Theme,
+}
+
+impl SettingsComponent {
+    fn load_rows(&mut self) {
+        let selected = self
+            .selected_binding()
+            .map(|(section, key, _)| (section.clone(), *key));
+        self.rows.clear();
+        for (section, title) in SECTIONS {
+            let Some(bindings) = self.keybindings.get(&section) else {
+                continue;
+            };
+            let mut bindings: Vec<_> = bindings.iter().collect();
+            bindings
+                .sort_by_cached_key(|(key, action)| (action.to_string(), key_event_to_string(key)));
+            self.rows.push(Row::Section(title));
+            for (key, action) in bindings {
+                self.rows
+                    .push(Row::Binding(section.clone(), *key, action.clone()));
+            }
+        }
+        match selected {
+            Some((section, key)) => self.select_binding(&section, &key),
+            None => self.list_state.select(None),
+        }
+    }
+    fn selected_binding(&self) -> Option<(&AppComponent, &KeyEvent, &Action)> {
+        match self.rows.get(self.list_state.selected()?)? {
+            Row::Binding(section, key, action) => Some((section, key, action)),
+            Row::Section(_) => None,
+        }
+    }
+    fn select_binding(&mut self, section: &AppComponent, key: &KeyEvent) {
+        let index = self
+            .rows
+            .iter()
+            .position(|row| matches!(row, Row::Binding(s, k, _) if s == section && k == key));
+        self.list_state.select(index);
+    }
+    /// Selects the binding `rows` away from the selected one, skipping the section titles.
+    fn move_selection(&mut self, rows: isize) -> ActionResult {
+        let bindings: Vec<usize> = (0..self.rows.len())
+            .filter(|i| matches!(self.rows[*i], Row::Binding(..)))
+            .collect();
+        if bindings.is_empty() {
+            return ActionResult::consumed(false);
+        }
+        let current = self
+            .list_state
+            .selected()
+            .and_then(|selected| bindings.iter().position(|i| *i == selected));
+        let next = match current {
+            Some(current) => current.saturating_add_signed(rows).min(bindings.len() - 1),
+            None if rows < 0 => bindings.len() - 1,
+            None => 0,
+        };
+        self.list_state.select(Some(bindings[next]));
+        self.status = None;
+        ActionResult::consumed(true)
+    }
+    fn start_rebinding(&mut self) -> ActionResult {
+        if self.selected_binding().is_none() {
+            return ActionResult::consumed(false);
+        }
+        self.mode = Mode::PressKey;
+        self.status = None;
+        ActionResult::consumed(true)
+    }
+    fn press_key(&mut self, key: KeyEvent) -> ActionResult {
+        if matches!(key.code, KeyCode::Modifier(_)) {
+            return ActionResult::consumed(false);
+        }
+        if key.code == KeyCode::Esc && key.modifiers == KeyModifiers::NONE {
+            self.mode = Mode::Browse;
+            return ActionResult::consumed(true);
+        }
+        let Some((section, old_key, action)) = self.selected_binding() else {
+            self.mode = Mode::Browse;
+            return ActionResult::consumed(true);
+        };
+        let (section, old_key, action) = (section.clone(), *old_key, action.clone());
+        let name = key_event_to_string(&key);
+        if key_event_to_config_string(&key).is_none() {
+            self.mode = Mode::Browse;
+            self.status = Some((format!("{name} cannot be bound"), true));
+            return ActionResult::consumed(true);
+        }
+        let bound = self.keybindings.get_action(&section, key).cloned();
+        match bound {
+            Some(bound) if bound == action => {
+                self.mode = Mode::Browse;
+                self.status = Some((format!("{name} is already bound to {action}"), false));
+            }
+            Some(_) if self.mode != Mode::Replace(key) => self.mode = Mode::Replace(key),
+            _ => {
+                self.mode = Mode::Browse;
+                self.rebind(&section, old_key, key, action);
+            }
+        }
+        ActionResult::consumed(true)
+    }
+    fn rebind(&mut self, section: &AppComponent, old_key: KeyEvent, key: KeyEvent, action: Action) {
+        let result = self.edit_config_file(|contents| {
+            let contents = self.unbind_in(contents, section, &old_key)?;
+            bind_key(&contents, section, &key, Some(&action))
+        });
+        let name = key_event_to_string(&key);
+        if let Err(e) = result {
+            self.status = Some((format!("{name} was not bound: {e}"), true));
+            return;
+        }
+        if let Some(bindings) = self.keybindings.get_mut(section) {
+            bindings.remove(&old_key);
+            bindings.insert(key, action.clone());
+        }
+        self.status = Some((format!("{name} is now bound to {action}"), false));
+        self.load_rows();
+        self.select_binding(section, &key);
+    }
+    fn unbind(&mut self) -> ActionResult {
+        let Some((section, key, action)) = self.selected_binding() else {
+            return ActionResult::consumed(false);
+        };
+        let (section, key, action) = (section.clone(), *key, action.clone());
+        let result = self.edit_config_file(|contents| self.unbind_in(contents, &section, &key));
+        let name = key_event_to_string(&key);
+        self.status = match result {
+            Ok(()) => {
+                if let Some(bindings) = self.keybindings.get_mut(&section) {
+                    bindings.remove(&key);
+                }
+                self.load_rows();
+                Some((format!("{name} is no longer bound to {action}"), false))
+            }
+            Err(e) => Some((format!("{name} was not unbound: {e}"), true)),
+        };
+        ActionResult::consumed(true)
+    }
+    /// Unbinds `key` in the config file `contents`, binding it to nothing if it comes from the
+    /// default keybindings.
+    fn unbind_in(&self, contents: &str, section: &AppComponent, key: &KeyEvent) -> Result<String> {
+        if self.default_keybindings.get_action(section, *key).is_some() {
+            bind_key(contents, section, key, None)
+        } else {
+            remove_key(contents, section, key)
+        }
+    }
+    /// Edits the config file and has it reloaded, which the config watcher may not do if it
+    /// could not be started.
+    fn edit_config_file<F>(&self, edit: F) -> Result<()>
+    where
+        F: FnOnce(&str) -> Result<String>,
+    {
+        let file = &self.config_file;
+        let contents = if file.is_file() {
+            fs::read_to_string(file)?
+        } else {
+            String::new()
+        };
+        let contents = edit(&contents)?;
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(file, contents)?;
+        if let Some(sender) = &self.async_action_sender {
+            let _ = sender.send(AsyncAction::ConfigChanged);
+        }
+        Ok(())
+    }
+    fn open_config_file(&self) -> ActionResult {
+        let path = self.config_file.display().to_string();
+        let action = AsyncAction::Navigate(Some(AppComponent::OpenedEditor(path)));
+        let _ = self.async_action_sender.as_ref().unwrap().send(action);
+        ActionResult::consumed(false)
+    }
+    fn go_back(&self) -> ActionResult {
+        let _ = self
+            .async_action_sender
+            .as_ref()
+            .unwrap()
+            .send(AsyncAction::Navigate(None));
+        ActionResult::consumed(false)
+    }
+    fn status_line(&self) -> Line<'_> {
+        match (&self.mode, self.selected_binding()) {
+            (Mode::PressKey, Some((_, _, action))) => {
+                let text = format!("Press the new key for {action}, or esc to cancel");
+                Line::styled(text, self.theme.accent)
+            }
+            (Mode::Replace(key), Some((section, _, _))) => {
+                let name = key_event_to_string(key);
+                let bound = self
+                    .keybindings
+                    .get_action(section, *key)
+                    .map(Action::to_string)
+                    .unwrap_or_default();
+                let text = format!("{name} is bound to {bound}, press it again to replace it");
+                Line::styled(text, self.theme.error)
+            }
+            (_, selected) => match &self.status {
+                Some((text, true)) => Line::styled(text.clone(), self.theme.error),
+                Some((text, false)) => Line::styled(text.clone(), self.theme.notification),
+                None => match selected {
+                    Some((section, key, _)) if self.is_repeated(section, key) => {
+                        let name = key_event_to_string(key);
+                        let text = format!("{name} is bound on several lines of the config file");
+                        Line::styled(text, self.theme.error)
+                    }
+                    _ => Line::default(),
+                },
+            },
+        }
+    }
+    fn is_repeated(&self, section: &AppComponent, key: &KeyEvent) -> bool {
+        self.repeated_keys.contains(&(section.clone(), *key))
+    }
+    fn map_to_list_item<'a>(&self, row: &Row, selected: bool) -> ListItem<'a> {
+        match row {
+            Row::Section(title) => ListItem::new(Line::styled(*title, self.theme.accent.bold())),
+            Row::Binding(section, key, action) => {
+                let replaced = match &self.mode {
+                    Mode::Replace(replaced) => {
+                        replaced == key
+                            && self
+                                .selected_binding()
+                                .is_some_and(|(s, _, _)| s == section)
+                    }
+                    _ => false,
+                };
+                let conflict = replaced || self.is_repeated(section, key);
+                let style = if conflict {
+                    self.theme.error
+                } else if selected {
+                    self.theme.active
+                } else {
+                    self.theme.muted
+                };
+                let key = format!("{:>KEY_WIDTH$}  ", key_event_to_string(key));
+                let line = Line::from(vec![
+                    Span::styled(key, self.theme.keys),
+                    Span::styled(action.to_string(), style),
+                ]);
+                ListItem::new(line)
+            }
+        }
+    }
+}
+
+impl Component for SettingsComponent {
+    fn register_config(&mut self, config: &Config, parent_comp: &AppComponent) {
+        self.keybindings = config.keybindings.clone();
+        self.default_keybindings = Config::default_keybindings().unwrap_or_default();
+        self.config_file = config.config.config_dir.join(CONFIG_FILE_NAME);
+        self.repeated_keys = fs::read_to_string(&self.config_file)
+            .map(|contents| repeated_keys(&contents))
+            .unwrap_or_default();
+        self.keybinds.register_keybinds(parent_comp, config);
+        self.theme = config.theme.clone();
+        self.load_rows();
+    }
+    fn register_async_action_sender(&mut self, sender: AsyncActionSender) {
+        self.async_action_sender = Some(sender)
+    }
+    fn init(&mut self) {
+        self.move_selection(1);
+    }
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> ActionResult {
+        if self.mode == Mode::Browse {
+            return ActionResult::not_consumed(false);
+        }
+        self.press_key(key_event)
+    }
+    fn handle_action(&mut self, action: &Action) -> ActionResult {
+        match action {
+            Action::Up => self.move_selection(-1),
+            Action::Down => self.move_selection(1),
+            Action::PageUp => self.move_selection(-(PAGE_ROWS as isize)),
+            Action::PageDown => self.move_selection(PAGE_ROWS as isize),
+            Action::Confirm => self.start_rebinding(),
+            Action::Remove => self.unbind(),
+            Action::Config => self.open_config_file(),
+            Action::Cancel => self.go_back(),
+            _ => ActionResult::not_consumed(false),
+        }
+    }
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let title = Line::raw(" Settings ").centered();
+        let up_down_title = format!(" {} {} ", self.keybinds.up, self.keybinds.down);
+        let label = key_label_format(&self.keybinds.cancel, "Back");
+        let cancel_title = Line::from(label).left_aligned();
+        let label = key_label_format(&self.keybinds.config, "Edit file");
+        let config_title = Line::from(label).left_aligned();
+        let mut block = default_block(&self.theme)
+            .title_top(title)
+            .title_bottom(cancel_title)
+            .title_bottom(config_title)
+            .title_bottom(Line::from(up_down_title).centered());
+        if self.selected_binding().is_some() {
+            let label = key_label_format(&self.keybinds.remove, "Unbind");
+            let remove_title = Line::from(label).right_aligned();
+            let label = key_label_format(&self.keybinds.confirm, "Rebind");
+            let confirm_title = Line::from(label).right_aligned();
+            block = block.title_bottom(remove_title).title_bottom(confirm_title);
+        }
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let [list_area, status_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+        let selected = self.list_state.selected();
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| self.map_to_list_item(row, selected == Some(i)))
+            .collect();
+        let list = List::new(items)
+            .direction(ListDirection::TopToBottom)
+            .highlight_symbol(HIGHLIGHT_SYMBOL)
+            .highlight_spacing(HighlightSpacing::Always)
+            .scroll_padding(5);
+        frame.render_stateful_widget(list, list_area, &mut self.list_state);
+        frame.render_widget(Paragraph::new(self.status_line()).centered(), status_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::app_config::AppConfig;
+    use crate::test_util::TempDir;
+    use tokio::sync::mpsc;
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    fn settings(dir: &TempDir) -> (SettingsComponent, mpsc::UnboundedReceiver<AsyncAction>) {
+        let config = Config {
+            config: AppConfig {
+                config_dir: dir.join("config"),
+                ..Default::default()
+            },
+            keybindings: Config::default_keybindings().unwrap(),
+            ..Default::default()
+        };
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut settings = SettingsComponent::default();
+        settings.register_config(&config, &AppComponent::Settings);
+        settings.register_async_action_sender(sender);
+        settings.select_binding(&AppComponent::Editor, &ctrl('s'));
+        (settings, receiver)
+    }
+
+    fn config_file(dir: &TempDir) -> String {
+        fs::read_to_string(dir.join("config").join(CONFIG_FILE_NAME)).unwrap()
+    }
+
+    #[test]
+    fn rebinding_unbinds_the_default_key_and_reloads_the_config() {
+        let dir = TempDir::new("settings");
+        let (mut settings, mut receiver) = settings(&dir);
+        settings.start_rebinding();
+        settings.press_key(ctrl('k'));
+        let expected = "keybindings:\n  Editor:\n    ctrl-s: ~\n    ctrl-k: Save\n";
+        assert_eq!(config_file(&dir), expected);
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(AsyncAction::ConfigChanged)
+        ));
+        let editor = &AppComponent::Editor;
+        assert_eq!(
+            settings.selected_binding(),
+            Some((editor, &ctrl('k'), &Action::Save))
+        );
+    }
+
+    #[test]
+    fn keys_bound_to_another_action_are_replaced_when_pressed_twice() {
+        let dir = TempDir::new("settings");
+        let (mut settings, _receiver) = settings(&dir);
+        settings.start_rebinding();
+        settings.press_key(ctrl('q'));
+        assert!(settings.mode == Mode::Replace(ctrl('q')));
+        assert!(!dir.join("config").exists());
+        settings.press_key(ctrl('q'));
+        let expected = "keybindings:\n  Editor:\n    ctrl-s: ~\n    ctrl-q: Save\n";
+        assert_eq!(config_file(&dir), expected);
+        let editor = &AppComponent::Editor;
+        let action = settings.keybindings.get_action(editor, ctrl('q'));
+        assert_eq!(action, Some(&Action::Save));
+    }
+
+    #[test]
+    fn unbinding_keys_and_repeated_keys_are_written_to_the_file() {
+        let dir = TempDir::new("settings");
+        let (mut settings, _receiver) = settings(&dir);
+        settings.unbind();
+        assert_eq!(
+            config_file(&dir),
+            "keybindings:\n  Editor:\n    ctrl-s: ~\n"
+        );
+        assert!(settings
+            .selected_binding()
+            .is_none_or(|(_, key, _)| *key != ctrl('s')));
+
+        let file = dir.join("config").join(CONFIG_FILE_NAME);
+        fs::write(
+            &file,
+            "keybindings:\n  Editor:\n    ctrl-k: Save\n    Ctrl-K: Save\n",
+        )
+        .unwrap();
+        let (settings, _receiver) = self::settings(&dir);
+        assert!(settings.is_repeated(&AppComponent::Editor, &ctrl('k')));
+        assert!(!settings.is_repeated(&AppComponent::Editor, &ctrl('s')));
+    }
+}
@@ -0,0 +1,137 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use strum::Display;
+
+/// Largest size files are decompressed to, so a small file cannot fill up the memory
+pub const MAX_DECOMPRESSED_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Compression formats that files are transparently decompressed from and compressed back to.
+#[derive(Clone, Copy, Debug, Display, Eq, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum Codec {
+    Gzip,
+    Zstd,
+    Xz,
+}
+
+impl Codec {
+    const ALL: [Codec; 3] = [Codec::Gzip, Codec::Zstd, Codec::Xz];
+
+    fn magic(self) -> &'static [u8] {
+        match self {
+            Codec::Gzip => &[0x1f, 0x8b],
+            Codec::Zstd => &[0x28, 0xb5, 0x2f, 0xfd],
+            Codec::Xz => &[0xfd, b'7', b'z', b'X', b'Z', 0x00],
+        }
+    }
+    fn extension(self) -> &'static str {
+        match self {
+            Codec::Gzip => "gz",
+            Codec::Zstd => "zst",
+            Codec::Xz => "xz",
+        }
+    }
+    /// The codec the contents of the file are compressed with, known by their first bytes.
+    ///
+    /// Empty files, like the ones that do not exist yet, are known by their extension instead.
+    pub fn detect(path: &Path, contents: &[u8]) -> Option<Self> {
+        if contents.is_empty() {
+            return Self::from_extension(path);
+        }
+        Self::ALL
+            .into_iter()
+            .find(|codec| contents.starts_with(codec.magic()))
+    }
+    /// The codec files with the extension of `path` are compressed with, like `gzip` for
+    /// `log.gz`.
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|codec| codec.extension() == extension)
+    }
+    /// The path without the extension of this codec, like `log` for `log.gz`.
+    pub fn uncompressed_path(self, path: &Path) -> PathBuf {
+        let extension = path.extension().and_then(|e| e.to_str());
+        match extension {
+            Some(extension) if extension.eq_ignore_ascii_case(self.extension()) => {
+                path.with_extension("")
+            }
+            _ => path.to_path_buf(),
+        }
+    }
+    /// Decompresses the contents, failing if they are larger than 256 MiB once decompressed.
+    pub fn decompress(self, contents: &[u8]) -> io::Result<Vec<u8>> {
+        self.decompress_at_most(contents, MAX_DECOMPRESSED_SIZE)
+    }
+    fn decompress_at_most(self, contents: &[u8], limit: u64) -> io::Result<Vec<u8>> {
+        let decoder: Box<dyn Read + '_> = match self {
+            Codec::Gzip => Box::new(GzDecoder::new(contents)),
+            Codec::Zstd => Box::new(zstd::stream::read::Decoder::new(contents)?),
+            Codec::Xz => Box::new(xz2::read::XzDecoder::new(contents)),
+        };
+        read_at_most(decoder, limit)
+    }
+    pub fn compress(self, contents: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Codec::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(contents)?;
+                encoder.finish()
+            }
+            Codec::Zstd => zstd::encode_all(contents, zstd::DEFAULT_COMPRESSION_LEVEL),
+            Codec::Xz => {
+                let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+                encoder.write_all(contents)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+/// Reads everything the reader decompresses, failing once it is larger than `limit` bytes
+/// instead of trusting the size a file claims to have.
+pub fn read_at_most<R: Read>(reader: R, limit: u64) -> io::Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    reader.take(limit + 1).read_to_end(&mut decompressed)?;
+    if decompressed.len() as u64 > limit {
+        let size = limit >> 20;
+        let message = format!("The file is larger than {size} MiB once decompressed");
+        return Err(io::Error::new(io::ErrorKind::FileTooLarge, message));
+    }
+    Ok(decompressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compressed_contents_are_detected_by_their_magic_bytes() {
+        let text = b"line\n".repeat(100);
+        for codec in Codec::ALL {
+            let compressed = codec.compress(&text).unwrap();
+            let detected = Codec::detect(Path::new("fixture.txt"), &compressed);
+            assert_eq!(detected, Some(codec));
+            assert_eq!(codec.decompress(&compressed).unwrap(), text);
+        }
+        assert_eq!(Codec::detect(Path::new("plain.gz"), b"text"), None);
+        assert_eq!(Codec::detect(Path::new("new.log.XZ"), b""), Some(Codec::Xz));
+        let path = Path::new("app.log.zst");
+        assert_eq!(Codec::Zstd.uncompressed_path(path), Path::new("app.log"));
+    }
+
+    #[test]
+    fn decompressing_stops_at_the_limit() {
+        let text = vec![b'a'; 10_000];
+        for codec in Codec::ALL {
+            let compressed = codec.compress(&text).unwrap();
+            let error = codec.decompress_at_most(&compressed, 9_999).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::FileTooLarge);
+            let decompressed = codec.decompress_at_most(&compressed, 10_000).unwrap();
+            assert_eq!(decompressed, text);
+        }
+    }
+}
@@ -1,5 +1,10 @@
-use serde::{Deserialize, Serialize};
+use crate::config::theme::DEFAULT_THEME;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::path::PathBuf;
+use strum::Display;
+
+/// Fastest animation speed, above which scaled frame times could overflow
+pub const MAX_SPEED: f32 = 1000.0;
 
 #[derive(Clone, Default, Debug, Deserialize, Serialize)]
 pub struct AppConfig {
@@ -7,4 +12,167 @@ pub struct AppConfig {
     pub data_dir: PathBuf,
     #[serde(default)]
     pub config_dir: PathBuf,
+    /// Restores the last session of the working directory when opened without any file
+    #[serde(default)]
+    pub restore_session: bool,
+    /// Number of files kept in the file history, not counting pinned ones
+    #[serde(default = "default_history_limit")]
+    pub history_limit: usize,
+    /// How the file selector lists the contents of a folder
+    #[serde(default)]
+    pub file_selector: FileSelectorConfig,
+    /// Name of a built-in theme, or of a YAML file in the `themes` folder of the config
+    /// directory
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    #[serde(default)]
+    pub animations: AnimationsConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FileSelectorConfig {
+    #[serde(default)]
+    pub sort_by: SortKey,
+    /// Lists the files and folders starting with a dot
+    #[serde(default)]
+    pub show_hidden: bool,
+    /// Shows the columns next to the names of the files
+    #[serde(default)]
+    pub show_columns: bool,
+    #[serde(default = "default_columns")]
+    pub columns: Vec<Column>,
+}
+
+impl Default for FileSelectorConfig {
+    fn default() -> Self {
+        Self {
+            sort_by: SortKey::default(),
+            show_hidden: false,
+            show_columns: false,
+            columns: default_columns(),
+        }
+    }
+}
+
+/// How the interface is animated.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AnimationsConfig {
+    /// Turns every animation off, so the screen is only drawn again when something changes
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// How fast animations play, like `2.0` for twice as fast, up to [`MAX_SPEED`]. Animations
+    /// are off unless it is above zero
+    #[serde(default = "default_speed", deserialize_with = "deserialize_speed")]
+    pub speed: f32,
+    /// Opening the application
+    #[serde(default)]
+    pub startup: AnimationEffect,
+    #[serde(default)]
+    pub screen_enter: AnimationEffect,
+    #[serde(default)]
+    pub screen_leave: AnimationEffect,
+    /// Opening the file selector, the quick switcher, the file history or the sessions
+    #[serde(default)]
+    pub dialog: AnimationEffect,
+    /// Showing the help or the search box
+    #[serde(default)]
+    pub popup: AnimationEffect,
+    /// Showing a notification or a confirmation
+    #[serde(default)]
+    pub notification: AnimationEffect,
+}
+
+impl Default for AnimationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            speed: default_speed(),
+            startup: AnimationEffect::default(),
+            screen_enter: AnimationEffect::default(),
+            screen_leave: AnimationEffect::default(),
+            dialog: AnimationEffect::default(),
+            popup: AnimationEffect::default(),
+            notification: AnimationEffect::default(),
+        }
+    }
+}
+
+/// Effect an event is animated with. Leaving a screen plays the effect backwards.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AnimationEffect {
+    /// The effect the event has unless configured otherwise
+    #[default]
+    Default,
+    None,
+    /// Characters come together from random cells
+    Coalesce,
+    /// Sweeps in from the left
+    Sweep,
+    /// Fades in from the background
+    Fade,
+}
+
+/// What the contents of a folder are sorted by. Folders are always listed before files.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Display, Eq, PartialEq, Serialize)]
+#[strum(serialize_all = "lowercase")]
+pub enum SortKey {
+    /// Natural order of the names, so that `file2` comes before `file10`
+    #[default]
+    Name,
+    /// Largest first
+    Size,
+    /// Most recently modified first
+    Modified,
+    Extension,
+}
+
+impl SortKey {
+    pub fn next(self) -> Self {
+        match self {
+            SortKey::Name => SortKey::Size,
+            SortKey::Size => SortKey::Modified,
+            SortKey::Modified => SortKey::Extension,
+            SortKey::Extension => SortKey::Name,
+        }
+    }
+}
+
+/// Details of a file that can be shown next to its name.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Column {
+    Permissions,
+    Size,
+    /// Last modification time, in UTC
+    Modified,
+}
+
+fn default_columns() -> Vec<Column> {
+    vec![Column::Permissions, Column::Size, Column::Modified]
+}
+
+fn default_theme() -> String {
+    DEFAULT_THEME.to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_speed() -> f32 {
+    1.0
+}
+
+/// Reads the animation speed, using the default one instead of `.nan` or `.inf`, and keeping
+/// finite speeds between zero and [`MAX_SPEED`], since time cannot be scaled by the others.
+fn deserialize_speed<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    let speed = f32::deserialize(deserializer)?;
+    if speed.is_finite() {
+        Ok(speed.clamp(0.0, MAX_SPEED))
+    } else {
+        Ok(default_speed())
+    }
+}
+
+pub fn default_history_limit() -> usize {
+    100
 }
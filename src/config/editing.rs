@@ -0,0 +1,290 @@
+use crate::action::Action;
+use crate::component::AppComponent;
+use crate::config::keybindings::{key_event_to_config_string, parse_key_event};
+use crate::config::yaml::{is_null, Marked, Node};
+use color_eyre::eyre::{bail, eyre};
+use color_eyre::Result;
+use crossterm::event::KeyEvent;
+use std::collections::HashSet;
+
+const INDENT: &str = "  ";
+
+/// Binds `key` to `action` in the `section` keybindings of the config file `contents`, or to
+/// nothing when `action` is `None`, which unbinds it from the default keybindings.
+///
+/// The first line the key is bound on is replaced if there is one, and the other lines binding
+/// it under another spelling are removed. Otherwise a line is added to the section, creating it
+/// if needed. The rest of the file is kept as written, comments and line endings included.
+pub fn bind_key(
+    contents: &str,
+    section: &AppComponent,
+    key: &KeyEvent,
+    action: Option<&Action>,
+) -> Result<String> {
+    let raw_key = key_event_to_config_string(key)
+        .ok_or_else(|| eyre!("This key cannot be written to the config file"))?;
+    let value = action.map_or("~".to_string(), Action::to_string);
+    let binding = format!("{}: {value}", yaml_key(&raw_key));
+    let section_name = serde_yaml::to_string(section)?.trim().to_string();
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+    let Some(root) = Marked::parse(contents)? else {
+        lines.extend(new_keybindings(&section_name, &binding));
+        return Ok(join(lines, contents));
+    };
+    let Some((keybindings_key, keybindings)) = root.get("keybindings") else {
+        if !matches!(root.node, Node::Map(_)) {
+            bail!("The config file does not map settings to their values");
+        }
+        lines.extend(new_keybindings(&section_name, &binding));
+        return Ok(join(lines, contents));
+    };
+    let sections = match &keybindings.node {
+        Node::Scalar(value) if is_null(value) => vec![],
+        Node::Map(sections) => entry_lines(sections),
+        _ => bail!("The keybindings of the config file are not a map"),
+    };
+    check_block_style(keybindings_key.line, &sections, "keybindings")?;
+    let Some((section_key, bindings)) = keybindings.get(&section_name) else {
+        let section_indent = sections
+            .first()
+            .map_or(INDENT.to_string(), |(line, _)| indentation(&lines, *line));
+        let new_section = [
+            format!("{section_indent}{section_name}:"),
+            format!("{section_indent}{INDENT}{binding}"),
+        ];
+        let after = if sections.is_empty() {
+            keybindings_key.line
+        } else {
+            keybindings.last_line()
+        };
+        lines.splice(after..after, new_section);
+        return Ok(join(lines, contents));
+    };
+    let entries = match &bindings.node {
+        Node::Scalar(value) if is_null(value) => vec![],
+        Node::Map(entries) => entry_lines(entries),
+        _ => bail!("The keybindings of {section_name} are not a map"),
+    };
+    check_block_style(section_key.line, &entries, &section_name)?;
+    let bound_lines: Vec<usize> = entries
+        .iter()
+        .filter(|(_, raw)| parse_key_event(raw).is_ok_and(|event| event == *key))
+        .map(|(line, _)| *line)
+        .collect();
+    match bound_lines.split_first() {
+        Some((&line, repeated)) => {
+            let indent = indentation(&lines, line);
+            let comment = lines[line - 1]
+                .find(" #")
+                .map(|start| lines[line - 1][start..].to_string())
+                .unwrap_or_default();
+            lines[line - 1] = format!("{indent}{binding}{comment}");
+            for line in repeated.iter().rev() {
+                lines.remove(line - 1);
+            }
+        }
+        None => {
+            let indent = entries.last().map_or_else(
+                || indentation(&lines, section_key.line) + INDENT,
+                |(line, _)| indentation(&lines, *line),
+            );
+            let after = if entries.is_empty() {
+                section_key.line
+            } else {
+                bindings.last_line()
+            };
+            lines.insert(after, format!("{indent}{binding}"));
+        }
+    }
+    Ok(join(lines, contents))
+}
+
+/// Removes the line binding `key` in the `section` keybindings of the config file `contents`,
+/// so its default binding applies again. The file is returned as it is if there is no such line.
+pub fn remove_key(contents: &str, section: &AppComponent, key: &KeyEvent) -> Result<String> {
+    let section_name = serde_yaml::to_string(section)?.trim().to_string();
+    let root = Marked::parse(contents)?;
+    let bindings = root
+        .as_ref()
+        .and_then(|root| root.get("keybindings"))
+        .and_then(|(_, keybindings)| keybindings.get(&section_name));
+    let Some((section_key, bindings)) = bindings else {
+        return Ok(contents.to_string());
+    };
+    let Node::Map(entries) = &bindings.node else {
+        return Ok(contents.to_string());
+    };
+    let entries = entry_lines(entries);
+    check_block_style(section_key.line, &entries, &section_name)?;
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+    let bound_lines = entries
+        .iter()
+        .rev()
+        .filter(|(_, raw)| parse_key_event(raw).is_ok_and(|event| event == *key));
+    for (line, _) in bound_lines {
+        lines.remove(line - 1);
+    }
+    Ok(join(lines, contents))
+}
+
+/// Keys bound on several lines of the same keybindings section of the config file `contents`,
+/// under different spellings like `ctrl-s` and `Ctrl-S`.
+pub fn repeated_keys(contents: &str) -> HashSet<(AppComponent, KeyEvent)> {
+    let mut repeated = HashSet::new();
+    let root = Marked::parse(contents).ok().flatten();
+    let keybindings = root.as_ref().and_then(|root| root.get("keybindings"));
+    let Some((
+        _,
+        Marked {
+            node: Node::Map(sections),
+            ..
+        },
+    )) = keybindings
+    else {
+        return repeated;
+    };
+    for (section, bindings) in sections {
+        let section = serde_yaml::from_str::<AppComponent>(section.scalar().unwrap_or_default());
+        let (Ok(section), Node::Map(entries)) = (section, &bindings.node) else {
+            continue;
+        };
+        let mut bound = HashSet::new();
+        for (key, _) in entries {
+            let Ok(key) = parse_key_event(key.scalar().unwrap_or_default()) else {
+                continue;
+            };
+            if !bound.insert(key) {
+                repeated.insert((section.clone(), key));
+            }
+        }
+    }
+    repeated
+}
+
+/// Lines and keys of the entries of a map.
+fn entry_lines(entries: &[(Marked, Marked)]) -> Vec<(usize, String)> {
+    entries
+        .iter()
+        .map(|(key, _)| (key.line, key.scalar().unwrap_or_default().to_string()))
+        .collect()
+}
+
+/// Fails unless every entry is on its own line after the one of its map, since maps written in
+/// flow style, like `{ctrl-s: Save}`, cannot be edited line by line.
+fn check_block_style(map_line: usize, entries: &[(usize, String)], name: &str) -> Result<()> {
+    let mut previous = map_line;
+    for (line, _) in entries {
+        if *line <= previous {
+            bail!("The {name} section is written on one line, edit the config file instead");
+        }
+        previous = *line;
+    }
+    Ok(())
+}
+
+fn new_keybindings(section_name: &str, binding: &str) -> [String; 3] {
+    [
+        "keybindings:".to_string(),
+        format!("{INDENT}{section_name}:"),
+        format!("{INDENT}{INDENT}{binding}"),
+    ]
+}
+
+fn indentation(lines: &[String], line: usize) -> String {
+    let text = &lines[line - 1];
+    text[..text.len() - text.trim_start().len()].to_string()
+}
+
+/// Quotes the key unless it is made only of letters, digits and hyphens.
+fn yaml_key(key: &str) -> String {
+    if key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        key.to_string()
+    } else {
+        format!("'{}'", key.replace('\'', "''"))
+    }
+}
+
+/// Joins the lines with the line ending of the original `contents`.
+fn join(lines: Vec<String>, contents: &str) -> String {
+    let line_ending = if contents.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut joined = lines.join(line_ending);
+    joined.push_str(line_ending);
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    const CONFIG: &str = "\
+# My config
+keybindings:
+  Editor:
+    ctrl-s: Save # saves
+    ctrl-q: Quit
+theme: gruvbox
+";
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    #[test]
+    fn rebinding_keeps_the_rest_of_the_file() {
+        let editor = AppComponent::Editor;
+        let contents = bind_key(CONFIG, &editor, &ctrl('s'), Some(&Action::Copy)).unwrap();
+        let contents = bind_key(&contents, &editor, &ctrl('k'), Some(&Action::Save)).unwrap();
+        let contents = remove_key(&contents, &editor, &ctrl('q')).unwrap();
+        let contents = bind_key(&contents, &AppComponent::Pager, &ctrl('q'), None).unwrap();
+        let expected = "\
+# My config
+keybindings:
+  Editor:
+    ctrl-s: Copy # saves
+    ctrl-k: Save
+  Pager:
+    ctrl-q: ~
+theme: gruvbox
+";
+        assert_eq!(contents, expected);
+    }
+
+    #[test]
+    fn keybindings_are_added_to_files_without_them() {
+        let key = KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE);
+        let contents = bind_key(
+            "theme: light",
+            &AppComponent::Pager,
+            &key,
+            Some(&Action::Search),
+        );
+        let expected = "theme: light\nkeybindings:\n  Pager:\n    '/': Search\n";
+        assert_eq!(contents.unwrap(), expected);
+        let flow = "keybindings: {Pager: {q: Quit}}\n";
+        assert!(bind_key(flow, &AppComponent::Pager, &key, None).is_err());
+    }
+
+    #[test]
+    fn line_endings_and_repeated_keys_are_handled() {
+        let editor = AppComponent::Editor;
+        let crlf = CONFIG.replace('\n', "\r\n");
+        let contents = bind_key(&crlf, &editor, &ctrl('k'), Some(&Action::Save)).unwrap();
+        let contents = remove_key(&contents, &editor, &ctrl('q')).unwrap();
+        let expected =
+            "# My config\r\nkeybindings:\r\n  Editor:\r\n    ctrl-s: Save # saves\r\n    \
+                        ctrl-k: Save\r\ntheme: gruvbox\r\n";
+        assert_eq!(contents, expected);
+
+        let repeated = "keybindings:\n  Editor:\n    ctrl-s: Save\n    Ctrl-S: Save\n";
+        let found = repeated_keys(repeated);
+        assert_eq!(found, HashSet::from([(editor.clone(), ctrl('s'))]));
+        let contents = bind_key(repeated, &editor, &ctrl('s'), Some(&Action::Copy)).unwrap();
+        assert_eq!(contents, "keybindings:\n  Editor:\n    ctrl-s: Copy\n");
+        assert!(repeated_keys(&contents).is_empty());
+    }
+}
@@ -1,8 +1,76 @@
+use crate::config::app_config::{AnimationEffect, AnimationsConfig};
 use ratatui::prelude::Color;
 use ratatui::style::Style;
-use tachyonfx::fx::{coalesce, coalesce_from, dissolve_to, evolve_into, EvolveSymbolSet};
+use tachyonfx::fx::{
+    coalesce, coalesce_from, dissolve, dissolve_to, evolve_into, fade_from, fade_to, sweep_in,
+    sweep_out, EvolveSymbolSet,
+};
 use tachyonfx::pattern::{RadialPattern, SweepPattern};
-use tachyonfx::{Effect, Interpolation};
+use tachyonfx::{Effect, Interpolation, Motion};
+
+/// What an animation is played for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AnimationEvent {
+    Startup,
+    ScreenEnter,
+    ScreenLeave,
+    Dialog,
+    Popup,
+    Notification,
+}
+
+impl AnimationEvent {
+    fn configured(self, config: &AnimationsConfig) -> AnimationEffect {
+        match self {
+            AnimationEvent::Startup => config.startup,
+            AnimationEvent::ScreenEnter => config.screen_enter,
+            AnimationEvent::ScreenLeave => config.screen_leave,
+            AnimationEvent::Dialog => config.dialog,
+            AnimationEvent::Popup => config.popup,
+            AnimationEvent::Notification => config.notification,
+        }
+    }
+    /// How long configured effects take, in milliseconds, close to the default ones
+    fn duration(self) -> u32 {
+        match self {
+            AnimationEvent::Startup => 1000,
+            AnimationEvent::ScreenEnter | AnimationEvent::ScreenLeave => 300,
+            AnimationEvent::Dialog => 250,
+            AnimationEvent::Popup | AnimationEvent::Notification => 200,
+        }
+    }
+}
+
+/// The effect `event` is animated with according to the config, or `None` if it is not
+/// animated.
+///
+/// `default` is the effect of the event unless another one is configured, and effects fade
+/// from and to the `background` color.
+pub fn configured_effect(
+    config: &AnimationsConfig,
+    event: AnimationEvent,
+    default: Effect,
+    background: Color,
+) -> Option<Effect> {
+    if !config.enabled || config.speed <= 0.0 {
+        return None;
+    }
+    let timer = (event.duration(), Interpolation::QuadOut);
+    let leaving = event == AnimationEvent::ScreenLeave;
+    let effect = match event.configured(config) {
+        AnimationEffect::Default => default,
+        AnimationEffect::None => return None,
+        AnimationEffect::Coalesce if leaving => dissolve(timer),
+        AnimationEffect::Coalesce => coalesce(timer),
+        AnimationEffect::Sweep if leaving => {
+            sweep_out(Motion::LeftToRight, 15, 0, background, timer)
+        }
+        AnimationEffect::Sweep => sweep_in(Motion::LeftToRight, 15, 0, background, timer),
+        AnimationEffect::Fade if leaving => fade_to(background, background, timer),
+        AnimationEffect::Fade => fade_from(background, background, timer),
+    };
+    Some(effect)
+}
 
 pub fn init_effect() -> Effect {
     let style = Style::default();
@@ -37,3 +105,39 @@ pub fn floating_component_enter_effect() -> Effect {
 pub fn floating_component_bottom_right_enter() -> Effect {
     coalesce_from(Style::default(), 200).with_pattern(RadialPattern::new(0.0, 0.0))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::app_config::MAX_SPEED;
+
+    fn animations(yaml: &str) -> AnimationsConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn effect(config: &AnimationsConfig, event: AnimationEvent) -> Option<Effect> {
+        configured_effect(config, event, show_notification_effect(), Color::Reset)
+    }
+
+    #[test]
+    fn speeds_that_can_not_scale_time_are_replaced() {
+        assert_eq!(animations("speed: .nan").speed, 1.0);
+        assert_eq!(animations("speed: .inf").speed, 1.0);
+        assert_eq!(animations("speed: -.inf").speed, 1.0);
+        assert_eq!(animations("speed: -2").speed, 0.0);
+        assert_eq!(animations("speed: 1e30").speed, MAX_SPEED);
+        assert_eq!(animations("speed: 2.5").speed, 2.5);
+        assert_eq!(animations("{}").speed, 1.0);
+    }
+
+    #[test]
+    fn events_are_animated_as_configured() {
+        let config = animations("notification: None\ndialog: Fade");
+        assert!(effect(&config, AnimationEvent::Notification).is_none());
+        assert!(effect(&config, AnimationEvent::Dialog).is_some());
+        assert!(effect(&config, AnimationEvent::Startup).is_some());
+        assert!(effect(&animations("enabled: false"), AnimationEvent::Startup).is_none());
+        assert!(effect(&animations("speed: 0"), AnimationEvent::Startup).is_none());
+        assert!(effect(&animations("speed: .nan"), AnimationEvent::Startup).is_some());
+    }
+}
@@ -1,9 +1,10 @@
 use crate::action::Action;
 use crate::component::AppComponent;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::de::Error;
 use serde::{Deserialize, Deserializer};
 use std::collections::hash_map::Iter;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ops::{Deref, DerefMut};
 
 type KeyEventMap = HashMap<KeyEvent, Action>;
@@ -12,11 +13,16 @@ type ScreenMap = HashMap<AppComponent, KeyEventMap>;
 #[derive(Clone, Default, Debug)]
 pub struct Keybindings {
     map: ScreenMap,
+    /// Keys bound to nothing with `~`, so they are left out of the default keybindings
+    unbound: HashMap<AppComponent, HashSet<KeyEvent>>,
 }
 
 impl Keybindings {
-    pub fn with(map: ScreenMap) -> Self {
-        Self { map }
+    /// Whether `key` was unbound in `app_component`, instead of bound to an action.
+    pub fn is_unbound(&self, app_component: &AppComponent, key: &KeyEvent) -> bool {
+        self.unbound
+            .get(app_component)
+            .is_some_and(|keys| keys.contains(key))
     }
     pub fn get_action(&self, app_component: &AppComponent, key: KeyEvent) -> Option<&Action> {
         self.map.get(app_component).and_then(|map| map.get(&key))
@@ -57,27 +63,66 @@ impl<'de> Deserialize<'de> for Keybindings {
         D: Deserializer<'de>,
     {
         let parsed_map =
-            <HashMap<AppComponent, HashMap<String, Action>>>::deserialize(deserializer)?;
-        let keybindings: ScreenMap = parsed_map
-            .into_iter()
-            .map(|(comp, key_event_map)| {
-                let converted: KeyEventMap = key_event_map
-                    .into_iter()
-                    .map(|(key, action)| (parse_key_event(&key).unwrap(), action))
-                    .collect();
-                (comp, converted)
-            })
-            .collect();
-        Ok(Keybindings::with(keybindings))
+            <HashMap<AppComponent, HashMap<String, Option<Action>>>>::deserialize(deserializer)?;
+        let mut keybindings = Keybindings::default();
+        for (comp, key_event_map) in parsed_map {
+            let bindings = keybindings.map.entry(comp.clone()).or_default();
+            let unbound = keybindings.unbound.entry(comp).or_default();
+            for (key, action) in key_event_map {
+                let key = parse_key_event(&key).map_err(D::Error::custom)?;
+                if let Some(action) = action {
+                    bindings.insert(key, action);
+                } else {
+                    unbound.insert(key);
+                }
+            }
+        }
+        Ok(keybindings)
     }
 }
 
-fn parse_key_event(raw: &str) -> Result<KeyEvent, String> {
+pub fn parse_key_event(raw: &str) -> Result<KeyEvent, String> {
     let raw_lower = raw.to_ascii_lowercase();
     let (remaining, modifiers) = extract_modifiers(&raw_lower);
     parse_key_code_with_modifiers(remaining, modifiers)
 }
 
+/// Names of the keys that are not a single character, as understood by `parse_key_event`
+pub(crate) const KEY_NAMES: &[&str] = &[
+    "esc",
+    "enter",
+    "left",
+    "right",
+    "up",
+    "down",
+    "home",
+    "end",
+    "pageup",
+    "pagedown",
+    "backtab",
+    "backspace",
+    "delete",
+    "insert",
+    "f1",
+    "f2",
+    "f3",
+    "f4",
+    "f5",
+    "f6",
+    "f7",
+    "f8",
+    "f9",
+    "f10",
+    "f11",
+    "f12",
+    "space",
+    "hyphen",
+    "minus",
+    "tab",
+];
+/// Names of the modifiers that may prefix a key, joined to it with `-`
+pub(crate) const MODIFIER_NAMES: &[&str] = &["ctrl", "alt", "shift"];
+
 fn parse_key_code_with_modifiers(
     raw: &str,
     mut modifiers: KeyModifiers,
@@ -128,6 +173,41 @@ fn parse_key_code_with_modifiers(
     Ok(KeyEvent::new(c, modifiers))
 }
 
+/// Names the key the way `parse_key_event` reads it, to be written to the config file, or
+/// `None` if it cannot be read back as the same key.
+pub fn key_event_to_config_string(key_event: &KeyEvent) -> Option<String> {
+    let mut modifiers = key_event.modifiers;
+    let name = match key_event.code {
+        KeyCode::Char(c) if c.is_ascii_graphic() && c != '-' => {
+            if c.is_ascii_uppercase() {
+                modifiers.insert(KeyModifiers::SHIFT);
+            }
+            c.to_ascii_lowercase().to_string()
+        }
+        code => {
+            // Backtab is always read with shift
+            if code == KeyCode::BackTab {
+                modifiers.remove(KeyModifiers::SHIFT);
+            }
+            let name = KEY_NAMES
+                .iter()
+                .find(|name| parse_key_event(name).is_ok_and(|event| event.code == code))?;
+            name.to_string()
+        }
+    };
+    let mut key: String = [
+        (KeyModifiers::CONTROL, "ctrl-"),
+        (KeyModifiers::ALT, "alt-"),
+        (KeyModifiers::SHIFT, "shift-"),
+    ]
+    .iter()
+    .filter(|(modifier, _)| modifiers.contains(*modifier))
+    .map(|(_, prefix)| *prefix)
+    .collect();
+    key.push_str(&name);
+    (parse_key_event(&key).ok()? == *key_event).then_some(key)
+}
+
 pub fn key_event_to_string(key_event: &KeyEvent) -> String {
     let char;
     let key_code = match key_event.code {
@@ -146,7 +226,7 @@ pub fn key_event_to_string(key_event: &KeyEvent) -> String {
         KeyCode::Delete => "delete",
         KeyCode::Insert => "insert",
         KeyCode::F(c) => {
-            char = format!("f({c})");
+            char = format!("f{c}");
             &char
         }
         KeyCode::Char(' ') => "space",
@@ -184,7 +264,7 @@ pub fn key_event_to_string(key_event: &KeyEvent) -> String {
     key
 }
 
-fn extract_modifiers(raw: &str) -> (&str, KeyModifiers) {
+pub(crate) fn extract_modifiers(raw: &str) -> (&str, KeyModifiers) {
     let mut modifiers = KeyModifiers::empty();
     let mut current = raw;
 
@@ -221,3 +301,35 @@ impl DerefMut for Keybindings {
         &mut self.map
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_strings_are_read_back_as_the_same_key() {
+        let keys = [
+            KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL),
+            KeyEvent::new(KeyCode::Char('S'), KeyModifiers::SHIFT),
+            KeyEvent::new(KeyCode::Char('-'), KeyModifiers::ALT),
+            KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT),
+            KeyEvent::new(KeyCode::F(5), KeyModifiers::CONTROL | KeyModifiers::ALT),
+            KeyEvent::new(KeyCode::PageUp, KeyModifiers::CONTROL),
+        ];
+        for key in keys {
+            let raw = key_event_to_config_string(&key).unwrap();
+            assert_eq!(parse_key_event(&raw), Ok(key), "{raw}");
+        }
+        for name in KEY_NAMES {
+            let key = parse_key_event(name).unwrap();
+            let raw = key_event_to_config_string(&key).unwrap();
+            assert_eq!(parse_key_event(&raw), Ok(key), "{name}");
+        }
+        let caps_lock = KeyEvent::new(KeyCode::CapsLock, KeyModifiers::NONE);
+        assert_eq!(key_event_to_config_string(&caps_lock), None);
+        let non_ascii = KeyEvent::new(KeyCode::Char('é'), KeyModifiers::NONE);
+        assert_eq!(key_event_to_config_string(&non_ascii), None);
+    }
+}
@@ -1,19 +1,27 @@
 use crate::config::app_config::AppConfig;
 use crate::config::keybindings::Keybindings;
+use crate::config::theme::Theme;
+use crate::config::validation::Problem;
+use color_eyre::eyre::bail;
 use color_eyre::Result;
 use config::File;
 use config::FileFormat::Yaml;
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
 use serde::Deserialize;
-use std::env;
 use std::path::PathBuf;
+use std::{env, fs};
 
-mod app_config;
+pub mod app_config;
+pub mod editing;
 pub mod effects;
 pub(crate) mod keybindings;
+pub mod theme;
+pub mod validation;
+pub mod watcher;
+pub(crate) mod yaml;
 
-const CONFIG_FILE_NAME: &str = "config.yaml";
+pub(crate) const CONFIG_FILE_NAME: &str = "config.yaml";
 const CONFIG: &str = include_str!("../../.config/config.yaml");
 
 lazy_static! {
@@ -33,27 +41,65 @@ pub struct Config {
     pub config: AppConfig,
     #[serde(default)]
     pub keybindings: Keybindings,
+    /// Styles of the theme named in `config`
+    #[serde(skip)]
+    pub theme: Theme,
+    /// Why the theme named in `config` could not be loaded, in which case the default one is
+    /// used
+    #[serde(skip)]
+    pub theme_error: Option<String>,
 }
 
 impl Config {
     pub fn new() -> Result<Config> {
-        let default_config = serde_yaml::from_str::<Config>(CONFIG)?;
         let config_dir = get_config_dir();
         let data_dir = get_data_dir();
         let file = config_dir.join(CONFIG_FILE_NAME);
+        if let Some(problems) = Self::check()?
+            && validation::has_errors(&problems)
+        {
+            bail!(validation::report(&file, &problems));
+        }
         let source = File::from(file.clone()).format(Yaml).required(false);
         let config = config::Config::builder()
             .set_default("config_dir", config_dir.to_str().unwrap())?
             .set_default("data_dir", data_dir.to_str().unwrap())?
             .add_source(source);
         let mut config: Config = config.build()?.try_deserialize()?;
-        for (app_component, default_bindings) in default_config.keybindings.iter() {
-            let user_bindings = config.keybindings.entry(app_component.clone()).or_default();
+        config.add_default_keybindings(&Self::default_keybindings()?);
+        match Theme::load(&config.config.theme, &config_dir) {
+            Ok(theme) => config.theme = theme,
+            Err(e) => config.theme_error = Some(e.to_string()),
+        }
+        Ok(config)
+    }
+    /// Adds the `defaults` keybindings to the ones of the config file, except for the keys it
+    /// binds to another action or unbinds with `~`.
+    fn add_default_keybindings(&mut self, defaults: &Keybindings) {
+        for (app_component, default_bindings) in defaults.iter() {
             for (key, cmd) in default_bindings.iter() {
+                if self.keybindings.is_unbound(app_component, key) {
+                    continue;
+                }
+                let user_bindings = self.keybindings.entry(app_component.clone()).or_default();
                 user_bindings.entry(*key).or_insert_with(|| cmd.clone());
             }
         }
-        Ok(config)
+    }
+    /// The keybindings the config file adds to, or unbinds keys from.
+    pub fn default_keybindings() -> Result<Keybindings> {
+        Ok(serde_yaml::from_str::<Config>(CONFIG)?.keybindings)
+    }
+    /// Finds the problems of the config file, or `None` when there is no config file and the
+    /// defaults are used.
+    pub fn check() -> Result<Option<Vec<Problem>>> {
+        let config_dir = get_config_dir();
+        let file = config_dir.join(CONFIG_FILE_NAME);
+        if !file.is_file() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&file)?;
+        Ok(Some(validation::check(&contents, &config_dir)))
     }
 }
 
@@ -68,7 +114,7 @@ pub fn get_config_dir() -> PathBuf {
 }
 
 pub fn get_config_file_dir() -> PathBuf {
-    get_config_dir().join("config.yml")
+    get_config_dir().join(CONFIG_FILE_NAME)
 }
 
 pub fn get_data_dir() -> PathBuf {
@@ -84,3 +130,32 @@ pub fn get_data_dir() -> PathBuf {
 fn project_directory() -> Option<ProjectDirs> {
     ProjectDirs::from("io", "github.knightleo", env!("CARGO_PKG_NAME"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::Action;
+    use crate::component::AppComponent;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn keys_bound_to_nothing_are_left_out_of_the_defaults() {
+        let yaml = "keybindings:\n  Editor:\n    ctrl-s: ~\n    ctrl-q: Save\n";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.add_default_keybindings(&Config::default_keybindings().unwrap());
+        let editor = AppComponent::Editor;
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        let keybindings = &config.keybindings;
+        assert_eq!(keybindings.get_action(&editor, ctrl('s')), None);
+        assert_eq!(
+            keybindings.get_action(&editor, ctrl('q')),
+            Some(&Action::Save)
+        );
+        assert_eq!(
+            keybindings.get_action(&editor, ctrl('c')),
+            Some(&Action::Copy)
+        );
+        let pager = keybindings.get_action(&AppComponent::Pager, KeyEvent::from(KeyCode::Down));
+        assert_eq!(pager, Some(&Action::Down));
+    }
+}
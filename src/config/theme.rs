@@ -0,0 +1,206 @@
+use color_eyre::eyre::{bail, Result};
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Deserializer};
+use std::fs;
+use std::path::Path;
+
+/// Folder inside the config directory where user themes are kept, one YAML file per theme
+pub const THEMES_DIR_NAME: &str = "themes";
+/// Name of the theme the interface is drawn with when none is configured
+pub const DEFAULT_THEME: &str = "default";
+/// Themes shipped with the application, besides the default one
+const BUILT_IN_THEMES: [(&str, &str); 2] = [
+    ("gruvbox", include_str!("../../.config/themes/gruvbox.yaml")),
+    ("light", include_str!("../../.config/themes/light.yaml")),
+];
+
+/// Styles the interface is drawn with, by what they are used for.
+///
+/// Themes are read from YAML, where each slot is either a color, like `dark gray`, `#1d2021` or
+/// `243`, used as the foreground, or a map with any of `fg`, `bg` and `modifiers`. Slots left
+/// out keep the style of the default theme.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    /// Background of the whole screen
+    #[serde(deserialize_with = "style")]
+    pub background: Style,
+    /// Background dialogs fade in from when opened
+    #[serde(deserialize_with = "style")]
+    pub dialog: Style,
+    /// Text of the editor
+    #[serde(deserialize_with = "style")]
+    pub text: Style,
+    /// Selected text in the editor
+    #[serde(deserialize_with = "style")]
+    pub selection: Style,
+    #[serde(deserialize_with = "style")]
+    pub line_numbers: Style,
+    /// Text matching the search in the editor
+    #[serde(deserialize_with = "style")]
+    pub search_matches: Style,
+    /// Borders of panes and dialogs
+    #[serde(deserialize_with = "style")]
+    pub border: Style,
+    /// Border of the pane that has the focus, when there are several
+    #[serde(deserialize_with = "style")]
+    pub focused_border: Style,
+    /// Item of a list under the cursor
+    #[serde(deserialize_with = "style")]
+    pub highlight: Style,
+    /// Items of a list that are current or can be picked
+    #[serde(deserialize_with = "style")]
+    pub active: Style,
+    /// Secondary text, like hints, placeholders and the items of a list that are not current
+    #[serde(deserialize_with = "style")]
+    pub muted: Style,
+    /// Text that should stand out, like marked files and fuzzy matched letters
+    #[serde(deserialize_with = "style")]
+    pub accent: Style,
+    /// Keys in the help
+    #[serde(deserialize_with = "style")]
+    pub keys: Style,
+    /// Contents of the preview pane
+    #[serde(deserialize_with = "style")]
+    pub preview: Style,
+    #[serde(deserialize_with = "style")]
+    pub notification: Style,
+    /// Error notifications and messages
+    #[serde(deserialize_with = "style")]
+    pub error: Style,
+    /// Lines git leaves out of commit messages and rebase todos
+    #[serde(deserialize_with = "style")]
+    pub comment: Style,
+    /// Columns past which commit message lines are too long
+    #[serde(deserialize_with = "style")]
+    pub ruler: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let background = Color::from_u32(0x1d2021);
+        Self {
+            background: Style::new().bg(background),
+            dialog: Style::new().bg(background),
+            text: Style::new(),
+            selection: Style::new().bg(Color::LightBlue),
+            line_numbers: Style::new().fg(Color::DarkGray),
+            search_matches: Style::new().bg(Color::Blue),
+            border: Style::new(),
+            focused_border: Style::new().fg(Color::White),
+            highlight: Style::new().fg(Color::White).add_modifier(Modifier::ITALIC),
+            active: Style::new().fg(Color::White),
+            muted: Style::new().fg(Color::DarkGray),
+            accent: Style::new().fg(Color::Yellow),
+            keys: Style::new().fg(Color::Gray),
+            preview: Style::new().fg(Color::Gray),
+            notification: Style::new(),
+            error: Style::new().fg(Color::Red),
+            comment: Style::new().fg(Color::DarkGray),
+            ruler: Style::new().bg(Color::from_u32(0x3c3836)),
+        }
+    }
+}
+
+impl Theme {
+    /// Loads the theme called `name`, either a built-in one or a user theme from the themes
+    /// folder inside `config_dir`, which takes precedence.
+    pub fn load(name: &str, config_dir: &Path) -> Result<Self> {
+        let file = config_dir
+            .join(THEMES_DIR_NAME)
+            .join(format!("{name}.yaml"));
+        if file.is_file() {
+            let contents = fs::read_to_string(&file)?;
+            return match serde_yaml::from_str(&contents) {
+                Ok(theme) => Ok(theme),
+                Err(e) => bail!("Invalid theme {}: {e}", file.display()),
+            };
+        }
+        if name == DEFAULT_THEME {
+            return Ok(Self::default());
+        }
+        match BUILT_IN_THEMES.iter().find(|(built_in, _)| *built_in == name) {
+            Some((_, contents)) => Ok(serde_yaml::from_str(contents)?),
+            None => bail!("No theme named {name}, and no {} file", file.display()),
+        }
+    }
+    /// Names of every theme that can be loaded: the default and built-in ones, and the user
+    /// themes inside `config_dir`, sorted.
+    pub fn names(config_dir: &Path) -> Vec<String> {
+        let mut names = vec![DEFAULT_THEME.to_string()];
+        names.extend(BUILT_IN_THEMES.iter().map(|(name, _)| name.to_string()));
+        if let Ok(entries) = fs::read_dir(config_dir.join(THEMES_DIR_NAME)) {
+            let user_themes = entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|extension| extension == "yaml"))
+                .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()));
+            names.extend(user_themes);
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+    /// Color dialogs fade in from, the background of the `dialog` slot.
+    pub fn dialog_color(&self) -> Color {
+        self.dialog.bg.unwrap_or(Color::Reset)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StyleDefinition {
+    Foreground(Color),
+    Style {
+        #[serde(default)]
+        fg: Option<Color>,
+        #[serde(default)]
+        bg: Option<Color>,
+        #[serde(default)]
+        modifiers: Vec<String>,
+    },
+}
+
+fn style<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Style, D::Error> {
+    let (fg, bg, modifiers) = match StyleDefinition::deserialize(deserializer)? {
+        StyleDefinition::Foreground(fg) => (Some(fg), None, Vec::new()),
+        StyleDefinition::Style { fg, bg, modifiers } => (fg, bg, modifiers),
+    };
+    let mut style = Style::new();
+    style.fg = fg;
+    style.bg = bg;
+    for name in modifiers {
+        let Some(modifier) = Modifier::from_name(&name.to_uppercase()) else {
+            let message = format!("unknown modifier {name}");
+            return Err(serde::de::Error::custom(message));
+        };
+        style = style.add_modifier(modifier);
+    }
+    Ok(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_slots_keep_the_default_style() {
+        let yaml = "
+line_numbers: '#665c54'
+selection:
+  bg: dark gray
+  modifiers: [bold, italic]
+";
+        let theme: Theme = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(theme.line_numbers, Style::new().fg(Color::from_u32(0x665c54)));
+        let selection = Style::new()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD | Modifier::ITALIC);
+        assert_eq!(theme.selection, selection);
+        assert_eq!(theme.error, Theme::default().error);
+        assert!(serde_yaml::from_str::<Theme>("borders: red").is_err());
+        for (name, _) in BUILT_IN_THEMES {
+            assert!(Theme::load(name, Path::new("/nonexistent")).is_ok());
+        }
+    }
+}
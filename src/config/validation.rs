@@ -0,0 +1,384 @@
+use crate::action::Action;
+use crate::component::AppComponent;
+use crate::config::app_config::{AppConfig, MAX_SPEED};
+use crate::config::keybindings::{extract_modifiers, parse_key_event, KEY_NAMES, MODIFIER_NAMES};
+use crate::config::theme::Theme;
+use crate::config::yaml::{is_null, Marked, Node};
+use crossterm::event::KeyEvent;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use strum::VariantNames;
+
+/// Something wrong in a config file, found by `check`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Problem {
+    /// Line of the config file, starting at 1
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+    /// What was probably meant instead, when something close enough is known
+    pub suggestion: Option<String>,
+}
+
+/// Whether the config can still be loaded despite a problem.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Severity {
+    /// The config is not loaded
+    Error,
+    /// The config is loaded, but is probably not what was meant
+    Warning,
+}
+
+impl Problem {
+    fn error(line: usize, message: String) -> Self {
+        Self {
+            line,
+            severity: Severity::Error,
+            message,
+            suggestion: None,
+        }
+    }
+    fn warning(line: usize, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(line, message)
+        }
+    }
+    fn suggesting<S: AsRef<str>>(mut self, input: &str, candidates: &[S]) -> Self {
+        self.suggestion = closest(input, candidates);
+        self
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean `{suggestion}`?")?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the problems found in `file`, one per line, like a compiler would.
+pub fn report(file: &Path, problems: &[Problem]) -> String {
+    problems
+        .iter()
+        .map(|problem| format!("{}:{}: {problem}", file.display(), problem.line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn has_errors(problems: &[Problem]) -> bool {
+    problems
+        .iter()
+        .any(|problem| problem.severity == Severity::Error)
+}
+
+/// Finds every problem of the config file `contents` instead of stopping at the first one:
+/// YAML syntax, settings of the wrong type, a missing theme, animation speeds that are not
+/// used as written, and keybindings with unknown sections, keys or actions, or bound twice.
+pub fn check(contents: &str, config_dir: &Path) -> Vec<Problem> {
+    let root = match Marked::parse(contents) {
+        Ok(Some(root)) => root,
+        Ok(None) => return vec![],
+        Err(e) => {
+            let message = format!("invalid YAML, {}", e.info());
+            return vec![Problem::error(e.marker().line(), message)];
+        }
+    };
+    let entries = match root.node {
+        Node::Map(entries) => entries,
+        Node::Scalar(value) if is_null(&value) => return vec![],
+        _ => {
+            let message = "the config must map settings to their values".to_string();
+            return vec![Problem::error(root.line, message)];
+        }
+    };
+    let mut problems = check_settings(contents, &entries);
+    for (key, value) in &entries {
+        match (key.scalar(), &value.node) {
+            (Some("theme"), Node::Scalar(name)) => {
+                if let Err(e) = Theme::load(name, config_dir) {
+                    let message = format!("{e}, the default theme is used");
+                    let problem = Problem::warning(value.line, message);
+                    problems.push(problem.suggesting(name, &Theme::names(config_dir)));
+                }
+            }
+            (Some("animations"), _) => {
+                let speed = value.get("speed").and_then(|(_, speed)| check_speed(speed));
+                problems.extend(speed);
+            }
+            (Some("keybindings"), Node::Map(sections)) => {
+                problems.extend(check_keybindings(sections));
+            }
+            (Some("keybindings"), Node::Scalar(value)) if is_null(value) => {}
+            (Some("keybindings"), _) => {
+                let message = "keybindings must map sections to their bindings".to_string();
+                problems.push(Problem::error(value.line, message));
+            }
+            _ => {}
+        }
+    }
+    problems.sort_by_key(|problem| problem.line);
+    problems
+}
+
+/// Deserializes the settings one at a time, since serde stops at the first error. Each one is
+/// read from its own lines, unless the config is written in flow style.
+fn check_settings(contents: &str, entries: &[(Marked, Marked)]) -> Vec<Problem> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut starts: Vec<usize> = entries.iter().map(|(key, _)| key.line).collect();
+    starts.dedup();
+    let chunks: Vec<(usize, String)> = if starts.len() == entries.len() {
+        let ends = starts.iter().skip(1).map(|next| next - 1);
+        let chunks = starts.iter().zip(ends.chain([lines.len()]));
+        chunks
+            .map(|(start, end)| (start - 1, lines[start - 1..end].join("\n")))
+            .collect()
+    } else {
+        vec![(0, contents.to_string())]
+    };
+    chunks
+        .into_iter()
+        .filter_map(|(offset, chunk)| {
+            let e = serde_yaml::from_str::<AppConfig>(&chunk).err()?;
+            let line = offset + e.location().map_or(1, |location| location.line());
+            let message = e.to_string();
+            let message = message.split(" at line ").next().unwrap_or_default();
+            Some(Problem::error(line, message.to_string()))
+        })
+        .collect()
+}
+
+/// Warns about animation speeds replaced when the config is loaded. Speeds that are not
+/// numbers at all are reported with the other settings.
+fn check_speed(speed: &Marked) -> Option<Problem> {
+    let value = speed.scalar()?;
+    let parsed: f32 = serde_yaml::from_str(value).ok()?;
+    let message = if !parsed.is_finite() {
+        format!("the animation speed `{value}` is not finite, the default speed is used")
+    } else if parsed < 0.0 {
+        format!("the animation speed `{value}` is negative, animations are turned off")
+    } else if parsed > MAX_SPEED {
+        format!("the animation speed `{value}` is above {MAX_SPEED}, which is used instead")
+    } else {
+        return None;
+    };
+    Some(Problem::warning(speed.line, message))
+}
+
+fn check_keybindings(sections: &[(Marked, Marked)]) -> Vec<Problem> {
+    let section_names = unit_variants::<AppComponent>(AppComponent::VARIANTS);
+    let action_names = unit_variants::<Action>(Action::VARIANTS);
+    let mut problems = vec![];
+    for (section, bindings) in sections {
+        let name = section.scalar().unwrap_or_default();
+        if serde_yaml::from_str::<AppComponent>(name).is_err() {
+            let message = format!("unknown keybindings section `{name}`");
+            let problem = Problem::error(section.line, message);
+            problems.push(problem.suggesting(name, &section_names));
+        }
+        let Node::Map(bindings) = &bindings.node else {
+            let message = format!("the keybindings of `{name}` must map keys to actions");
+            problems.push(Problem::error(bindings.line, message));
+            continue;
+        };
+        let mut bound: HashMap<KeyEvent, (usize, &str)> = HashMap::new();
+        for (key, action) in bindings {
+            let raw_key = key.scalar().unwrap_or_default();
+            // Binding a key to nothing unbinds it from the default keybindings
+            let unbinding = action.scalar().is_some_and(is_null);
+            let action_name = action.scalar().filter(|_| !unbinding);
+            if let Some(action_name) = action_name
+                && serde_yaml::from_str::<Action>(action_name).is_err()
+            {
+                let message = format!("unknown action `{action_name}`");
+                let problem = Problem::error(action.line, message);
+                problems.push(problem.suggesting(action_name, &action_names));
+            }
+            let key_event = match parse_key_event(raw_key) {
+                Ok(key_event) => key_event,
+                Err(_) => {
+                    problems.push(key_problem(key.line, raw_key));
+                    continue;
+                }
+            };
+            let action_name = if unbinding {
+                "nothing"
+            } else {
+                action_name.unwrap_or_default()
+            };
+            match bound.get(&key_event) {
+                Some((line, first)) if *first == action_name => {
+                    let message = format!(
+                        "`{raw_key}` is bound to {action_name} again, already done on line {line}"
+                    );
+                    problems.push(Problem::warning(key.line, message));
+                }
+                Some((line, first)) => {
+                    let message = format!(
+                        "`{raw_key}` is bound to both {first}, on line {line}, and {action_name}"
+                    );
+                    problems.push(Problem::error(key.line, message));
+                }
+                None => {
+                    bound.insert(key_event, (key.line, action_name));
+                }
+            }
+        }
+    }
+    problems
+}
+
+/// Explains why `raw` is not a key, suggesting the closest modifier or key name.
+fn key_problem(line: usize, raw: &str) -> Problem {
+    let lower = raw.to_ascii_lowercase();
+    let (remaining, _) = extract_modifiers(&lower);
+    match remaining.rsplit_once('-') {
+        Some((modifier, _)) if !modifier.is_empty() => {
+            let message = format!("unknown modifier `{modifier}` in key `{raw}`");
+            Problem::error(line, message).suggesting(modifier, MODIFIER_NAMES)
+        }
+        _ => {
+            let message = format!("unknown key `{raw}`");
+            Problem::error(line, message).suggesting(remaining, KEY_NAMES)
+        }
+    }
+}
+
+/// Names of the variants that are written as a plain word, and not with a value.
+fn unit_variants<T: serde::de::DeserializeOwned>(names: &[&'static str]) -> Vec<&'static str> {
+    names
+        .iter()
+        .copied()
+        .filter(|name| serde_yaml::from_str::<T>(name).is_ok())
+        .collect()
+}
+
+/// The candidate closest to `input`, ignoring case, as long as less than half of it differs.
+/// Between candidates as close, one `input` abbreviates is preferred, like `pageup` for `pgup`.
+fn closest<S: AsRef<str>>(input: &str, candidates: &[S]) -> Option<String> {
+    let input = input.to_lowercase();
+    candidates
+        .iter()
+        .map(|candidate| {
+            let lower = candidate.as_ref().to_lowercase();
+            let distance = levenshtein(&input, &lower);
+            (distance, !abbreviates(&input, &lower), candidate.as_ref())
+        })
+        .filter(|(distance, _, candidate)| *distance * 2 <= candidate.len().max(input.len()))
+        .min_by_key(|(distance, abbreviation, _)| (*distance, *abbreviation))
+        .map(|(_, _, candidate)| candidate.to_string())
+}
+
+/// Whether the characters of `input` all appear in `word`, in order.
+fn abbreviates(input: &str, word: &str) -> bool {
+    let mut word = word.chars();
+    input.chars().all(|c| word.any(|w| w == c))
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CONFIG;
+
+    fn problems(contents: &str) -> Vec<(usize, Severity, String, Option<String>)> {
+        check(contents, Path::new("/nonexistent"))
+            .into_iter()
+            .map(|p| (p.line, p.severity, p.message, p.suggestion))
+            .collect()
+    }
+
+    #[test]
+    fn the_default_config_has_no_problems() {
+        assert_eq!(problems(CONFIG), vec![]);
+    }
+
+    #[test]
+    fn every_keybinding_problem_is_reported_with_its_line() {
+        let yaml = "
+history_limit: 10
+keybindings:
+  Editr:
+    ctrl-q: Quitt
+  Editor:
+    ctl-s: Save
+    ctrl-s: Save
+    Ctrl-S: Save
+    ctrl-x: Quit
+    ctrl-X: Cut
+    pgup: Paste
+";
+        let error = Severity::Error;
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            problems(yaml),
+            vec![
+                (
+                    4,
+                    error,
+                    "unknown keybindings section `Editr`".into(),
+                    some("Editor")
+                ),
+                (5, error, "unknown action `Quitt`".into(), some("Quit")),
+                (
+                    7,
+                    error,
+                    "unknown modifier `ctl` in key `ctl-s`".into(),
+                    some("ctrl")
+                ),
+                (
+                    9,
+                    Severity::Warning,
+                    "`Ctrl-S` is bound to Save again, already done on line 8".into(),
+                    None
+                ),
+                (
+                    11,
+                    error,
+                    "`ctrl-X` is bound to both Quit, on line 10, and Cut".into(),
+                    None
+                ),
+                (12, error, "unknown key `pgup`".into(), some("pageup")),
+            ]
+        );
+    }
+
+    #[test]
+    fn settings_of_the_wrong_type_are_reported() {
+        let found = problems("history_limit: many\ntheme: gruvbx\nfile_selector:\n  sort_by: 1\n");
+        assert_eq!(found.len(), 3);
+        assert_eq!((found[0].0, found[0].1), (1, Severity::Error));
+        assert_eq!((found[1].0, found[1].1), (2, Severity::Warning));
+        assert_eq!(found[1].3.as_deref(), Some("gruvbox"));
+        assert_eq!(found[2].0, 4);
+    }
+
+    #[test]
+    fn animation_speeds_replaced_when_loading_are_reported() {
+        let found = problems("animations:\n  speed: .nan\n");
+        assert_eq!((found[0].0, found[0].1), (2, Severity::Warning));
+        let found = problems("animations: {speed: -1}\n");
+        assert_eq!((found[0].0, found[0].1), (1, Severity::Warning));
+        let found = problems("animations: {speed: 1e30}\n");
+        assert_eq!((found[0].0, found[0].1), (1, Severity::Warning));
+        assert!(problems("animations:\n  speed: 1.5\n").is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+use crate::action::{AsyncAction, AsyncActionSender};
+use crate::config::theme::THEMES_DIR_NAME;
+use crate::config::CONFIG_FILE_NAME;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::fs;
+use std::path::Path;
+
+/// Watches the config directory, sending `AsyncAction::ConfigChanged` whenever the config file
+/// or a theme is written, created or removed.
+///
+/// The directory is watched rather than the file, since editors often save by replacing the
+/// file. It is created if it does not exist yet, so the first config written is noticed. The
+/// watch stops when this is dropped.
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl ConfigWatcher {
+    pub fn new(config_dir: &Path, sender: AsyncActionSender) -> notify::Result<Self> {
+        fs::create_dir_all(config_dir).map_err(notify::Error::io)?;
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            let access = matches!(event.kind, EventKind::Access(_));
+            if !access && event.paths.iter().any(|path| is_config(path)) {
+                let _ = sender.send(AsyncAction::ConfigChanged);
+            }
+        })?;
+        watcher.watch(config_dir, RecursiveMode::Recursive)?;
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+fn is_config(path: &Path) -> bool {
+    let in_themes = path
+        .parent()
+        .and_then(Path::file_name)
+        .is_some_and(|dir| dir == THEMES_DIR_NAME);
+    let theme = in_themes && path.extension().is_some_and(|extension| extension == "yaml");
+    theme || path.file_name().is_some_and(|name| name == CONFIG_FILE_NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_config_file_and_themes_are_watched() {
+        let config_dir = Path::new("/home/user/.config/texti");
+        let themes = config_dir.join(THEMES_DIR_NAME);
+        assert!(is_config(&config_dir.join(CONFIG_FILE_NAME)));
+        assert!(is_config(&themes.join("dark.yaml")));
+        assert!(!is_config(&themes.join("dark.yaml~")));
+        assert!(!is_config(&config_dir.join("other.yaml")));
+        assert!(!is_config(&config_dir.join("nested").join("dark.yaml")));
+        assert!(!is_config(&config_dir.join(format!("{CONFIG_FILE_NAME}.swp"))));
+    }
+}
@@ -0,0 +1,101 @@
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, ScanError};
+
+/// A YAML node with the line it starts on, keeping only what is needed to check and edit the
+/// config file by its lines.
+pub(crate) struct Marked {
+    /// Line of the file, starting at 1
+    pub line: usize,
+    pub node: Node,
+}
+
+pub(crate) enum Node {
+    Scalar(String),
+    Map(Vec<(Marked, Marked)>),
+    /// A sequence or an alias
+    Other,
+}
+
+impl Marked {
+    /// Parses the first YAML document of `contents`, if there is one.
+    pub fn parse(contents: &str) -> Result<Option<Self>, ScanError> {
+        let mut builder = TreeBuilder::default();
+        Parser::new_from_str(contents).load(&mut builder, false)?;
+        Ok(builder.root)
+    }
+    pub fn scalar(&self) -> Option<&str> {
+        match &self.node {
+            Node::Scalar(value) => Some(value),
+            _ => None,
+        }
+    }
+    /// The entry of this map whose key is `key`.
+    pub fn get(&self, key: &str) -> Option<&(Marked, Marked)> {
+        match &self.node {
+            Node::Map(entries) => entries.iter().find(|(k, _)| k.scalar() == Some(key)),
+            _ => None,
+        }
+    }
+    /// Last line this node is written on, not counting the items of sequences.
+    pub fn last_line(&self) -> usize {
+        match &self.node {
+            Node::Map(entries) => entries
+                .iter()
+                .map(|(key, value)| key.last_line().max(value.last_line()))
+                .max()
+                .unwrap_or(self.line),
+            _ => self.line,
+        }
+    }
+}
+
+/// Whether a scalar stands for no value.
+pub(crate) fn is_null(value: &str) -> bool {
+    matches!(value, "" | "~" | "null")
+}
+
+/// Builds the tree of the first YAML document out of the parser events.
+#[derive(Default)]
+struct TreeBuilder {
+    /// Collections being read, with their line, whether they are a map, and their items
+    open: Vec<(usize, bool, Vec<Marked>)>,
+    root: Option<Marked>,
+}
+
+impl TreeBuilder {
+    fn push(&mut self, line: usize, node: Node) {
+        let marked = Marked { line, node };
+        match self.open.last_mut() {
+            Some((_, _, items)) => items.push(marked),
+            None => self.root = self.root.take().or(Some(marked)),
+        }
+    }
+}
+
+impl MarkedEventReceiver for TreeBuilder {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        match event {
+            Event::Scalar(value, ..) => self.push(mark.line(), Node::Scalar(value)),
+            Event::Alias(_) => self.push(mark.line(), Node::Other),
+            Event::MappingStart(..) => self.open.push((mark.line(), true, vec![])),
+            Event::SequenceStart(..) => self.open.push((mark.line(), false, vec![])),
+            Event::MappingEnd | Event::SequenceEnd => {
+                let Some((line, map, items)) = self.open.pop() else {
+                    return;
+                };
+                let node = if map {
+                    let mut items = items.into_iter();
+                    let mut entries = vec![];
+                    while let (Some(key), Some(value)) = (items.next(), items.next()) {
+                        entries.push((key, value));
+                    }
+                    Node::Map(entries)
+                } else {
+                    Node::Other
+                };
+                self.push(line, node);
+            }
+            _ => {}
+        }
+    }
+}
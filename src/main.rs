@@ -1,28 +1,63 @@
 use crate::app::App;
 use crate::cli::Cli;
+use crate::component::editor::batch::BatchRunner;
+use crate::config::validation::{has_errors, report};
+use crate::config::{get_config_file_dir, Config};
 use clap::Parser;
 use color_eyre::eyre::Result;
+use std::process::ExitCode;
 
 mod action;
 mod app;
+mod archive;
 mod cli;
 mod component;
+mod compression;
 mod config;
 mod errors;
 mod event;
+mod session;
+#[cfg(test)]
+mod test_util;
 mod tui;
 mod util;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     errors::init()?;
     let cli = Cli::parse();
-    match App::new_in_editor(cli.file_dir) {
-        Ok(mut app) => app.run().await?,
+    if cli.check_config {
+        return check_config();
+    }
+    if let Some(script) = cli.script.clone() {
+        let runner = BatchRunner::new(&script)?;
+        runner.run(cli.editor_options()?)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let session = cli.session.clone();
+    let editor_options = cli.editor_options()?;
+    match App::new_in_editor(editor_options, session) {
+        Ok(mut app) => app.run().await,
         Err(e) => {
             let msg = format!("Error creating application: {:?}", e);
             color_eyre::eyre::bail!(msg)
         }
     }
-    Ok(())
+}
+
+/// Prints the problems of the config file, failing when there is an error. Warnings alone
+/// leave a config that still loads.
+fn check_config() -> Result<ExitCode> {
+    let file = get_config_file_dir();
+    match Config::check()? {
+        None => println!("{} does not exist, the defaults are used", file.display()),
+        Some(problems) if problems.is_empty() => println!("{} has no problems", file.display()),
+        Some(problems) => {
+            println!("{}", report(&file, &problems));
+            if has_errors(&problems) {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
 }
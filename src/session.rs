@@ -0,0 +1,110 @@
+use crate::component::AppComponent;
+use color_eyre::eyre::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SESSIONS_DIR: &str = "sessions";
+const SESSION_EXTENSION: &str = "yaml";
+const LAST_SESSIONS_FILE_NAME: &str = "last_sessions.yaml";
+
+/// Where a buffer was left, restored once its file is loaded again.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Hash, Serialize)]
+pub struct BufferSession {
+    pub path: PathBuf,
+    /// Row and column of the cursor
+    pub cursor: (usize, usize),
+    /// First visible row
+    pub top_row: u16,
+    /// Search pattern highlighted in the buffer
+    pub search: Option<String>,
+}
+
+impl BufferSession {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            ..Default::default()
+        }
+    }
+}
+
+/// Open buffers and the screen shown when quitting, to be restored later.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Hash, Serialize)]
+pub struct Session {
+    /// Name the session is saved as, or `None` if it is only kept as the last session of its
+    /// working directory
+    #[serde(skip)]
+    pub name: Option<String>,
+    /// Screen that was shown
+    pub component: AppComponent,
+    pub buffers: Vec<BufferSession>,
+    /// Index of the buffer that was visible
+    pub current_buffer: usize,
+}
+
+impl Session {
+    pub fn new(component: AppComponent) -> Self {
+        Self {
+            component,
+            ..Default::default()
+        }
+    }
+    /// Loads the session saved as `name`, or `None` if there is no such session.
+    pub fn load(data_dir: &Path, name: &str) -> Result<Option<Self>> {
+        let file = Self::file(data_dir, name)?;
+        if !file.is_file() {
+            return Ok(None);
+        }
+        let mut session: Session = serde_yaml::from_str(&fs::read_to_string(file)?)?;
+        session.name = Some(name.to_string());
+        Ok(Some(session))
+    }
+    pub fn save(&self, data_dir: &Path, name: &str) -> Result<()> {
+        let file = Self::file(data_dir, name)?;
+        fs::create_dir_all(data_dir.join(SESSIONS_DIR))?;
+        fs::write(file, serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+    /// Names of every saved session, sorted.
+    pub fn names(data_dir: &Path) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(data_dir.join(SESSIONS_DIR)) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == SESSION_EXTENSION))
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+            .collect();
+        names.sort();
+        names
+    }
+    /// Loads the last session saved while working in `directory`.
+    pub fn load_last(data_dir: &Path, directory: &Path) -> Option<Self> {
+        Self::last_sessions(data_dir).remove(directory)
+    }
+    pub fn save_last(&self, data_dir: &Path, directory: &Path) -> Result<()> {
+        let mut sessions = Self::last_sessions(data_dir);
+        sessions.insert(directory.to_path_buf(), self.clone());
+        fs::create_dir_all(data_dir)?;
+        let file = data_dir.join(LAST_SESSIONS_FILE_NAME);
+        fs::write(file, serde_yaml::to_string(&sessions)?)?;
+        Ok(())
+    }
+    fn last_sessions(data_dir: &Path) -> HashMap<PathBuf, Session> {
+        let file = data_dir.join(LAST_SESSIONS_FILE_NAME);
+        fs::read_to_string(file)
+            .ok()
+            .and_then(|contents| serde_yaml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+    fn file(data_dir: &Path, name: &str) -> Result<PathBuf> {
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            bail!("Invalid session name `{name}`");
+        }
+        let file_name = format!("{name}.{SESSION_EXTENSION}");
+        Ok(data_dir.join(SESSIONS_DIR).join(file_name))
+    }
+}
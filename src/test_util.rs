@@ -0,0 +1,44 @@
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of temporary directories created so far, keeping their names unique between tests
+static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+/// An empty directory under the system temporary directory, removed with everything in it
+/// when dropped.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let count = CREATED.fetch_add(1, Ordering::Relaxed);
+        let dir_name = format!("texti-{name}-{}-{count}", process::id());
+        let dir = std::env::temp_dir().join(dir_name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+    /// Creates the directory holding the given empty files.
+    pub fn with_files(name: &str, files: &[&str]) -> Self {
+        let dir = Self::new(name);
+        for file in files {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        dir
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -11,7 +11,8 @@ use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
 use futures::{FutureExt, StreamExt};
 use ratatui::backend::CrosstermBackend as Backend;
 use ratatui::Terminal;
-use std::io::{stdout, Stdout};
+use std::fs::{File, OpenOptions};
+use std::io::{stdout, IsTerminal, Stdout, Write};
 use std::ops::{Deref, DerefMut};
 use std::time::Duration;
 use tokio::sync::mpsc;
@@ -26,8 +27,43 @@ type EventSender = UnboundedSender<Event>;
 const TICK_DURATION: Duration = Duration::from_millis(1000);
 const FRAME_DURATION: Duration = Duration::from_millis(33);
 
+/// Where the interface is drawn.
+///
+/// When stdout is redirected, for example when texti is in the middle of a pipeline, the
+/// interface is drawn to the controlling terminal so stdout is left for the edited text.
+pub enum TerminalOutput {
+    Stdout(Stdout),
+    Tty(File),
+}
+
+impl TerminalOutput {
+    pub fn new() -> Result<Self> {
+        let stdout = stdout();
+        if stdout.is_terminal() {
+            return Ok(Self::Stdout(stdout));
+        }
+        let tty = OpenOptions::new().write(true).open("/dev/tty")?;
+        Ok(Self::Tty(tty))
+    }
+}
+
+impl Write for TerminalOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            TerminalOutput::Stdout(stdout) => stdout.write(buf),
+            TerminalOutput::Tty(tty) => tty.write(buf),
+        }
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            TerminalOutput::Stdout(stdout) => stdout.flush(),
+            TerminalOutput::Tty(tty) => tty.flush(),
+        }
+    }
+}
+
 pub struct Tui {
-    pub terminal: Terminal<Backend<Stdout>>,
+    pub terminal: Terminal<Backend<TerminalOutput>>,
     pub event_loop_task: JoinHandle<()>,
     pub cancellation_token: CancellationToken,
     pub event_receiver: EventReceiver,
@@ -38,7 +74,7 @@ impl Tui {
     pub fn new() -> Result<Self> {
         let (event_tx, event_rx) = mpsc::unbounded_channel();
         Ok(Self {
-            terminal: Terminal::new(Backend::new(stdout()))?,
+            terminal: Terminal::new(Backend::new(TerminalOutput::new()?))?,
             event_loop_task: tokio::spawn(async {}),
             cancellation_token: CancellationToken::new(),
             event_sender: event_tx,
@@ -48,11 +84,11 @@ impl Tui {
 
     pub fn enter(&mut self) -> Result<()> {
         crossterm::terminal::enable_raw_mode()?;
-        let mut stdout = stdout();
-        crossterm::execute!(stdout, EnableBracketedPaste)?;
-        crossterm::execute!(stdout, EnterAlternateScreen, cursor::Hide)?;
+        let output = self.terminal.backend_mut();
+        crossterm::execute!(output, EnableBracketedPaste)?;
+        crossterm::execute!(output, EnterAlternateScreen, cursor::Hide)?;
         crossterm::execute!(
-            stdout,
+            output,
             PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
         )?;
         self.start_receiving_events();
@@ -65,9 +101,10 @@ impl Tui {
             self.terminal.flush()?;
             crossterm::terminal::disable_raw_mode()?
         }
-        crossterm::execute!(stdout(), DisableBracketedPaste)?;
-        crossterm::execute!(stdout(), LeaveAlternateScreen, cursor::Show)?;
-        crossterm::execute!(stdout(), PopKeyboardEnhancementFlags)?;
+        let output = self.terminal.backend_mut();
+        crossterm::execute!(output, DisableBracketedPaste)?;
+        crossterm::execute!(output, LeaveAlternateScreen, cursor::Show)?;
+        crossterm::execute!(output, PopKeyboardEnhancementFlags)?;
         Ok(())
     }
 
@@ -140,7 +177,7 @@ impl Tui {
 }
 
 impl Deref for Tui {
-    type Target = Terminal<Backend<Stdout>>;
+    type Target = Terminal<Backend<TerminalOutput>>;
 
     fn deref(&self) -> &Self::Target {
         &self.terminal
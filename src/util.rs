@@ -1,44 +1,263 @@
-use crate::action::AsyncAction;
-use color_eyre::eyre::{bail, Result};
-use std::path::Path;
+use crate::action::{AsyncAction, AsyncActionSender};
+use crate::archive::{read_entry, split_archive_path};
+use crate::compression::Codec;
+use ignore::WalkBuilder;
+use std::fs;
+use std::io;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
 use tokio::fs::File;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::sync::CancellationToken;
+
+/// Number of found files sent at once while walking a directory
+const FOUND_FILES_BATCH: usize = 1024;
 
 pub async fn read_dir(path: &Path) -> AsyncAction {
+    match read_file(path).await {
+        Ok((contents, codec)) => AsyncAction::LoadFileContents(path.to_path_buf(), contents, codec),
+        Err(err) => AsyncAction::FileError(path.to_path_buf(), format!("{:?}", err)),
+    }
+}
+
+/// Reads the file like [`read_text`], away from the async tasks.
+async fn read_file(path: &Path) -> io::Result<(String, Option<Codec>)> {
+    let path = path.to_path_buf();
+    let read = tokio::task::spawn_blocking(move || read_text(&path));
+    read.await.map_err(io::Error::other)?
+}
+
+/// Reads the text of a file, decompressing it if needed, along with the codec it was
+/// compressed with. Missing files and directories are read as empty, while files that are not
+/// UTF-8 text fail.
+pub fn read_text(path: &Path) -> io::Result<(String, Option<Codec>)> {
+    if let Some((archive, inner)) = split_archive_path(path) {
+        let contents = read_entry(archive, inner)?;
+        return Ok((utf8_text(path, contents)?, None));
+    }
     if !path.exists() || path.is_dir() {
-        return AsyncAction::LoadFileContents(String::new());
+        return Ok((String::new(), Codec::from_extension(path)));
     }
-    let res = tokio::fs::read(path).await;
-    match res {
+    let contents = fs::read(path)?;
+    let codec = Codec::detect(path, &contents);
+    let contents = match codec {
+        Some(codec) => codec.decompress(&contents)?,
+        None => contents,
+    };
+    Ok((utf8_text(path, contents)?, codec))
+}
+
+fn utf8_text(path: &Path, contents: Vec<u8>) -> io::Result<String> {
+    String::from_utf8(contents).map_err(|_| {
+        let message = format!("{} is not UTF-8 text", path.display());
+        io::Error::new(io::ErrorKind::InvalidData, message)
+    })
+}
+
+/// Reads what was appended to the file after its first `offset` bytes.
+///
+/// A character cut in half by a write still in progress is left out, along with the file
+/// length it was read up to, so it is read whole the next time.
+pub async fn read_appended(path: &Path, offset: u64) -> AsyncAction {
+    let read = async {
+        let mut file = File::open(path).await?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents).await?;
+        Ok::<_, std::io::Error>(contents)
+    };
+    match read.await {
         Ok(contents) => {
-            let string = String::from_utf8(contents).unwrap();
-            AsyncAction::LoadFileContents(string)
+            let complete = &contents[..complete_utf8_len(&contents)];
+            let length = offset + complete.len() as u64;
+            let string = String::from_utf8_lossy(complete).into_owned();
+            AsyncAction::AppendFileContents(path.to_path_buf(), length, string)
+        }
+        Err(err) => AsyncAction::FileError(path.to_path_buf(), format!("{:?}", err)),
+    }
+}
+
+/// Length of `bytes` without the start of a character cut at the end. Invalid bytes before
+/// it are kept, to be replaced when decoded.
+fn complete_utf8_len(bytes: &[u8]) -> usize {
+    let mut start = 0;
+    loop {
+        match std::str::from_utf8(&bytes[start..]) {
+            Ok(_) => return bytes.len(),
+            Err(e) => match e.error_len() {
+                Some(invalid) => start += e.valid_up_to() + invalid,
+                None => return start + e.valid_up_to(),
+            },
+        }
+    }
+}
+
+/// Walks the files under the directory in the background, sending them in batches as they are
+/// found, until `limit` files were found or the walk is cancelled.
+///
+/// Hidden files and the ones ignored by `.gitignore` or `.ignore` files are skipped.
+pub fn find_files(
+    dir: PathBuf,
+    limit: usize,
+    sender: AsyncActionSender,
+    cancellation_token: CancellationToken,
+) {
+    tokio::task::spawn_blocking(move || {
+        let mut batch = Vec::with_capacity(FOUND_FILES_BATCH);
+        let mut found = 0;
+        for entry in WalkBuilder::new(&dir).build().flatten() {
+            if cancellation_token.is_cancelled() {
+                return;
+            }
+            if found >= limit {
+                break;
+            }
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                continue;
+            }
+            batch.push(entry.into_path());
+            found += 1;
+            if batch.len() == FOUND_FILES_BATCH {
+                let files = std::mem::take(&mut batch);
+                if sender.send(AsyncAction::FoundFiles(dir.clone(), files)).is_err() {
+                    return;
+                }
+            }
+        }
+        if !batch.is_empty() {
+            let _ = sender.send(AsyncAction::FoundFiles(dir, batch));
+        }
+    });
+}
+
+/// A calendar date and time in UTC, converted from unix timestamps with std alone.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DateTime {
+    pub year: i64,
+    /// Month of the year, from 1 to 12
+    pub month: u32,
+    /// Day of the month, from 1
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl DateTime {
+    const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+    /// Days between 0000-03-01 and the unix epoch
+    const EPOCH_DAYS: i64 = 719_468;
+    /// Days in 400 years, after which the calendar repeats
+    const ERA_DAYS: i64 = 146_097;
+
+    /// Splits the seconds since the unix epoch into their calendar date and time.
+    pub fn from_timestamp(timestamp: i64) -> Self {
+        let days = timestamp.div_euclid(Self::SECONDS_PER_DAY);
+        let seconds = timestamp.rem_euclid(Self::SECONDS_PER_DAY) as u32;
+        // Years start in March here, so the leap day is the last day of the year
+        let days = days + Self::EPOCH_DAYS;
+        let era = days.div_euclid(Self::ERA_DAYS);
+        let day_of_era = days.rem_euclid(Self::ERA_DAYS);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Self {
+            year,
+            month,
+            day,
+            hour: seconds / 3600,
+            minute: seconds / 60 % 60,
+            second: seconds % 60,
         }
-        Err(err) => AsyncAction::Error(format!("{:?}", err)),
+    }
+    /// The seconds since the unix epoch at this date and time.
+    pub fn timestamp(&self) -> i64 {
+        let year = self.year - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let shifted_month = i64::from((self.month + 9) % 12);
+        let day_of_year = (153 * shifted_month + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = 365 * year_of_era + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * Self::ERA_DAYS + day_of_era - Self::EPOCH_DAYS;
+        let seconds = self.hour * 3600 + self.minute * 60 + self.second;
+        days * Self::SECONDS_PER_DAY + i64::from(seconds)
     }
 }
 
-pub async fn read_dir_limited(path: &Path, lines_limit: usize) -> Result<String> {
-    if lines_limit == 0 || !path.exists() || path.is_dir() {
-        bail!("Limit of {lines_limit} files is invalid");
+/// Whether the current user can write to the file or directory at `path`.
+///
+/// Paths that do not exist yet are writable if their closest existing parent is. Paths inside
+/// archives are never writable.
+pub fn is_writable(path: &Path) -> bool {
+    if split_archive_path(path).is_some() {
+        return false;
     }
-    let file = match File::open(path).await {
-        Ok(file) => file,
-        Err(err) => bail!(err),
+    let Some(existing) = path.ancestors().find(|p| p.exists()) else {
+        return false;
+    };
+    can_write(existing)
+}
+
+/// Asks the system whether the current user may write to `path`, owner and groups included.
+#[cfg(unix)]
+fn can_write(path: &Path) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    let Ok(path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
     };
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-    let mut count = lines_limit;
-    let mut string = String::new();
-    while count > 0
-        && let Ok(line) = lines.next_line().await
-    {
-        if let Some(line) = &line {
-            string += line
+    unsafe { libc::access(path.as_ptr(), libc::W_OK) == 0 }
+}
+
+/// Other platforms only tell whether directories are read-only, so files are opened to find out.
+#[cfg(not(unix))]
+fn can_write(path: &Path) -> bool {
+    use std::fs::{self, OpenOptions};
+    if path.is_file() {
+        return OpenOptions::new().write(true).open(path).is_ok();
+    }
+    fs::metadata(path).is_ok_and(|metadata| !metadata.permissions().readonly())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn characters_cut_at_the_end_are_left_out() {
+        let text = "añb".as_bytes();
+        assert_eq!(complete_utf8_len(text), text.len());
+        assert_eq!(complete_utf8_len(&text[..2]), 1);
+        assert_eq!(complete_utf8_len(b"a\xffb\xc3"), 3);
+    }
+
+    #[test]
+    fn timestamps_convert_to_dates() {
+        let date = |year, month, day, hour, minute, second| DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        };
+        let cases = [
+            (0, date(1970, 1, 1, 0, 0, 0)),
+            (-1, date(1969, 12, 31, 23, 59, 59)),
+            (951_827_696, date(2000, 2, 29, 12, 34, 56)),
+            (4_107_542_400, date(2100, 3, 1, 0, 0, 0)),
+        ];
+        for (timestamp, expected) in cases {
+            assert_eq!(DateTime::from_timestamp(timestamp), expected);
+            assert_eq!(expected.timestamp(), timestamp);
         }
-        count -= 1;
-        string += "\n";
     }
-    string.pop();
-    Ok(string)
 }